@@ -0,0 +1,128 @@
+"""Apply several constraints to the same generation, allowing only tokens every one allows."""
+
+from __future__ import annotations
+
+from collections.abc import Iterable
+from typing import Any, Protocol
+
+from transformers.tokenization_utils_base import PreTrainedTokenizerBase
+from transformers.tokenization_utils_fast import PreTrainedTokenizerFast
+
+from pse.util.mask_logits import mask_logits_from_ids
+from pse.util.vocabulary import build_vocabulary, get_decode
+
+
+class Constraint(Protocol):
+    """
+    Anything that masks logits and follows the generated tokens, e.g. a StructuringEngine.
+    """
+
+    def process_logits(self, input_ids: Any, logits: Any) -> Any: ...
+
+    def consume(self, token_id: int) -> Any: ...
+
+    def consume_text(self, text: str) -> Any: ...
+
+
+class CompositeEngine:
+    """
+    Holds several constraints over one generation and masks logits by all of them.
+
+    A token survives only if every constraint allows it. Constraints that only
+    forbid token ids, such as `ForbiddenWordsConstraint`, are combined into one
+    set and masked in a single pass; every other constraint masks the output of
+    the previous one.
+    """
+
+    def __init__(self, constraints: Iterable[Constraint]) -> None:
+        self.constraints: list[Constraint] = list(constraints)
+        if not self.constraints:
+            raise ValueError("At least one constraint is required.")
+
+    def process_logits(self, input_ids: Any, logits: Any) -> Any:
+        forbidden_token_ids: set[int] = set()
+        for constraint in self.constraints:
+            if isinstance(constraint, ForbiddenWordsConstraint):
+                forbidden_token_ids |= constraint.get_forbidden_token_ids()
+            else:
+                logits = constraint.process_logits(input_ids, logits)
+        if forbidden_token_ids:
+            logits = mask_logits_from_ids(logits, forbidden_token_ids)
+        return logits
+
+    def consume(self, token_id: int) -> None:
+        for constraint in self.constraints:
+            constraint.consume(token_id)
+
+    def consume_text(self, text: str) -> None:
+        for constraint in self.constraints:
+            constraint.consume_text(text)
+
+    @property
+    def has_reached_accept_state(self) -> bool:
+        """
+        Whether every constraint that has an accept state has reached it.
+        """
+        return all(
+            getattr(constraint, "has_reached_accept_state", True)
+            for constraint in self.constraints
+        )
+
+
+class ForbiddenWordsConstraint:
+    """
+    Masks any token that would complete one of the forbidden words.
+
+    Words are matched case-sensitively against the generated text, including
+    words that span several tokens.
+    """
+
+    def __init__(
+        self,
+        tokenizer: PreTrainedTokenizerFast | PreTrainedTokenizerBase,
+        words: Iterable[str],
+    ) -> None:
+        self.words: list[str] = [word for word in words if word]
+        if not self.words:
+            raise ValueError("At least one forbidden word is required.")
+
+        self.decode = get_decode(tokenizer)
+        self.token_texts: dict[int, str] = build_vocabulary(tokenizer.get_vocab(), self.decode)
+        # tokens containing a whole word are forbidden whatever precedes them
+        self._always_forbidden: set[int] = {
+            token_id
+            for token_id, text in self.token_texts.items()
+            if any(word in text for word in self.words)
+        }
+        # the tokens starting with each word suffix, by suffix
+        self._completion_token_ids: dict[str, set[int]] = {
+            word[split:]: set() for word in self.words for split in range(1, len(word))
+        }
+        for token_id, text in self.token_texts.items():
+            for completion, token_ids in self._completion_token_ids.items():
+                if text.startswith(completion):
+                    token_ids.add(token_id)
+        self._tail_length = max(len(word) for word in self.words) - 1
+        self.text = ""
+
+    def process_logits(self, _: Any, logits: Any) -> Any:
+        return mask_logits_from_ids(logits, self.get_forbidden_token_ids())
+
+    def get_forbidden_token_ids(self) -> set[int]:
+        """
+        The token ids that would complete a forbidden word after the text so far.
+        """
+        forbidden = set(self._always_forbidden)
+        # word suffixes that would complete a word begun at the end of the text
+        for word in self.words:
+            for split in range(1, len(word)):
+                if self.text.endswith(word[:split]):
+                    forbidden |= self._completion_token_ids[word[split:]]
+        return forbidden
+
+    def consume(self, token_id: int) -> None:
+        self.consume_text(self.token_texts.get(token_id) or self.decode([token_id]))
+
+    def consume_text(self, text: str) -> None:
+        # only the end of the text can begin a word
+        self.text = (self.text + text)[-self._tail_length :] if self._tail_length else ""
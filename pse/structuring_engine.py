@@ -1,11 +1,18 @@
 from __future__ import annotations
 
+import bisect
+import copy
+import heapq
 import json
 import logging
+import os
 import time
-from collections.abc import Callable, Iterator
+from collections.abc import Callable, Iterable, Iterator
+from dataclasses import dataclass, field
+from enum import Enum
 from typing import Any, TypeVar
 
+import numpy as np
 from pse_core.engine import Engine
 from pse_core.state_machine import StateMachine
 from pse_core.stepper import Stepper
@@ -13,8 +20,36 @@ from pydantic import BaseModel
 from transformers.tokenization_utils_base import PreTrainedTokenizerBase
 from transformers.tokenization_utils_fast import PreTrainedTokenizerFast
 
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.loop import LoopStateMachine
+from pse.types.base.token_id import TokenIdStepper
+from pse.types.enum import EnumStateMachine
 from pse.types.json import JSONSchemaSource, json_schema_state_machine
+from pse.types.json.json_array import ArrayStateMachine, ArrayStepper
+from pse.types.json.json_object import ObjectSchemaStateMachine
+from pse.types.json.schema_cache import SchemaCache
+from pse.types.key_value import KeyValueStateMachine
+from pse.types.object import ObjectStepper
+from pse.types.object import ObjectStateMachine
+from pse.types.regex import RegexSetStateMachine, RegexSetStepper, RegexStateMachine
+from pse.types.string import StringStepper
+from pse.types.whitespace import WhitespaceStateMachine
+from pse.util.costs import path_cost
+from pse.util.debug_log import summarize_steppers
+from pse.util.errors import PseAmbiguityError
 from pse.util.get_top_logits import get_top_k
+from pse.util.json_events import JsonEvent, JsonEventParser
+from pse.util.json_patch import JsonPatchOp, diff_json
+from pse.util.mask_logits import (
+    bias_logits,
+    mask_logits_from_ids,
+    mask_logits_to_ids,
+    unmask_logits,
+)
+from pse.util.metrics import EngineMetrics
+from pse.util.names import describe, get_name
+from pse.util.sample_accepting import sample_accepting
+from pse.util.vocabulary import get_decode
 
 logger = logging.getLogger(__name__)
 
@@ -22,6 +57,172 @@ Array_Type = TypeVar("Array_Type", bound=Any)
 OutputType = TypeVar("OutputType")
 
 
+ENGINE_STATE_VERSION = 2
+# bounds the search for the shortest completion of deeply nested structures
+MAX_COMPLETION_SEARCH = 10_000
+# the most output tokens `budget_aware_closure` reserves for closing the structure
+MAX_CLOSURE_TOKENS = 64
+# what a tokenizer decodes an incomplete UTF-8 sequence to
+PARTIAL_CHARACTER = "\ufffd"
+MAX_UTF8_BYTES = 4
+
+
+class EngineMode(Enum):
+    """
+    How much freedom the engine leaves to the model.
+
+    DEFAULT: the model is consulted for every token, and optional whitespace is allowed.
+    STRICT: schemas compile without optional whitespace, forced continuations are
+        emitted without consulting the model, and dead steppers are pruned eagerly.
+    """
+
+    DEFAULT = "default"
+    STRICT = "strict"
+
+
+class CompletionPreference(Enum):
+    """
+    Which completions the engine nudges the model towards when the
+    structure could be closed now or continued.
+
+    NONE: no bias.
+    SHORTER: favor tokens that complete the structure.
+    LONGER: favor tokens that keep the structure open.
+    """
+
+    NONE = "none"
+    SHORTER = "shorter"
+    LONGER = "longer"
+
+
+@dataclass
+class ReplayReport:
+    """
+    The outcome of replaying a sequence of token ids through the engine.
+
+    Attributes:
+        consumed_token_ids: The token ids that were consumed before getting stuck.
+        stuck_index: Index of the first token id that could not be consumed, if any.
+        stuck_token: The decoded text of the token id at `stuck_index`.
+        expected: The valid continuations at the point the replay got stuck.
+        raw_output: The raw output of the engine when the replay stopped.
+        has_reached_accept_state: Whether the engine accepted the replayed sequence.
+    """
+
+    consumed_token_ids: list[int] = field(default_factory=list)
+    stuck_index: int | None = None
+    stuck_token: str | None = None
+    expected: list[str] = field(default_factory=list)
+    raw_output: str = ""
+    has_reached_accept_state: bool = False
+
+    @property
+    def is_stuck(self) -> bool:
+        return self.stuck_index is not None
+
+
+class ValidationStatus(Enum):
+    """
+    How much of a message the configured structure accepts.
+
+    VALID: the whole message is accepted.
+    PARTIAL: a non-empty prefix is consumed, but the message
+        breaks after it or ends before the structure is complete.
+    INVALID: not even the first character is accepted.
+    """
+
+    VALID = "valid"
+    PARTIAL = "partial"
+    INVALID = "invalid"
+
+
+@dataclass
+class ValidationResult:
+    """
+    The outcome of validating a complete message against the configured structure.
+
+    Attributes:
+        status: Whether the message is valid, partially valid or invalid.
+        valid_prefix: The longest prefix of the message the structure consumes.
+        error_index: Index of the first character that could not be consumed,
+            or None if the whole message was consumed.
+        error_token_index: Index of the token holding that character, in the
+            message's tokenization, or None if the whole message was consumed.
+        expected: The valid continuations where validation stopped, if not valid.
+    """
+
+    status: ValidationStatus
+    valid_prefix: str = ""
+    error_index: int | None = None
+    error_token_index: int | None = None
+    expected: list[str] = field(default_factory=list)
+
+    @property
+    def is_valid(self) -> bool:
+        return self.status == ValidationStatus.VALID
+
+
+@dataclass
+class AmbiguityReport:
+    """
+    How many steppers a structure keeps alive while sample outputs are consumed.
+
+    Attributes:
+        sample_count: The number of sample outputs replayed.
+        token_count: The number of tokens consumed across every sample.
+        max_steppers: The most steppers alive after any single token.
+        avg_steppers: The average number of steppers alive after each token.
+    """
+
+    sample_count: int = 0
+    token_count: int = 0
+    max_steppers: int = 0
+    avg_steppers: float = 0.0
+
+
+@dataclass
+class Expectation:
+    """
+    What one kind of live stepper expects next, for machine-readable error messages.
+
+    Attributes:
+        label: The type of the innermost state machine doing the expecting, e.g. "Integer",
+            qualified by the name of its closest named ancestor, e.g. "Integer[age]".
+        examples: Example strings that would be valid next.
+    """
+
+    label: str
+    examples: list[str] = field(default_factory=list)
+
+
+class StructuralHintKind(Enum):
+    """
+    The kind of structure the engine expects next.
+    """
+
+    KEY = "key"
+    KEY_SEPARATOR = "key_separator"
+    VALUE = "value"
+    ITEM_SEPARATOR = "item_separator"
+    OBJECT_CLOSE = "object_close"
+    ARRAY_CLOSE = "array_close"
+
+
+@dataclass
+class StructuralHint:
+    """
+    Where the output stands in the structure, for client UIs.
+
+    Attributes:
+        kind: What is expected next, e.g. a key or a value.
+        value_type: For values, the type of the state machine that will
+            accept the value, e.g. "JSONNumber"; otherwise None.
+    """
+
+    kind: StructuralHintKind
+    value_type: str | None = None
+
+
 class StructuringEngine(Engine):
     """
     The types of objects that the engine can use as a schema.
@@ -33,40 +234,338 @@ class StructuringEngine(Engine):
         whitelist_control_tokens: list[str] | None = None,
         multi_token_sampling: bool = False,
         max_resample_attempts: int = 5,
+        mode: EngineMode = EngineMode.DEFAULT,
+        schema_cache: SchemaCache | None = None,
+        leading_markers: list[str] | None = None,
+        completion_preference: CompletionPreference = CompletionPreference.NONE,
+        completion_bias: float = 1.0,
+        emit_defaults: bool = False,
+        max_output_tokens: int | None = None,
+        budget_aware_closure: bool = False,
+        no_backtrack: bool = False,
+        repetition_bias: float = 1.0,
     ) -> None:
         """
         Initialize the StructuringEngine with a tokenizer and vocabulary.
+
+        A `schema_cache` can be shared between engines to reuse compiled schemas.
+        `leading_markers` (e.g. a BOM or a model-specific marker) are accepted
+        before the structure begins and left out of the structured output.
+        `completion_preference` adds `completion_bias` to (or subtracts it from)
+        the logits of tokens that would complete the structure.
+        With `emit_defaults`, required properties with a schema `default` are
+        filled in automatically when the model closes their object early.
+        With `budget_aware_closure`, once the tokens left in `max_output_tokens`
+        only just cover closing the structure, only tokens on the shortest
+        completion are allowed, so the output ends well-formed instead of cut off.
+        At most `MAX_CLOSURE_TOKENS` are reserved for closing.
+        `no_backtrack` is an assertion mode for structures meant to be unambiguous:
+        stepping is unchanged, but `PseAmbiguityError` is raised as soon as more
+        than one stepper is alive. It catches ambiguity; it does not make stepping faster.
+        Where a loop built with `greedy` could either repeat or stop, tokens that
+        repeat it get `repetition_bias` added (greedy) or subtracted (lazy).
         """
         self.tokenizer = tokenizer
+        self.decode = get_decode(tokenizer)
+        self.mode = mode
+        self.completion_preference = completion_preference
+        self.completion_bias = completion_bias
+        self.repetition_bias = repetition_bias
+        self.emit_defaults = emit_defaults
+        self.injected_token_ids: list[int] = []
+        # everything consumed this generation, as runs of token ids or of text
+        self._consumed_segments: list[list[int] | str] = []
+        # closing tokens unmasked by the last `process_logits`, for `sample` to fill before
+        self._default_fill_token_ids: set[int] = set()
+        self.max_output_tokens = max_output_tokens
+        self.budget_aware_closure = budget_aware_closure
+        self.output_token_count = 0
+        self.no_backtrack = no_backtrack
+        self.event_parser: JsonEventParser | None = None
+        # the accepted text already fed to the event parser
+        self._event_text = ""
+        self.accepts_empty = False
+        self.fallback_state_machine: StateMachine | None = None
+        self.fallback_steppers: list[Stepper] = []
+        self._configured_state_machines: tuple[StateMachine | None, StateMachine | None] = (
+            None,
+            None,
+        )
+        self.reset_count = 0
+        self.pending_token_ids: list[int] = []
+        self._byte_token_ids: set[int] | None = None
+        self._text_to_token_ids: dict[str, list[int]] | None = None
+        self._sorted_token_texts: list[str] | None = None
+        # the steppers `get_terminal_token_ids` last ran on, and its result
+        self._terminal_token_ids_cache: tuple[list[Stepper], set[int]] = ([], set())
+        # likewise for `get_repetition_token_ids`
+        self._repetition_token_ids_cache: tuple[
+            list[Stepper], tuple[set[int], set[int]]
+        ] = ([], (set(), set()))
+        self.constrained = True
+        self.passthrough_buffer = ""
+        self.forbidden_chars: frozenset[str] = frozenset()
+        self._forbidden_token_ids: set[int] = set()
+        self._patched_value: Any = None
+        self.metrics = EngineMetrics()
+        self._generation_finished = False
+        self.schema_cache = schema_cache
+        self.leading_marker_state_machine: StateMachine | None = None
+        if leading_markers:
+            self.leading_marker_state_machine = LoopStateMachine(
+                EnumStateMachine(leading_markers, require_quotes=False),
+                min_loop_count=0,
+                max_loop_count=len(leading_markers),
+            )
         self.control_tokens = self.build_control_tokens(whitelist_control_tokens)
         super().__init__(
             tokenizer.get_vocab(),
             lambda x: tokenizer.encode(x, add_special_tokens=False),
-            lambda x: tokenizer.decode(x),
+            self.decode,
             multi_token_sampling=multi_token_sampling,
             control_tokens=self.control_tokens,
             max_resamples=max_resample_attempts,
         )
 
+    @classmethod
+    def from_regex(
+        cls,
+        pattern: str,
+        tokenizer: PreTrainedTokenizerFast | PreTrainedTokenizerBase,
+        **kwargs: Any,
+    ) -> StructuringEngine:
+        """
+        Build an engine whose output must fully match a regular expression.
+
+        Any other keyword arguments are passed on to the constructor.
+
+        Raises:
+            ValueError: If the pattern is not a valid regular expression.
+        """
+        engine = cls(tokenizer, **kwargs)
+        engine.configure(RegexStateMachine(pattern))
+        return engine
+
+    @classmethod
+    def from_regex_set(
+        cls,
+        patterns: list[str],
+        tokenizer: PreTrainedTokenizerFast | PreTrainedTokenizerBase,
+        **kwargs: Any,
+    ) -> StructuringEngine:
+        """
+        Build an engine whose output must fully match any one of several regular expressions.
+
+        Once accepted, `matched_pattern_index` reports which pattern matched.
+        Any other keyword arguments are passed on to the constructor.
+
+        Raises:
+            ValueError: If no patterns are given or any is not a valid regular expression.
+        """
+        engine = cls(tokenizer, **kwargs)
+        engine.configure(RegexSetStateMachine(patterns))
+        return engine
+
+    def matched_pattern_index(self) -> int | None:
+        """
+        The index of the pattern an accepted regex-set output matched, or None.
+        """
+        for stepper in self.steppers:
+            if not stepper.has_reached_accept_state() or stepper.remaining_input:
+                continue
+            current: Stepper | None = stepper
+            while current is not None:
+                if isinstance(current, RegexSetStepper):
+                    return current.get_matched_pattern_index()
+                current = current.sub_stepper
+        return None
+
     def configure(
         self,
         structure: JSONSchemaSource | StateMachine,
+        accept_empty: bool = False,
         **kwargs: Any,
     ) -> None:
         """
         Configure the structuring engine with a schema.
+
+        Args:
+            structure: The schema or state machine to constrain the output to.
+            accept_empty: Whether an empty output is valid, allowing EOS before any
+                token. Implied when the root state machine is optional.
+        """
+        self.state_machine = self._build_state_machine(structure, **kwargs)
+        self.fallback_state_machine = None
+        self.fallback_steppers = []
+        self._configured_state_machines = (self.state_machine, None)
+        self.reset_count = 0
+
+        # a root has no parent to skip over it, so optionality is handled here
+        self.accepts_empty = accept_empty or self.state_machine.is_optional
+        self._start_generation()
+
+    def reset(self, hard_reset: bool = False) -> None:
+        """
+        Restart generation from the start of the configured structure.
+
+        Nothing is recompiled and the vocabulary is kept, so this is cheap
+        enough to call before every retry in an agent loop. A fallback set up
+        with `with_fallback` is restored too. `reset_count` counts the resets
+        since the structure was configured, for enforcing a retry budget.
+
+        Args:
+            hard_reset: Also drop the configured structure, as the base engine does;
+                the engine must be configured again before use.
+        """
+        if hard_reset:
+            super().reset(hard_reset)
+            self._configured_state_machines = (None, None)
+            self.fallback_state_machine = None
+            self.fallback_steppers = []
+            self.reset_count = 0
+            return
+
+        self._rewind()
+        self.reset_count += 1
+
+    def _rewind(self) -> None:
+        """
+        Go back to the start of the configured structure, and of its fallback.
+        """
+        primary, fallback = self._configured_state_machines
+        if primary is None:
+            raise RuntimeError("Configure the engine before resetting it.")
+        self.state_machine = primary
+        super().reset(False)
+        self.fallback_state_machine = fallback
+        self.fallback_steppers = fallback.get_steppers() if fallback else []
+        self._start_generation()
+
+    def _start_generation(self) -> None:
+        """
+        Put the steppers and per-generation counters back at the start.
+        """
+        self.steppers = self.state_machine.get_steppers()
+        self._generation_finished = False
+        self.injected_token_ids = []
+        self._consumed_segments = []
+        self.output_token_count = 0
+        self.pending_token_ids = []
+        self.constrained = True
+        self.passthrough_buffer = ""
+        # patches are reported against the new generation, starting from `null`
+        self._patched_value = None
+        self._event_text = ""
+        if self.event_parser is not None:
+            self.event_parser.reset()
+        self._assert_unambiguous()
+
+    def with_fallback(
+        self,
+        primary: JSONSchemaSource | StateMachine,
+        fallback: JSONSchemaSource | StateMachine,
+        **kwargs: Any,
+    ) -> None:
+        """
+        Configure a strict primary structure with a looser fallback.
+
+        The output is constrained by the primary. The fallback follows along on
+        everything consumed, and if the primary dies (e.g. a consumed token
+        diverges from it) the engine continues on the fallback, keeping the
+        output so far.
+
+        Args:
+            primary: The structure to constrain the output to while it is alive.
+            fallback: The structure to continue on once the primary dies.
+            **kwargs: Passed on to `configure`.
+        """
+        self.configure(primary, **kwargs)
+        kwargs.pop("accept_empty", None)
+        self.fallback_state_machine = self._build_state_machine(fallback, **kwargs)
+        self.fallback_steppers = self.fallback_state_machine.get_steppers()
+        self._configured_state_machines = (self.state_machine, self.fallback_state_machine)
+
+    def _build_state_machine(
+        self, structure: JSONSchemaSource | StateMachine, **kwargs: Any
+    ) -> StateMachine:
+        """
+        Compile a structure with the engine's options, behind any leading markers.
         """
         if isinstance(structure, StateMachine):
-            self.state_machine = structure
+            state_machine = structure
         else:
-            _, self.state_machine = json_schema_state_machine(structure, **kwargs)
+            if self.mode == EngineMode.STRICT:
+                kwargs.setdefault("max_whitespace", 0)
+            if self.emit_defaults:
+                kwargs.setdefault("emit_defaults", True)
+            if self.schema_cache is not None:
+                _, state_machine = self.schema_cache.get_or_compile(structure, **kwargs)
+            else:
+                _, state_machine = json_schema_state_machine(structure, **kwargs)
 
-        self.steppers = self.state_machine.get_steppers()
+        if self.leading_marker_state_machine is not None:
+            state_machine = ChainStateMachine(
+                [self.leading_marker_state_machine, state_machine]
+            )
+        return state_machine
+
+    def _advance_fallback(self, consumed: str | list[int], primary_died: bool) -> bool:
+        """
+        Advance the fallback steppers, switching over to them if the primary died.
+
+        Returns:
+            Whether the engine switched to the fallback.
+        """
+        if self.fallback_state_machine is None:
+            return False
+
+        if text := self._decode_consumed(consumed):
+            self.fallback_steppers = [
+                stepper
+                for stepper in self.fallback_state_machine.advance_all_basic(
+                    self.fallback_steppers, text
+                )
+                if not stepper.remaining_input
+            ]
+        if not primary_died:
+            if not self.fallback_steppers:
+                # the fallback died first; the primary is on its own
+                self.fallback_state_machine = None
+            return False
+
+        if not self.fallback_steppers:
+            self.fallback_state_machine = None
+            return False
+
+        logger.debug("Primary structure died, continuing on the fallback")
+        self.state_machine = self.fallback_state_machine
+        self.steppers = self.fallback_steppers
+        self.fallback_state_machine = None
+        self.fallback_steppers = []
+        return True
 
     def process_logits(self, _: Any, raw_logits: Array_Type) -> Array_Type:
         """
         Process the logits and return the processed logits.
         """
+        if not self.constrained:
+            if self._forbidden_token_ids:
+                return mask_logits_from_ids(raw_logits, self._forbidden_token_ids)
+            return raw_logits
+
+        if not self.steppers:
+            # the primary died without consuming anything; mask by the fallback
+            self._advance_fallback([], primary_died=True)
+
+        if self.pending_token_ids:
+            # only bytes that continue the pending character, into one the structure allows
+            adjusted_logits = mask_logits_to_ids(raw_logits, self.get_pending_token_ids())
+            if self._forbidden_token_ids:
+                adjusted_logits = mask_logits_from_ids(
+                    adjusted_logits, self._forbidden_token_ids
+                )
+            return adjusted_logits
+
         tic = time.perf_counter()
         self.multi_token_mapping: dict[int, list[int]] = {}
         # move logits to cpu if they aren't already on cpu
@@ -77,7 +576,43 @@ class StructuringEngine(Engine):
 
         # process logits
         self.print_top_logits(raw_logits, 5, "Before 🟡")
-        adjusted_logits = self.mask_invalid_tokens(raw_logits)
+        if (allowed_token_ids := self.get_allowed_token_ids()) is not None:
+            adjusted_logits = mask_logits_to_ids(raw_logits, allowed_token_ids)
+        else:
+            adjusted_logits = self.mask_invalid_tokens(raw_logits)
+        if self._forbidden_token_ids:
+            adjusted_logits = mask_logits_from_ids(
+                adjusted_logits, self._forbidden_token_ids
+            )
+        self._default_fill_token_ids = set()
+        if self.emit_defaults and (fill_token_ids := self.get_default_fill_token_ids()):
+            # let the model close early; the defaults are filled in when it does
+            adjusted_logits = unmask_logits(adjusted_logits, raw_logits, fill_token_ids)
+            self._default_fill_token_ids = fill_token_ids
+        if self._is_empty_accepted() and self.tokenizer.eos_token_id is not None:
+            adjusted_logits = unmask_logits(
+                adjusted_logits, raw_logits, [self.tokenizer.eos_token_id]
+            )
+        if (closure_token_ids := self.get_closure_token_ids()) is not None:
+            adjusted_logits = mask_logits_to_ids(adjusted_logits, closure_token_ids)
+        if (
+            self.completion_preference != CompletionPreference.NONE
+            and self.completion_bias
+        ):
+            if terminal_token_ids := self.get_terminal_token_ids():
+                bias = self.completion_bias
+                if self.completion_preference == CompletionPreference.LONGER:
+                    bias = -bias
+                adjusted_logits = bias_logits(adjusted_logits, terminal_token_ids, bias)
+        greedy_token_ids, lazy_token_ids = self.get_repetition_token_ids()
+        if greedy_token_ids:
+            adjusted_logits = bias_logits(
+                adjusted_logits, greedy_token_ids, self.repetition_bias
+            )
+        if lazy_token_ids:
+            adjusted_logits = bias_logits(
+                adjusted_logits, lazy_token_ids, -self.repetition_bias
+            )
         self.print_top_logits(adjusted_logits, 5, "After 🟢")
         # move logits back to original device if they didn't start on cpu
         if original_device:
@@ -103,6 +638,14 @@ class StructuringEngine(Engine):
         Note:
             Parent class expects single-batch input of shape (1, sequence_length)
         """
+        if not self.constrained:
+            sampled = sampler(logprobs)
+            token_ids = sampled.tolist()
+            if token_ids and isinstance(token_ids[0], list):
+                token_ids = token_ids[0]
+            self.passthrough_buffer += self.decode(token_ids)
+            return sampled
+
         tic = time.perf_counter()
         # move logits to cpu if they aren't already on cpu
         original_device = None
@@ -110,12 +653,29 @@ class StructuringEngine(Engine):
             original_device = logprobs.device.type
             logprobs = logprobs.cpu()
 
-        # Process each batch individually
-        samples = [
-            self.select_next_tokens(batch[None], sampler)
-            for batch in logprobs
-            if batch is not None and batch.ndim == 1
-        ]
+        samples = None
+        if self.emit_defaults and len(logprobs) == 1:
+            samples = self._sample_with_default_fill(logprobs, sampler)
+        if samples is None:
+            # Process each batch individually
+            samples = [
+                self.select_next_tokens(batch[None], sampler)
+                for batch in logprobs
+                if batch is not None and batch.ndim == 1
+            ]
+            if len(samples) == 1:
+                self._record_consumed(samples[0])
+        if len(samples) == 1:
+            self._advance_fallback(samples[0], primary_died=not self.steppers)
+            self._emit_events()
+        if self.mode == EngineMode.STRICT and len(samples) == 1:
+            self.prune_dead_steppers()
+            samples[0] = [*samples[0], *self.emit_forced_tokens()]
+        for sample in samples:
+            self.output_token_count += len(sample)
+            self.metrics.record_step(len(sample), len(self.steppers))
+            self._record_generation_outcome(stuck=not sample)
+
         # Unwrap single batch
         sampled_token_id = samples[0] if len(samples) == 1 else samples
         result = type(logprobs)(sampled_token_id)
@@ -127,53 +687,1425 @@ class StructuringEngine(Engine):
         logger.debug(f"Sampling completed in {toc - tic:.4f}s: \033[33m{result}\033[0m")
         return result
 
-    def get_structured_output(
-        self,
-        output_type: type[OutputType] | None = None,
-        raise_on_error: bool = False,
-    ) -> OutputType | Any:
+    def forbid_chars(self, chars: Iterable[str]) -> None:
         """
-        Parse and cast the output to the given type.
+        Forbid characters anywhere in the output, on top of the structure.
+
+        Every token whose text contains a forbidden character is masked out,
+        whether or not generation is currently constrained.
+        The end-of-sequence token is never masked.
+        Pass an empty iterable to lift the restriction.
+
+        Args:
+            chars: The forbidden characters, e.g. `{"\\n", "\\r"}` for single-line output.
         """
-        for stepper in self.steppers:
-            for _, token_safe_output in self._iter_state_and_output(
-                stepper,
-                output_type,
-                raise_on_error,
-            ):
-                return token_safe_output
+        self.forbidden_chars = frozenset("".join(chars))
+        self._forbidden_token_ids = {
+            token_id
+            for token_id, text in self.reverse_vocabulary.items()
+            if token_id != self.tokenizer.eos_token_id
+            and not self.forbidden_chars.isdisjoint(text)
+        }
 
-    def get_labeled_output(
-        self,
-        output_type: type[OutputType] | None = None,
-        raise_on_error: bool = False,
-    ) -> Iterator[tuple[str, OutputType | Any]]:
+    def _sample_with_default_fill(
+        self, logprobs: Array_Type, sampler: Callable[..., Array_Type]
+    ) -> list[list[int]] | None:
         """
-        Get each part of the output labeled with the identifier of the step that produced it.
+        Sample a single batch while a default fill is possible.
+
+        Returns None to fall back to normal selection when no fill applies
+        or the sampled token cannot be consumed; the steppers are then left
+        as they were.
         """
-        for stepper in self.steppers:
-            yield from self._iter_state_and_output(
-                stepper,
-                output_type,
-                raise_on_error,
+        if not self._default_fill_token_ids:
+            return None
+
+        sampled_token_ids = sampler(logprobs).tolist()
+        if sampled_token_ids and isinstance(sampled_token_ids[0], list):
+            sampled_token_ids = sampled_token_ids[0]
+        if not sampled_token_ids:
+            return None
+
+        token_id = sampled_token_ids[0]
+        fill_token_ids: list[int] = []
+        fill = ""
+        if token_id in self._default_fill_token_ids:
+            # only worked out for a closing token that was actually sampled
+            fill_token_ids, fill = self._tokenize_fill(
+                self.get_default_fill(self.decode([token_id]))
             )
 
-    def _iter_state_and_output(
-        self,
-        stepper: Stepper,
-        output_type: type[OutputType] | None,
-        raise_on_error: bool,
-    ) -> Iterator[tuple[str, OutputType | Any]]:
+        steppers = self.steppers
+        if fill:
+            super().consume_text(fill)
+        consumed_token_id = super().consume(token_id) if self.steppers else None
+        if consumed_token_id is None:
+            # the fill is only kept together with the token that needed it
+            self.steppers = steppers
+            return None
+
+        if fill:
+            self._record_consumed(fill)
+            logger.debug(f"Filled in defaults: {fill!r}")
+        self._record_consumed([consumed_token_id])
+        return [[*fill_token_ids, consumed_token_id]]
+
+    def set_constrained(self, constrained: bool) -> None:
         """
-        Helper method to parse and yield structured output from a stepper.
+        Pause or resume constrained generation.
+
+        While unconstrained, logits pass through unmasked and consumed text is
+        buffered instead of advancing the steppers. When constraints resume, the
+        buffer is replayed: if the steppers can consume all of it they advance,
+        otherwise it is treated as a free-form region and the structure resumes
+        from where it was paused.
+
+        Args:
+            constrained: Whether the structure should be enforced.
         """
-        for final_stepper in stepper.get_final_state():
-            identifier = final_stepper.get_identifier() or str(
-                final_stepper.current_state
+        if constrained and not self.constrained and self.passthrough_buffer:
+            resynced_steppers = [
+                stepper
+                for stepper in self.state_machine.advance_all_basic(
+                    self.steppers, self.passthrough_buffer
+                )
+                if not stepper.remaining_input
+            ]
+            if resynced_steppers:
+                self.steppers = resynced_steppers
+            else:
+                logger.debug(f"Passthrough region left unparsed: {self.passthrough_buffer!r}")
+            self.passthrough_buffer = ""
+
+        self.constrained = constrained
+
+    def consume_text(self, text: str, *args: Any, **kwargs: Any) -> Any:
+        if not self.constrained:
+            self.passthrough_buffer += text
+            return None
+        consumed = super().consume_text(text, *args, **kwargs)
+        self._record_consumed(text)
+        self._advance_fallback(text, primary_died=not self.steppers)
+        self._assert_unambiguous()
+        self._emit_events()
+        return consumed
+
+    def consume(self, token_id: int, *args: Any, **kwargs: Any) -> Any:
+        if not self.constrained:
+            self.passthrough_buffer += self.decode([token_id])
+            return token_id
+
+        if self.pending_token_ids or self.decode([token_id]).endswith(PARTIAL_CHARACTER):
+            return self._consume_partial_character(token_id)
+
+        self._fill_defaults(self.decode([token_id]))
+        consumed_token_id = super().consume(token_id, *args, **kwargs)
+        if self._advance_fallback(
+            [token_id if consumed_token_id is None else consumed_token_id],
+            primary_died=consumed_token_id is None or not self.steppers,
+        ):
+            consumed_token_id = token_id
+        self.output_token_count += 1
+        self._assert_unambiguous()
+        if logger.isEnabledFor(logging.DEBUG):
+            logger.debug(f"Steppers after token {token_id}: {summarize_steppers(self.steppers)}")
+        if consumed_token_id is not None:
+            self._record_consumed([consumed_token_id])
+            self._emit_events()
+        self.metrics.record_step(
+            1,
+            len(self.steppers),
+            healed=consumed_token_id is not None and consumed_token_id != token_id,
+        )
+        self._record_generation_outcome(stuck=consumed_token_id is None)
+        return consumed_token_id
+
+    def _consume_partial_character(self, token_id: int) -> int | None:
+        """
+        Buffer byte-level tokens until they decode to a complete character.
+
+        The grammar is only driven once the buffered tokens decode without a
+        trailing partial character, or when a UTF-8 sequence could not be
+        any longer, so multi-byte characters may be split across tokens.
+        """
+        self.pending_token_ids.append(token_id)
+        self.output_token_count += 1
+        text = self.decode(self.pending_token_ids)
+        if text.endswith(PARTIAL_CHARACTER) and len(self.pending_token_ids) < MAX_UTF8_BYTES:
+            return token_id
+
+        self.pending_token_ids = []
+        self._fill_defaults(text)
+        self.consume_text(text)
+        self.metrics.record_step(1, len(self.steppers))
+        self._record_generation_outcome(stuck=not self.steppers)
+        return token_id if self.steppers else None
+
+    def _fill_defaults(self, text: str) -> None:
+        """
+        Fill in missing defaults if the text closes an object early.
+        """
+        if not self.emit_defaults:
+            return
+        fill_token_ids, fill = self._tokenize_fill(self.get_default_fill(text))
+        if fill:
+            super().consume_text(fill)
+            self._record_consumed(fill)
+            self.injected_token_ids.extend(fill_token_ids)
+            self._advance_fallback(fill, primary_died=False)
+            self._emit_events()
+            logger.debug(f"Filled in defaults: {fill!r}")
+
+    def get_pending_token_ids(self) -> set[int]:
+        """
+        Get the byte tokens that may follow the pending part of a character.
+
+        A byte is allowed if the character stays incomplete but could still
+        be completed, or if it completes a character the structure accepts next.
+        """
+        pending_text = self.decode(self.pending_token_ids)
+        complete_text = pending_text.rstrip(PARTIAL_CHARACTER)
+        allowed: set[int] = set()
+        for token_id in self._get_byte_token_ids():
+            token_ids = [*self.pending_token_ids, token_id]
+            text = self.decode(token_ids)
+            if text.endswith(PARTIAL_CHARACTER):
+                # an invalid byte decodes to a replacement character of its own
+                if text == pending_text and len(token_ids) < MAX_UTF8_BYTES:
+                    allowed.add(token_id)
+                continue
+            if PARTIAL_CHARACTER in text[len(complete_text) :]:
+                continue
+            fill = self.get_default_fill(text)
+            if self._can_consume(self.steppers, fill + text):
+                allowed.add(token_id)
+        return allowed
+
+    def _get_byte_token_ids(self) -> set[int]:
+        """
+        The token ids that decode to part of a character on their own, found on first use.
+        """
+        if self._byte_token_ids is None:
+            self._byte_token_ids = {
+                token_id
+                for token_id, text in self.reverse_vocabulary.items()
+                if text == PARTIAL_CHARACTER
+            }
+        return self._byte_token_ids
+
+    def set_event_callback(self, callback: Callable[[JsonEvent], None] | None) -> None:
+        """
+        Stream the structured output as SAX-style events instead of buffering it.
+
+        Events (StartObject, Key, Value, EndObject, StartArray, EndArray) are
+        passed to the callback as soon as the text that completes them is consumed.
+        Pass None to stop streaming.
+        """
+        self.event_parser = JsonEventParser(callback) if callback else None
+        self._event_text = self._get_accepted_text() if self.steppers else ""
+
+    def _record_consumed(self, consumed: str | list[int]) -> None:
+        """
+        Record consumed token ids or text, for `serialize_state` to replay.
+        """
+        if not consumed:
+            return
+        last = self._consumed_segments[-1] if self._consumed_segments else None
+        if isinstance(consumed, str) and isinstance(last, str):
+            self._consumed_segments[-1] = last + consumed
+        elif isinstance(consumed, list) and isinstance(last, list):
+            last.extend(consumed)
+        else:
+            self._consumed_segments.append(
+                consumed if isinstance(consumed, str) else list(consumed)
+            )
+
+    def _emit_events(self) -> None:
+        """
+        Feed the event parser the text the steppers accepted since it was last fed.
+
+        What the steppers hold is what counts, so text that was rejected, or
+        left over as remaining input, never produces events.
+        """
+        if self.event_parser is None or not self.steppers:
+            return
+        accepted_text = self._get_accepted_text()
+        if not accepted_text.startswith(self._event_text):
+            logger.debug(f"Accepted text diverged from the events sent: {accepted_text!r}")
+            return
+        self.event_parser.feed(accepted_text[len(self._event_text) :])
+        self._event_text = accepted_text
+        if not self.can_continue():
+            # nothing can follow, so a trailing scalar is complete
+            self.event_parser.finish()
+
+    def _get_accepted_text(self) -> str:
+        """
+        The text every live stepper has accepted.
+        """
+        return os.path.commonprefix([stepper.get_raw_value() for stepper in self.steppers])
+
+    def _decode_consumed(self, consumed: str | list[int]) -> str:
+        """
+        The text of consumed text or token ids.
+        """
+        if isinstance(consumed, str):
+            return consumed
+        return "".join(
+            self.reverse_vocabulary.get(token_id) or self.decode([token_id])
+            for token_id in consumed
+        )
+
+    def _assert_unambiguous(self) -> None:
+        """
+        Raise if the engine is in `no_backtrack` mode but holds several steppers.
+
+        Only an assertion: the steppers were already advanced the usual way.
+        """
+        if self.no_backtrack and len(self.steppers) > 1:
+            paths = sorted({self._get_stepper_path(stepper) for stepper in self.steppers})
+            raise PseAmbiguityError(
+                f"Ambiguous structure: {len(self.steppers)} steppers at {paths}"
             )
-            token_safe_output = final_stepper.get_token_safe_output(
-                lambda x: self.tokenizer.decode(x)
+
+    def metrics_snapshot(self) -> dict[str, float]:
+        """
+        Get the engine's cumulative counters, for export by a serving wrapper.
+        """
+        return self.metrics.snapshot()
+
+    def _record_generation_outcome(self, stuck: bool) -> None:
+        """
+        Count the current generation as completed or stuck, at most once.
+        """
+        if self._generation_finished:
+            return
+        if stuck:
+            self.metrics.record_generation(completed=False)
+            self._generation_finished = True
+        elif self.has_reached_accept_state:
+            self.metrics.record_generation(completed=True)
+            self._generation_finished = True
+
+    def select(self, logprobs: Array_Type, top_k: int = 64) -> int | None:
+        """
+        Mask the logprobs and advance the engine with the best valid token.
+
+        Args:
+            logprobs: Array of shape (vocab_size,) or (1, vocab_size) containing log probabilities
+            top_k: Number of highest-scoring valid tokens to try, in order
+
+        Returns:
+            The id of the consumed token, or None if no valid token could be consumed.
+        """
+        adjusted_logprobs = self.process_logits(None, logprobs)
+        for token_id, score in get_top_k(adjusted_logprobs, top_k).items():
+            if score <= float("-inf"):
+                break
+
+            consumed_token_id = self.consume(token_id)
+            if consumed_token_id is not None:
+                return consumed_token_id
+
+        return None
+
+    def replay(self, token_ids: list[int]) -> ReplayReport:
+        """
+        Replay a trace of token ids from the start of the configured structure.
+
+        Used to reproduce stuck generations: the report pinpoints the first
+        token id that could not be consumed and what was expected instead.
+        The replay is not counted in the engine's metrics.
+
+        Args:
+            token_ids: The token ids to replay, in generation order.
+
+        Returns:
+            A report describing how far the replay got.
+        """
+        # a throwaway counter, so exported metrics only reflect real generations
+        metrics, self.metrics = self.metrics, EngineMetrics()
+        try:
+            report = self._replay(token_ids)
+        finally:
+            self.metrics = metrics
+        return report
+
+    def _replay(self, token_ids: list[int]) -> ReplayReport:
+        """
+        Replay a trace of token ids from the start of the configured structure.
+        """
+        self._rewind()
+        report = ReplayReport()
+        for index, token_id in enumerate(token_ids):
+            expected = sorted(
+                {
+                    continuation
+                    for stepper in self.steppers
+                    for continuation in stepper.get_valid_continuations()
+                }
+            )
+            if self.consume(token_id) is None:
+                report.stuck_index = index
+                report.stuck_token = self.decode([token_id])
+                report.expected = expected
+                logger.debug(
+                    f"Replay stuck at index {index} on {report.stuck_token!r}, "
+                    f"expected one of {expected}"
+                )
+                break
+            report.consumed_token_ids.append(token_id)
+
+        report.raw_output = self.decode(report.consumed_token_ids)
+        report.has_reached_accept_state = self.has_reached_accept_state
+        return report
+
+    def validate_message(self, text: str) -> ValidationResult:
+        """
+        Validate a complete message, e.g. one generated without constraints.
+
+        The message is tokenized against the vocabulary and driven through the
+        configured structure from the start, token by token. Within the token
+        that breaks it, characters are consumed one at a time, so the report
+        pinpoints both the token and the character. The engine's own steppers
+        are left untouched.
+
+        Args:
+            text: The message to validate.
+
+        Returns:
+            Whether the message is valid, and if not, where it broke.
+        """
+        steppers = self.state_machine.get_steppers()
+        consumed = 0
+        token_ids = self._tokenize_greedily(text)
+        error_token_index = len(token_ids)
+        for token_index, token_id in enumerate(token_ids):
+            token = self.reverse_vocabulary[token_id]
+            next_steppers = self._advance_steppers(steppers, token)
+            if not next_steppers:
+                error_token_index = token_index
+                break
+            steppers = next_steppers
+            consumed += len(token)
+
+        # the breaking token, or the rest of the text if it has no tokenization
+        for char in text[consumed:]:
+            next_steppers = self._advance_steppers(steppers, char)
+            if not next_steppers:
+                break
+            steppers = next_steppers
+            consumed += 1
+
+        is_accepted = any(stepper.has_reached_accept_state() for stepper in steppers)
+        if consumed == len(text) and (is_accepted or (not text and self.accepts_empty)):
+            return ValidationResult(ValidationStatus.VALID, valid_prefix=text)
+
+        expected = sorted(
+            {
+                continuation
+                for stepper in steppers
+                for continuation in stepper.get_valid_continuations()
+            }
+        )
+        return ValidationResult(
+            ValidationStatus.PARTIAL if consumed else ValidationStatus.INVALID,
+            valid_prefix=text[:consumed],
+            error_index=consumed if consumed < len(text) else None,
+            error_token_index=error_token_index if consumed < len(text) else None,
+            expected=expected,
+        )
+
+    def _advance_steppers(self, steppers: list[Stepper], text: str) -> list[Stepper]:
+        return [
+            stepper
+            for stepper in self.state_machine.advance_all_basic(steppers, text)
+            if not stepper.remaining_input
+        ]
+
+    def ambiguity_report(
+        self, samples: int = 10, max_len: int = 100, seed: int | None = 0
+    ) -> AmbiguityReport:
+        """
+        Estimate how ambiguous the configured structure is over this vocabulary.
+
+        Outputs are drawn with `sample_accepting`, tokenized against the vocabulary
+        and consumed token by token, counting the live steppers after each token.
+        High counts point at hotspots worth restructuring, e.g. unordered
+        object keys. The engine's own steppers are left untouched.
+
+        Args:
+            samples: The maximum number of sample outputs to replay.
+            max_len: The maximum length of each sample, in characters.
+            seed: Seed for the samples, or None for the shortest outputs.
+
+        Returns:
+            The stepper counts over every replayed sample.
+        """
+        report = AmbiguityReport()
+        stepper_total = 0
+        for sample in sample_accepting(self.state_machine, max_len, samples, seed):
+            report.sample_count += 1
+            steppers = self.state_machine.get_steppers()
+            for token_id in self._tokenize_greedily(sample):
+                steppers = self.state_machine.advance_all_basic(
+                    steppers, self.reverse_vocabulary[token_id]
+                )
+                report.token_count += 1
+                stepper_total += len(steppers)
+                report.max_steppers = max(report.max_steppers, len(steppers))
+
+        if report.token_count:
+            report.avg_steppers = stepper_total / report.token_count
+        return report
+
+    def serialize_state(self) -> str:
+        """
+        Checkpoint the in-progress generation.
+
+        The checkpoint records what has been consumed so far, not the steppers
+        themselves: sampled and consumed token ids, and text consumed directly,
+        forced or filled in, in the order it came. The vocabulary and the
+        configured structure are excluded and must be set up again before restoring.
+
+        Returns:
+            A JSON string to pass to `deserialize_state`.
+        """
+        state = {
+            "version": ENGINE_STATE_VERSION,
+            "segments": [
+                {"text": segment} if isinstance(segment, str) else {"token_ids": segment}
+                for segment in self._consumed_segments
+            ],
+            "pending_token_ids": self.pending_token_ids,
+            "constrained": self.constrained,
+            "passthrough_buffer": self.passthrough_buffer,
+        }
+        return json.dumps(state)
+
+    def deserialize_state(self, serialized_state: str) -> None:
+        """
+        Restore a generation checkpointed with `serialize_state`.
+
+        The engine must already be configured with the same structure;
+        the recorded input is replayed from its start.
+
+        Raises:
+            ValueError: If the checkpoint is malformed, from an unsupported
+                version, or cannot be replayed against the configured structure.
+        """
+        try:
+            state = json.loads(serialized_state)
+            version = state["version"]
+            segments: list[dict[str, Any]] = state["segments"]
+        except (json.JSONDecodeError, KeyError, TypeError) as e:
+            raise ValueError(f"Invalid engine state: {e}") from e
+
+        if version != ENGINE_STATE_VERSION:
+            raise ValueError(f"Unsupported engine state version: {version}")
+
+        # back to the configured primary and fallback, with nothing pending
+        self._rewind()
+        for segment in segments:
+            if "text" in segment:
+                self.consume_text(segment["text"])
+                if not self.steppers:
+                    raise ValueError(
+                        f"Engine state does not match the configured structure: "
+                        f"stuck on text {segment['text']!r}"
+                    )
+                continue
+            for token_id in segment.get("token_ids", []):
+                if self.consume(token_id) is None:
+                    raise ValueError(
+                        f"Engine state does not match the configured structure: "
+                        f"stuck at token {token_id} ({self.decode([token_id])!r})"
+                    )
+
+        # the bytes of a character still being split across tokens
+        self.pending_token_ids = list(state.get("pending_token_ids", []))
+
+        self.constrained = state.get("constrained", True)
+        self.passthrough_buffer = state.get("passthrough_buffer", "")
+
+    def get_forced_continuation(self) -> str:
+        """
+        Get the text that every live stepper agrees must come next.
+
+        Stops at the first genuine choice point: when steppers disagree,
+        any stepper accepts free-form input, or the structure may already end.
+
+        Returns:
+            The forced text, or an empty string if the model must choose.
+        """
+        forced = ""
+        steppers = list(self.steppers)
+        while steppers:
+            if any(
+                stepper.accepts_any_token() or stepper.has_reached_accept_state()
+                for stepper in steppers
+            ):
+                break
+
+            continuations = {
+                continuation
+                for stepper in steppers
+                for continuation in stepper.get_valid_continuations()
+                if continuation
+            }
+            if len(continuations) != 1:
+                break
+
+            continuation = continuations.pop()
+            steppers = [
+                stepper
+                for stepper in self.state_machine.advance_all_basic(steppers, continuation)
+                if not stepper.remaining_input
+            ]
+            if not steppers:
+                break
+            forced += continuation
+
+        return forced
+
+    def emit_forced_tokens(self) -> list[int]:
+        """
+        Consume the forced continuation, if any, and return its token ids.
+
+        The caller appends the returned ids to the generated sequence
+        without sampling them from the model.
+        """
+        token_ids = self.next_forced_tokens()
+        if token_ids:
+            # only the text the ids stand for, so the caller's sequence stays in sync
+            text = "".join(self.reverse_vocabulary[i] for i in token_ids)
+            self.consume_text(text)
+            logger.debug(f"Emitted forced continuation: {text!r}")
+        return token_ids
+
+    def next_forced_tokens(self) -> list[int]:
+        """
+        Get the token ids of the forced continuation, without consuming them.
+
+        Lets a serving loop skip the model for structural spans: if the result
+        is non-empty, the caller consumes these ids itself instead of sampling.
+        The text is tokenized greedily against the vocabulary, longest token first,
+        so no tokenizer-specific prefix is added mid-sequence. If only a prefix
+        of the text has an exact tokenization, only that prefix is returned.
+
+        Returns:
+            The forced token ids, or an empty list if the model must choose.
+        """
+        return self._tokenize_greedily(self.get_forced_continuation())
+
+    def would_accept(self, token_id: int) -> bool:
+        """
+        Whether consuming the token would keep the output valid, without consuming it.
+
+        The steppers are advanced on copies, so the engine is left unchanged.
+        Cheaper than masking the whole vocabulary when only one candidate matters.
+        """
+        if token_id in self._forbidden_token_ids:
+            return False
+        if token_id == self.tokenizer.eos_token_id:
+            return self.is_complete()
+        allowed_token_ids = self.get_allowed_token_ids()
+        if allowed_token_ids is not None and token_id not in allowed_token_ids:
+            return False
+
+        text = self._decode_consumed([token_id])
+        if not text:
+            return False
+        return any(
+            not stepper.remaining_input
+            for stepper in self.state_machine.advance_all_basic(self.steppers, text)
+        )
+
+    def valid_next_chars(self) -> set[str]:
+        """
+        Get the characters the structure allows next, below the level of tokens.
+
+        Candidates are the first character of each live stepper's valid
+        continuations, kept only if advancing by them keeps the output valid.
+        Free-form regions have no listed continuations, so they add nothing;
+        check `accepts_any_token` on the steppers for those.
+        """
+        candidates = {
+            continuation[0]
+            for stepper in self.steppers
+            for continuation in stepper.get_valid_continuations()
+            if continuation
+        }
+        return {
+            char
+            for char in candidates
+            if any(
+                not stepper.remaining_input
+                for stepper in self.state_machine.advance_all_basic(self.steppers, char)
+            )
+        }
+
+    def single_valid_token(self, advance: bool = False) -> int | None:
+        """
+        Get the only token the structure allows next, if exactly one is allowed.
+
+        Unlike `next_forced_tokens`, this looks at the token level, so it also
+        applies when the forced text has no tokenization of its own (e.g. an
+        explicit set of token ids). Only tokens sharing a prefix with a valid
+        continuation are checked, rather than masking the whole vocabulary.
+
+        Args:
+            advance: Whether to consume the token when there is one.
+
+        Returns:
+            The token id, or None if the model has a choice to make.
+        """
+        candidate_ids = self._get_candidate_token_ids()
+        if candidate_ids is None:
+            return None
+
+        token_id: int | None = None
+        for candidate_id in sorted(candidate_ids):
+            if not self.would_accept(candidate_id):
+                continue
+            if token_id is not None:
+                return None
+            token_id = candidate_id
+
+        if token_id is not None and advance:
+            self.consume(token_id)
+        return token_id
+
+    def _get_candidate_token_ids(self) -> set[int] | None:
+        """
+        Get the token ids that could be valid next, a superset of the valid ones.
+
+        Returns:
+            The candidates, or None if a free-form region allows almost any token.
+        """
+        if self.pending_token_ids:
+            return self.get_pending_token_ids()
+        candidate_ids = {self.tokenizer.eos_token_id} if self.is_complete() else set()
+        if (allowed_token_ids := self.get_allowed_token_ids()) is not None:
+            return candidate_ids | allowed_token_ids
+        if any(stepper.accepts_any_token() for stepper in self.steppers):
+            return None
+
+        for stepper in self.steppers:
+            for continuation in stepper.get_valid_continuations():
+                candidate_ids.update(self._get_token_ids_starting(continuation))
+        return candidate_ids
+
+    def _get_token_ids_starting(self, continuation: str) -> set[int]:
+        """
+        Get the token ids whose text starts the continuation.
+
+        These are the tokens ending within the continuation and those running past it.
+        """
+        text_to_token_ids = self._get_text_to_token_ids()
+        token_texts = self._get_sorted_token_texts()
+        token_ids: set[int] = set()
+        for end in range(1, len(continuation) + 1):
+            token_ids.update(text_to_token_ids.get(continuation[:end], []))
+        index = bisect.bisect_right(token_texts, continuation)
+        while index < len(token_texts) and token_texts[index].startswith(continuation):
+            token_ids.update(text_to_token_ids[token_texts[index]])
+            index += 1
+        return token_ids
+
+    def _get_sorted_token_texts(self) -> list[str]:
+        """
+        The distinct texts of the vocabulary's tokens, sorted, built on first use.
+        """
+        if self._sorted_token_texts is None:
+            self._sorted_token_texts = sorted(self._get_text_to_token_ids())
+        return self._sorted_token_texts
+
+    def _tokenize_greedily(self, text: str) -> list[int]:
+        """
+        Tokenize text against the vocabulary, longest token first.
+
+        Stops early if the rest of the text has no exact tokenization.
+        """
+        text_to_token_ids = self._get_text_to_token_ids()
+        max_token_length = max(map(len, text_to_token_ids), default=0)
+
+        token_ids: list[int] = []
+        position = 0
+        while position < len(text):
+            longest_end = min(len(text), position + max_token_length)
+            for end in range(longest_end, position, -1):
+                if candidate_ids := text_to_token_ids.get(text[position:end]):
+                    token_ids.append(min(candidate_ids))
+                    position = end
+                    break
+            else:
+                break
+
+        return token_ids
+
+    def _tokenize_fill(self, fill: str) -> tuple[list[int], str]:
+        """
+        Tokenize a default fill, returning the token ids and the text they decode to.
+
+        A fill the vocabulary can't spell in full is dropped, since only part
+        of it would leave the object incomplete.
+        """
+        token_ids = self._tokenize_greedily(fill)
+        text = "".join(self.reverse_vocabulary[i] for i in token_ids)
+        if text != fill:
+            logger.debug(f"Dropped default fill with no exact tokenization: {fill!r}")
+            return [], ""
+        return token_ids, text
+
+    def get_default_fill(self, token: str) -> str:
+        """
+        Get the defaults to fill in before a token that closes an object early.
+
+        Returns:
+            The fill text, or an empty string if the token is valid as-is
+            or no fill would make it valid.
+        """
+        if not self.emit_defaults or not token:
+            return ""
+        if self._can_consume(self.steppers, token):
+            return ""
+
+        for fill in self._get_default_fills():
+            if self._can_consume(self.steppers, fill + token):
+                return fill
+        return ""
+
+    def get_default_fill_token_ids(self) -> set[int]:
+        """
+        Get the token ids that are only valid once missing defaults are filled in.
+        """
+        text_to_token_ids = self._get_text_to_token_ids()
+        fill_token_ids: set[int] = set()
+        for fill in self._get_default_fills():
+            fill_steppers = [
+                stepper
+                for stepper in self.state_machine.advance_all_basic(self.steppers, fill)
+                if not stepper.remaining_input
+            ]
+            continuations = {
+                continuation
+                for stepper in fill_steppers
+                for continuation in stepper.get_valid_continuations()
+                if continuation in text_to_token_ids
+            }
+            for continuation in continuations:
+                if self.get_default_fill(continuation):
+                    fill_token_ids.update(text_to_token_ids[continuation])
+
+        return fill_token_ids
+
+    def take_injected_token_ids(self) -> list[int]:
+        """
+        Get the token ids of defaults filled in by `consume` since the last call.
+
+        The caller inserts them before the token that triggered the fill.
+        """
+        injected_token_ids, self.injected_token_ids = self.injected_token_ids, []
+        return injected_token_ids
+
+    def _get_default_fills(self) -> list[str]:
+        """
+        Get the candidate default fills of every open object, innermost first.
+        """
+        fills: list[str] = []
+        for stepper in self.steppers:
+            stepper_fills: list[str] = []
+            current: Stepper | None = stepper
+            while current is not None:
+                if isinstance(current.state_machine, ObjectSchemaStateMachine):
+                    value = current.get_current_value()
+                    if fill := current.state_machine.get_default_fill(
+                        value if isinstance(value, dict) else {}
+                    ):
+                        stepper_fills.append(fill)
+                current = current.sub_stepper
+
+            for fill in reversed(stepper_fills):
+                if fill not in fills:
+                    fills.append(fill)
+
+        return fills
+
+    def _can_consume(self, steppers: list[Stepper], text: str) -> bool:
+        return any(
+            not stepper.remaining_input
+            for stepper in self.state_machine.advance_all_basic(steppers, text)
+        )
+
+    def get_shortest_completion(self) -> str | None:
+        """
+        Get the cheapest text that brings the structure to an accept state.
+
+        Each continuation costs one, plus the cost of any edges it takes
+        (see `pse.util.costs`); without edge costs this is the completion
+        with the fewest continuations. Only the steppers' valid continuations
+        are searched, so free-form content (e.g. a string's characters) is never invented.
+
+        Returns:
+            The completion, "" if the structure is already complete,
+            or None if no completion was found.
+        """
+        if self.is_complete():
+            return ""
+
+        memo: dict[int, tuple[Stepper, float]] = {}
+
+        def edge_cost(steppers: list[Stepper]) -> float:
+            return min(path_cost(stepper, memo) for stepper in steppers)
+
+        base_cost = edge_cost(self.steppers) if self.steppers else 0.0
+        seen: set[str] = {""}
+        # (cost, insertion order, steps, text, steppers)
+        queue: list[tuple[float, int, int, str, list[Stepper]]] = [
+            (0.0, 0, 0, "", self.steppers)
+        ]
+        while queue and len(seen) < MAX_COMPLETION_SEARCH:
+            _, _, steps, text, steppers = heapq.heappop(queue)
+            if text and any(stepper.has_reached_accept_state() for stepper in steppers):
+                return text
+            continuations = sorted(
+                {
+                    continuation
+                    for stepper in steppers
+                    for continuation in stepper.get_valid_continuations()
+                    if continuation
+                },
+                key=lambda continuation: (len(continuation), continuation),
+            )
+            for continuation in continuations:
+                next_text = text + continuation
+                if next_text in seen:
+                    continue
+                seen.add(next_text)
+                next_steppers = [
+                    stepper
+                    for stepper in self.state_machine.advance_all_basic(
+                        steppers, continuation
+                    )
+                    if not stepper.remaining_input
+                ]
+                if next_steppers:
+                    cost = steps + 1 + edge_cost(next_steppers) - base_cost
+                    heapq.heappush(
+                        queue, (cost, len(seen), steps + 1, next_text, next_steppers)
+                    )
+
+        return None
+
+    def get_closure_token_ids(self) -> set[int] | None:
+        """
+        Get the token ids allowed while closing the structure within the token budget.
+
+        Returns:
+            The tokens on the shortest completion (or the end-of-sequence token
+            once complete), or None if the budget does not constrain the next token.
+        """
+        if not self.budget_aware_closure or self.max_output_tokens is None:
+            return None
+
+        # the completion search is skipped while the budget is far from closing
+        remaining_tokens = self.max_output_tokens - self.output_token_count
+        if remaining_tokens > MAX_CLOSURE_TOKENS:
+            return None
+
+        completion = self.get_shortest_completion()
+        if completion is None:
+            return None
+
+        if remaining_tokens > max(len(self._tokenize_greedily(completion)), 1):
+            return None
+
+        if not completion:
+            eos_token_id = self.tokenizer.eos_token_id
+            return {eos_token_id} if eos_token_id is not None else None
+
+        return {
+            token_id
+            for text, token_ids in self._get_text_to_token_ids().items()
+            if completion.startswith(text)
+            for token_id in token_ids
+        }
+
+    def get_terminal_token_ids(self) -> set[int]:
+        """
+        Get the token ids that would bring the structure to an accept state.
+
+        Includes the end-of-sequence token when the structure is already complete.
+        The result is kept until the steppers change, so masking the same state
+        again (e.g. when resampling) does not advance every continuation again.
+        """
+        cached_steppers, cached_token_ids = self._terminal_token_ids_cache
+        if self._is_current_steppers(cached_steppers):
+            return set(cached_token_ids)
+
+        text_to_token_ids = self._get_text_to_token_ids()
+        terminal_token_ids: set[int] = set()
+        continuations = {
+            continuation
+            for stepper in self.steppers
+            for continuation in stepper.get_valid_continuations()
+            if continuation in text_to_token_ids
+        }
+        for continuation in continuations:
+            steppers = self.state_machine.advance_all_basic(self.steppers, continuation)
+            if any(
+                stepper.has_reached_accept_state() and not stepper.remaining_input
+                for stepper in steppers
+            ):
+                terminal_token_ids.update(text_to_token_ids[continuation])
+
+        if self.is_complete() and self.tokenizer.eos_token_id is not None:
+            terminal_token_ids.add(self.tokenizer.eos_token_id)
+
+        self._terminal_token_ids_cache = (list(self.steppers), set(terminal_token_ids))
+        return terminal_token_ids
+
+    def get_repetition_token_ids(self) -> tuple[set[int], set[int]]:
+        """
+        Get the token ids that would repeat a loop that could also stop here.
+
+        Only loops built with `greedy` set are considered, and only between
+        repetitions, once the loop has repeated often enough to stop.
+        A token repeats a loop if its text starts one of the loop's continuations.
+        The result is kept until the steppers change.
+
+        Returns:
+            The token ids repeating greedy loops, and those repeating lazy loops.
+        """
+        cached_steppers, (cached_greedy, cached_lazy) = self._repetition_token_ids_cache
+        if self._is_current_steppers(cached_steppers):
+            return set(cached_greedy), set(cached_lazy)
+
+        greedy_token_ids: set[int] = set()
+        lazy_token_ids: set[int] = set()
+        for stepper in self.steppers:
+            current: Stepper | None = stepper
+            while current is not None:
+                state_machine = current.state_machine
+                if (
+                    isinstance(state_machine, LoopStateMachine)
+                    and state_machine.greedy is not None
+                    and current.sub_stepper is None
+                    and current.has_reached_accept_state()
+                ):
+                    token_ids = greedy_token_ids if state_machine.greedy else lazy_token_ids
+                    for continuation in current.get_valid_continuations():
+                        token_ids.update(self._get_token_ids_starting(continuation))
+                current = current.sub_stepper
+
+        self._repetition_token_ids_cache = (
+            list(self.steppers),
+            (set(greedy_token_ids), set(lazy_token_ids)),
+        )
+        return greedy_token_ids, lazy_token_ids
+
+    def _is_current_steppers(self, steppers: list[Stepper]) -> bool:
+        """
+        Whether `steppers` are the live steppers, the very same objects in order.
+        """
+        return (
+            bool(self.steppers)
+            and len(steppers) == len(self.steppers)
+            and all(cached is stepper for cached, stepper in zip(steppers, self.steppers))
+        )
+
+    def _get_text_to_token_ids(self) -> dict[str, list[int]]:
+        """
+        Map each token's text to the token ids that decode to it, built on first use.
+        """
+        if self._text_to_token_ids is None:
+            self._text_to_token_ids = {}
+            for token_id, text in self.reverse_vocabulary.items():
+                if text:
+                    self._text_to_token_ids.setdefault(text, []).append(token_id)
+        return self._text_to_token_ids
+
+    def get_allowed_token_ids(self) -> set[int] | None:
+        """
+        Get the token ids allowed next, if every live stepper is constrained by token id.
+
+        Returns:
+            The union of the allowed token ids, or None if any stepper is constrained by text.
+        """
+        allowed_token_ids: set[int] = set()
+        for stepper in self.steppers:
+            current: Stepper | None = stepper
+            while current is not None and not isinstance(current, TokenIdStepper):
+                current = current.sub_stepper
+            if current is None:
+                return None
+            if (token_ids := current.get_allowed_token_ids()) is None:
+                return None
+            allowed_token_ids.update(token_ids)
+
+        return allowed_token_ids if self.steppers else None
+
+    def expected(self, max_examples: int = 10) -> list[Expectation]:
+        """
+        Describe what the live steppers expect next.
+
+        Steppers are grouped by the type of their innermost state machine,
+        qualified by the name of the closest named state machine around it.
+
+        Args:
+            max_examples: Maximum number of example strings per expectation,
+                shortest first (e.g. structural tokens before long literals).
+
+        Returns:
+            One expectation per state machine type, in order of first appearance.
+        """
+        examples: dict[str, set[str]] = {}
+        for stepper in self.steppers:
+            innermost = stepper
+            name = get_name(innermost.state_machine)
+            while innermost.sub_stepper is not None:
+                innermost = innermost.sub_stepper
+                name = get_name(innermost.state_machine) or name
+
+            label = describe(innermost.state_machine, name)
+            examples.setdefault(label, set()).update(
+                continuation
+                for continuation in stepper.get_valid_continuations()
+                if continuation
+            )
+
+        return [
+            Expectation(
+                label,
+                sorted(
+                    label_examples,
+                    key=lambda example: (len(example), example),
+                )[:max_examples],
+            )
+            for label, label_examples in examples.items()
+            if label_examples
+        ]
+
+    def next_structural_event(self) -> StructuralHint | None:
+        """
+        Describe the structure expected next, e.g. "expecting a key".
+
+        Each live stepper is placed by the innermost object, array or key-value
+        pair it has entered; the most common hint among them wins, ties going
+        to the first stepper.
+
+        Returns:
+            The hint, or None if nothing more is expected.
+        """
+        votes: dict[tuple[StructuralHintKind, str | None], int] = {}
+        for stepper in self.steppers:
+            if (hint := self._get_structural_hint(stepper)) is not None:
+                key = (hint.kind, hint.value_type)
+                votes[key] = votes.get(key, 0) + 1
+
+        if not votes:
+            return None
+        kind, value_type = max(votes, key=lambda key: votes[key])
+        return StructuralHint(kind, value_type)
+
+    @staticmethod
+    def _get_structural_hint(stepper: Stepper) -> StructuralHint | None:
+        """
+        Place one stepper by the innermost container it has entered.
+        """
+        if not stepper.can_accept_more_input():
+            return None
+
+        container: Stepper | None = None
+        current: Stepper | None = stepper
+        while current is not None:
+            # objects and arrays are entered once their opening bracket is consumed,
+            # key-value pairs as soon as they are reached
+            if isinstance(current.state_machine, KeyValueStateMachine) or (
+                isinstance(current.state_machine, ObjectStateMachine | ArrayStateMachine)
+                and current.current_state != 0
+            ):
+                container = current
+            current = current.sub_stepper
+
+        if container is None:
+            return StructuralHint(StructuralHintKind.VALUE, describe(stepper.state_machine))
+
+        state_machine = container.state_machine
+        state, target_state = container.current_state, container.target_state
+        if isinstance(state_machine, KeyValueStateMachine):
+            if state == 0:
+                return StructuralHint(StructuralHintKind.KEY)
+            if state in (1, 2):
+                return StructuralHint(StructuralHintKind.KEY_SEPARATOR)
+            value_state_machine = state_machine.get_edges(4)[0][0]
+            return StructuralHint(StructuralHintKind.VALUE, describe(value_state_machine))
+
+        is_object = isinstance(state_machine, ObjectStateMachine)
+        close = StructuralHintKind.OBJECT_CLOSE if is_object else StructuralHintKind.ARRAY_CLOSE
+        if target_state == "$":
+            return StructuralHint(close)
+        if state in (3, 4):
+            return StructuralHint(StructuralHintKind.ITEM_SEPARATOR)
+        if is_object:
+            return StructuralHint(StructuralHintKind.KEY)
+        value_state_machine = state_machine.get_edges(2)[0][0]
+        return StructuralHint(StructuralHintKind.VALUE, describe(value_state_machine))
+
+    def is_within_value(self) -> bool:
+        """
+        Whether the output is inside a value, e.g. a string or number, rather
+        than between values on structural punctuation such as `{`, `:` or `,`.
+
+        Keys are structure, not values. Useful for highlighting in client UIs.
+
+        Returns:
+            True if any live stepper has started a value it has not yet finished.
+        """
+        return any(self._is_stepper_within_value(stepper) for stepper in self.steppers)
+
+    @staticmethod
+    def _is_stepper_within_value(stepper: Stepper) -> bool:
+        """
+        Follow one stepper down through the containers it has entered to the
+        value it is parsing, if it is parsing one.
+        """
+        current: Stepper | None = stepper
+        while current is not None:
+            state_machine = current.state_machine
+            if isinstance(state_machine, KeyValueStateMachine):
+                # the value is the last link, after the key and the colon
+                if current.current_state != 4:
+                    return False
+            elif isinstance(state_machine, ObjectStateMachine | ArrayStateMachine):
+                # properties and items are parsed from state 2
+                if current.current_state != 2:
+                    return False
+            elif isinstance(current, StringStepper):
+                return current.is_within_value()
+            elif isinstance(state_machine, WhitespaceStateMachine):
+                return False
+            elif current.sub_stepper is None:
+                return bool(current.get_raw_value())
+            current = current.sub_stepper
+        return False
+
+    def accept_candidates(self) -> list[tuple[Any, str]]:
+        """
+        List the distinct completed values among the live steppers.
+
+        Useful for ambiguous grammars, where the output so far can end in more
+        than one way. Candidates are ranked by how many steppers agree on them.
+
+        Returns:
+            (value, path) pairs, where the path names the state machines
+            from the root to the innermost stepper that produced the value.
+        """
+        votes: dict[tuple[str, str], int] = {}
+        values: dict[tuple[str, str], Any] = {}
+        for stepper in self.steppers:
+            if not stepper.has_reached_accept_state() or stepper.remaining_input:
+                continue
+
+            value = stepper.get_current_value()
+            key = (repr(value), self._get_stepper_path(stepper))
+            votes[key] = votes.get(key, 0) + 1
+            values.setdefault(key, value)
+
+        ranked = sorted(votes, key=lambda key: votes[key], reverse=True)
+        return [(values[key], key[1]) for key in ranked]
+
+    @staticmethod
+    def _get_stepper_path(stepper: Stepper) -> str:
+        """
+        Name the state machines from the root stepper to its innermost sub-stepper.
+        """
+        path: list[str] = []
+        current: Stepper | None = stepper
+        while current is not None:
+            path.append(describe(current.state_machine))
+            current = current.sub_stepper
+        return "/".join(path)
+
+    @property
+    def has_reached_accept_state(self) -> bool:
+        return self._is_empty_accepted() or super().has_reached_accept_state
+
+    def is_complete(self) -> bool:
+        """
+        Whether the output so far is a complete instance of the structure.
+
+        True if some stepper has reached an accept state with no input left over,
+        or nothing has been output yet and an empty output is accepted.
+        The structure may still be able to continue; see `can_continue`.
+        """
+        return self._is_empty_accepted() or any(
+            stepper.has_reached_accept_state() and not stepper.remaining_input
+            for stepper in self.steppers
+        )
+
+    def _is_empty_accepted(self) -> bool:
+        """
+        Whether nothing has been output yet and an empty output is accepted.
+        """
+        return self.accepts_empty and not any(
+            stepper.get_raw_value() for stepper in self.steppers
+        )
+
+    def can_continue(self) -> bool:
+        """
+        Whether the structure can accept more input.
+
+        Both this and `is_complete` are true for open-ended structures
+        (e.g. a repetition that has met its minimum count).
+        """
+        return any(stepper.can_accept_more_input() for stepper in self.steppers)
+
+    def prune_dead_steppers(self) -> None:
+        """
+        Drop steppers that can neither accept more input nor have reached an accept state.
+        """
+        live_steppers = [
+            stepper
+            for stepper in self.steppers
+            if stepper.can_accept_more_input() or stepper.has_reached_accept_state()
+        ]
+        if live_steppers:
+            self.steppers = live_steppers
+
+    def take_patches(self) -> list[JsonPatchOp]:
+        """
+        Get the JSON Patch (RFC 6902) operations since the last call.
+
+        Applying every returned patch, in order, to `null` rebuilds the
+        partial value of the structure generated so far.
+
+        Returns:
+            The operations that turn the previously reported value into the current one.
+        """
+        current_value = next(
+            (
+                stepper.get_current_value()
+                for stepper in self.steppers
+                if stepper.get_current_value() is not None
+            ),
+            None,
+        )
+        patches = diff_json(self._patched_value, current_value)
+        self._patched_value = copy.deepcopy(current_value)
+        return patches
+
+    def completed_items(self) -> list[Any]:
+        """
+        Get the items of a root-level array that have been finalized so far.
+
+        Lets records be streamed out one at a time, before the array is closed.
+
+        Returns:
+            The completed items, or an empty list if no array is being generated.
+        """
+        completed: list[Any] = []
+        for stepper in self.steppers:
+            root_array = self._get_root_array_stepper(stepper)
+            if root_array is not None and len(root_array.value) > len(completed):
+                completed = root_array.value[:]
+
+        return completed
+
+    @staticmethod
+    def _get_root_array_stepper(stepper: Stepper) -> ArrayStepper | None:
+        """
+        Find the outermost array stepper, unless the array is nested in an object.
+        """
+        current: Stepper | None = stepper
+        while current is not None:
+            if isinstance(current, ArrayStepper):
+                return current
+            if isinstance(current, ObjectStepper):
+                # an array under an object is a property value, not the root
+                return None
+            current = current.sub_stepper
+        return None
+
+    def get_structured_output(
+        self,
+        output_type: type[OutputType] | None = None,
+        raise_on_error: bool = False,
+    ) -> OutputType | Any:
+        """
+        Parse and cast the output to the given type.
+        """
+        for stepper in self.steppers:
+            for _, token_safe_output in self._iter_state_and_output(
+                stepper,
+                output_type,
+                raise_on_error,
+            ):
+                return token_safe_output
+
+    def finalize(
+        self,
+        close_open: bool = False,
+        output_type: type[OutputType] | None = None,
+    ) -> OutputType | Any:
+        """
+        Get the final output once generation has stopped, e.g. on EOS or a token budget.
+
+        Args:
+            close_open: Whether to repair an unfinished output by consuming the
+                shortest completion, closing any open strings and structures.
+            output_type: Passed on to `get_structured_output`.
+
+        Returns:
+            The structured output, or None if the output is unfinished and
+            could not be (or was not asked to be) closed.
+        """
+        if not self.is_complete():
+            completion = self.get_shortest_completion() if close_open else None
+            if completion is None:
+                return None
+            logger.debug(f"Closing open structures with {completion!r}")
+            self.consume_text(completion)
+            if not self.is_complete():
+                return None
+
+        return self.get_structured_output(output_type)
+
+    def get_labeled_output(
+        self,
+        output_type: type[OutputType] | None = None,
+        raise_on_error: bool = False,
+    ) -> Iterator[tuple[str, OutputType | Any]]:
+        """
+        Get each part of the output labeled with the identifier of the step that produced it.
+        """
+        for stepper in self.steppers:
+            yield from self._iter_state_and_output(
+                stepper,
+                output_type,
+                raise_on_error,
+            )
+
+    def _iter_state_and_output(
+        self,
+        stepper: Stepper,
+        output_type: type[OutputType] | None,
+        raise_on_error: bool,
+    ) -> Iterator[tuple[str, OutputType | Any]]:
+        """
+        Helper method to parse and yield structured output from a stepper.
+        """
+        for final_stepper in stepper.get_final_state():
+            if (
+                self.leading_marker_state_machine is not None
+                and final_stepper.state_machine is self.leading_marker_state_machine
+            ):
+                continue
+            identifier = final_stepper.get_identifier() or str(
+                final_stepper.current_state
             )
+            token_safe_output = final_stepper.get_token_safe_output(self.decode)
             output = self.cast_output(token_safe_output, output_type, raise_on_error)
             yield identifier.lower(), output
 
@@ -231,12 +2163,12 @@ class StructuringEngine(Engine):
             if score <= float("-inf") or score < -1e10:
                 continue
 
-            token = repr(self.tokenizer.decode(token_id))
+            token = repr(self.decode([token_id]))
 
             if token_id in self.multi_token_mapping:
                 multi_tokens = self.multi_token_mapping[token_id]
                 if len(multi_tokens) > 1:
-                    multi_repr = repr(self.tokenizer.decode(multi_tokens))
+                    multi_repr = repr(self.decode(multi_tokens))
                     token = f"{token} -📶-> {multi_repr}"
 
             rows.append(f"{token_id:<8} | {score:>10.4f} | {token}")
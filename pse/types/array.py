@@ -9,7 +9,7 @@ from pse_core.stepper import Stepper
 from pse.types.base.chain import ChainStateMachine
 from pse.types.base.phrase import PhraseStateMachine
 from pse.types.json.json_value import JsonStateMachine
-from pse.types.whitespace import WhitespaceStateMachine
+from pse.types.whitespace import DEFAULT_MAX_WHITESPACE, WhitespaceStateMachine
 
 
 class ArrayStateMachine(StateMachine):
@@ -20,19 +20,27 @@ class ArrayStateMachine(StateMachine):
     and maintaining the current array values being parsed.
     """
 
-    def __init__(self, state_graph: StateGraph | None = None) -> None:
+    def __init__(
+        self,
+        state_graph: StateGraph | None = None,
+        max_whitespace: int = DEFAULT_MAX_WHITESPACE,
+    ) -> None:
+        self.max_whitespace = max_whitespace
         base_array_state_graph: StateGraph = {
             0: [(PhraseStateMachine("["), 1)],
             1: [
-                (WhitespaceStateMachine(), 2),
+                (WhitespaceStateMachine(max_whitespace=max_whitespace), 2),
                 (PhraseStateMachine("]"), "$"),  # Allow empty array
             ],
             2: [(JsonStateMachine(), 3)],
-            3: [(WhitespaceStateMachine(), 4)],
+            3: [(WhitespaceStateMachine(max_whitespace=max_whitespace), 4)],
             4: [
                 (
                     ChainStateMachine(
-                        [PhraseStateMachine(","), WhitespaceStateMachine()]
+                        [
+                            PhraseStateMachine(","),
+                            WhitespaceStateMachine(max_whitespace=max_whitespace),
+                        ]
                     ),
                     2,
                 ),
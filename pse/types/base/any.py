@@ -1,12 +1,44 @@
-from pse_core import StateId
+from __future__ import annotations
+
+import os
+from typing import Any
+
+from pse_core import StateGraph, StateId
 from pse_core.state_machine import StateMachine
 from pse_core.stepper import Stepper
 
+from pse.types.base.chain import ChainStateMachine
+
 
 class AnyStateMachine(StateMachine):
-    def __init__(self, state_machines: list[StateMachine]) -> None:
+    def __init__(
+        self,
+        state_machines: list[StateMachine],
+        max_transitions_per_stepper: int | None = None,
+    ) -> None:
+        """
+        Args:
+            state_machines: The alternatives to choose between.
+            max_transitions_per_stepper: If set, a stepper branching into this choice
+                keeps at most this many branches, preferring those whose
+                first characters overlap most with the incoming token.
+                Branching with no token, e.g. to mask logits, keeps them all.
+        """
+        if max_transitions_per_stepper is not None and max_transitions_per_stepper < 1:
+            raise ValueError("max_transitions_per_stepper must be at least 1")
 
         self.state_machines: list[StateMachine] = state_machines
+        self.max_transitions_per_stepper = max_transitions_per_stepper
+        # alternatives sharing leading links only branch where they differ
+        self.merged = get_shared_prefix_state_machine(state_machines)
+        if self.merged is not None:
+            super().__init__(
+                state_graph=self.merged.state_graph,
+                end_states=self.merged.end_states,
+                is_optional=self.merged.is_optional,
+            )
+            return
+
         super().__init__(
             {
                 0: [
@@ -17,10 +49,131 @@ class AnyStateMachine(StateMachine):
         )
 
     def get_steppers(self, state: StateId | None = None) -> list[Stepper]:
+        if self.max_transitions_per_stepper is not None:
+            # branch lazily, once the incoming token is known
+            return [self.get_new_stepper(state or 0)]
+        if self.merged is not None:
+            return super().get_steppers(state)
+
         steppers = []
         for edge, _ in self.get_edges(state or 0):
             steppers.extend(edge.get_steppers())
         return steppers
 
+    def branch_stepper(self, stepper: Stepper, token: str | None = None) -> list[Stepper]:
+        branches = super().branch_stepper(stepper, token)
+        limit = self.max_transitions_per_stepper
+        # without a concrete token (e.g. while masking) every branch stays reachable
+        if limit is None or not token or len(branches) <= limit:
+            return branches
+
+        return prune_branches(branches, token, limit)
+
+    @staticmethod
+    def merge_prefixes(state_machines: list[StateMachine]) -> StateMachine:
+        """
+        Build a choice whose alternatives share a single path for common prefixes.
+
+        Chains are split into their links and merged into a prefix tree, so
+        alternatives that all start with the same links (e.g. `{"type":`) only
+        branch where they first differ. Any other state machine is one opaque link.
+        Links are shared when they compare equal.
+
+        Args:
+            state_machines: The alternatives to choose between.
+
+        Returns:
+            A state machine over the merged state graph.
+        """
+        if not state_machines:
+            raise ValueError("At least one state machine is required.")
+
+        # each node is a list of (link, child node) pairs and an end flag
+        root: dict[str, Any] = {"links": [], "is_end": False}
+        for state_machine in state_machines:
+            node = root
+            for link in _get_links(state_machine):
+                for existing_link, child in node["links"]:
+                    if existing_link == link:
+                        node = child
+                        break
+                else:
+                    child = {"links": [], "is_end": False}
+                    node["links"].append((link, child))
+                    node = child
+            node["is_end"] = True
+
+        state_graph: StateGraph = {}
+        end_states: list[StateId] = []
+        queue: list[tuple[int, dict[str, Any]]] = [(0, root)]
+        next_state = 1
+        while queue:
+            state, node = queue.pop(0)
+            if node["is_end"]:
+                end_states.append(state)
+            for link, child in node["links"]:
+                state_graph.setdefault(state, []).append((link, next_state))
+                queue.append((next_state, child))
+                next_state += 1
+
+        return StateMachine(
+            state_graph=state_graph,
+            end_states=end_states,
+            is_optional=root["is_end"]
+            or any(state_machine.is_optional for state_machine in state_machines),
+        )
+
     def __str__(self) -> str:
         return "Any"
+
+
+def get_shared_prefix_state_machine(
+    state_machines: list[StateMachine],
+) -> StateMachine | None:
+    """
+    Merge alternatives into a prefix tree, if any of them start with the same link.
+
+    Returns:
+        The merged state machine, or None if no two alternatives share a first link.
+    """
+    if len(state_machines) < 2:
+        return None
+    merged = AnyStateMachine.merge_prefixes(state_machines)
+    if len(merged.state_graph.get(0, [])) == len(state_machines):
+        return None
+    return merged
+
+
+def _get_links(state_machine: StateMachine) -> list[StateMachine]:
+    """
+    The links of a chain, in order, or the state machine itself if it is not a chain.
+    """
+    if not isinstance(state_machine, ChainStateMachine) or state_machine.is_optional:
+        return [state_machine]
+    return [
+        edge
+        for state in range(len(state_machine.state_graph))
+        for edge, _ in state_machine.state_graph[state]
+    ]
+
+
+def prune_branches(branches: list[Stepper], token: str, limit: int) -> list[Stepper]:
+    """
+    Keep the `limit` most promising branches for the incoming token.
+
+    Branches that can start on the token come first, then those whose
+    valid continuations share the longest prefix with it.
+    Ties keep their original order.
+    """
+
+    def score(branch: Stepper) -> tuple[bool, int]:
+        overlap = max(
+            (
+                len(os.path.commonprefix([continuation, token]))
+                for continuation in branch.get_valid_continuations()
+            ),
+            default=0,
+        )
+        return branch.should_start_step(token), overlap
+
+    return sorted(branches, key=score, reverse=True)[:limit]
@@ -2,6 +2,7 @@ from __future__ import annotations
 
 import logging
 
+from pse_core import Edge, StateGraph, StateId
 from pse_core.state_machine import StateMachine
 from pse_core.stepper import Stepper
 
@@ -30,6 +31,62 @@ class ChainStateMachine(StateMachine):
     def get_new_stepper(self, state: int | str | None = None) -> Stepper:
         return ChainStepper(self, state)
 
+    @staticmethod
+    def flatten(state_machines: list[StateMachine]) -> StateMachine:
+        """
+        Merge the state graphs of several state machines into a single flat graph.
+
+        Each state is prefixed with the index of the state machine it came from
+        (state 0 of the second state machine becomes "1.0"), so states from
+        different graphs never collide. The end states of each state machine
+        inherit the outgoing edges of the next state machine's start state.
+
+        Only the static state graphs are merged; state machines that customize
+        `get_edges` or `get_transitions` should be chained as sub-machines instead.
+
+        Args:
+            state_machines: State machines to be merged in sequence
+
+        Returns:
+            A state machine over the merged, namespaced state graph.
+        """
+        if not state_machines:
+            raise ValueError("At least one state machine is required.")
+
+        state_graph: StateGraph = {}
+        end_states: list[StateId] = []
+        next_start_edges: list[Edge] = []
+        is_accepting = True
+        for index in reversed(range(len(state_machines))):
+            state_machine = state_machines[index]
+            prefix = f"{index}."
+            sub_end_states = set(state_machine.end_states)
+            if state_machine.is_optional:
+                sub_end_states.add(state_machine.start_state)
+
+            for state, edges in state_machine.state_graph.items():
+                state_graph[f"{prefix}{state}"] = [
+                    (edge, f"{prefix}{target}") for edge, target in edges
+                ]
+            for end_state in sub_end_states:
+                state_graph.setdefault(f"{prefix}{end_state}", []).extend(
+                    next_start_edges
+                )
+
+            if is_accepting:
+                end_states.extend(f"{prefix}{state}" for state in sub_end_states)
+            is_accepting = is_accepting and state_machine.is_optional
+            next_start_edges = list(
+                state_graph.get(f"{prefix}{state_machine.start_state}", [])
+            )
+
+        return StateMachine(
+            state_graph=state_graph,
+            start_state=f"0.{state_machines[0].start_state}",
+            end_states=end_states,
+            is_optional=is_accepting,
+        )
+
     def __str__(self) -> str:
         return "Chain"
 
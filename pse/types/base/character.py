@@ -20,6 +20,7 @@ class CharacterStateMachine(StateMachine):
         char_limit: int | None = None,
         is_optional: bool = False,
         case_sensitive: bool = True,
+        exact_length: int | None = None,
     ) -> None:
         """
         Initialize a CharacterStateMachine with character sets and constraints.
@@ -32,11 +33,16 @@ class CharacterStateMachine(StateMachine):
             char_limit: Maximum number of characters allowed (unlimited if 0 or None)
             is_optional: Whether this state machine is optional
             case_sensitive: Whether character matching is case-sensitive
+            exact_length: Exactly this many characters are required; overrides char_min and char_limit
         """
         super().__init__(
             is_optional=is_optional,
             is_case_sensitive=case_sensitive,
         )
+        if exact_length is not None:
+            if exact_length < 1:
+                raise ValueError("exact_length must be at least 1")
+            char_min = char_limit = exact_length
         self.char_min = char_min or 0
         self.char_limit = char_limit or 0
         self.charset: set[str] = set()
@@ -7,6 +7,8 @@ from pse_core import StateGraph
 from pse_core.state_machine import StateMachine
 from pse_core.stepper import Stepper
 
+from pse.types.base.phrase import PhraseStateMachine
+
 logger = logging.getLogger(__name__)
 
 
@@ -22,12 +24,21 @@ class LoopStateMachine(StateMachine):
         max_loop_count: int = -1,
         separator_state_machine: StateMachine | None = None,
         track_separator: bool = True,
+        stop_before: StateMachine | None = None,
+        greedy: bool | None = None,
     ) -> None:
         """
         Args:
             state_machine: State machine to be looped through
+            stop_before: Optional lookahead; once it matches the upcoming input
+                the loop terminates, leaving the match in `remaining_input`.
+            greedy: Where the loop could either repeat or stop, whether the engine
+                biases the model towards repeating (True) or stopping (False).
+                None leaves the choice to the model.
         """
         self.separator_state_machine = separator_state_machine
+        self.greedy = greedy
+        self.stop_before = stop_before
         self.track_separator = track_separator
         if self.separator_state_machine:
             state_graph: StateGraph = {
@@ -64,12 +75,58 @@ class LoopStepper(Stepper):
         super().__init__(loop_state_machine, *args, **kwargs)
         self.state_machine: LoopStateMachine = loop_state_machine
         self.loop_count = 0
+        self.is_stopped = False
 
     def clone(self) -> Self:
         clone = super().clone()
         clone.loop_count = self.loop_count
+        clone.is_stopped = self.is_stopped
         return clone
 
+    def find_stop_index(self, token: str) -> int | None:
+        """
+        Find the first index in the token where the stop_before lookahead matches.
+
+        The token is scanned once, with a lookahead started at every index and
+        dropped as soon as it cannot match.
+
+        Returns:
+            The index of the match, or None if there is no lookahead or no match.
+        """
+        stop_before = self.state_machine.stop_before
+        if stop_before is None:
+            return None
+
+        stop_index: int | None = None
+        # the lookaheads still matching, by the index they started at
+        lookaheads: list[tuple[int, list[Stepper]]] = []
+        for index, char in enumerate(token):
+            if stop_index is None:
+                lookaheads.append((index, stop_before.get_steppers()))
+
+            live_lookaheads: list[tuple[int, list[Stepper]]] = []
+            for start, steppers in lookaheads:
+                steppers = [
+                    stepper
+                    for stepper in stop_before.advance_all_basic(steppers, char)
+                    if not stepper.remaining_input
+                ]
+                if any(stepper.has_reached_accept_state() for stepper in steppers):
+                    stop_index = start if stop_index is None else min(stop_index, start)
+                elif steppers:
+                    live_lookaheads.append((start, steppers))
+
+            # only a lookahead that started earlier can still beat the match
+            lookaheads = [
+                (start, steppers)
+                for start, steppers in live_lookaheads
+                if stop_index is None or start < stop_index
+            ]
+            if stop_index is not None and not lookaheads:
+                break
+
+        return stop_index
+
     def should_branch(self) -> bool:
         return super().should_branch() and self.loop_count < self.state_machine.max_loop_count
 
@@ -97,9 +154,38 @@ class LoopStepper(Stepper):
         return True
 
     def consume(self, token: str) -> list[Stepper]:
-        new_steppers: list[Stepper] = []
-
-        def _validate_loop_stepper(stepper: LoopStepper) -> Stepper | None:
+        if self.is_stopped:
+            return []
+
+        stop_index = self.find_stop_index(token)
+        if stop_index is None:
+            return self._consume(token)
+
+        if stop_index == 0:
+            if not self.has_reached_accept_state():
+                return self._consume(token)
+            stopped_stepper = self.clone()
+            stopped_stepper.remaining_input = token
+            stopped_stepper.is_stopped = True
+            return [stopped_stepper]
+
+        stopped_steppers: list[Stepper] = []
+        for new_stepper in self._consume(token[:stop_index]):
+            if new_stepper.remaining_input or not new_stepper.has_reached_accept_state():
+                continue
+            new_stepper.remaining_input = token[stop_index:]
+            new_stepper.is_stopped = True
+            stopped_steppers.append(new_stepper)
+
+        return stopped_steppers
+
+    def _consume(self, token: str) -> list[LoopStepper]:
+        if (repeated_steppers := self._consume_repeats(token)) is not None:
+            return repeated_steppers
+
+        new_steppers: list[LoopStepper] = []
+
+        def _validate_loop_stepper(stepper: LoopStepper) -> LoopStepper | None:
             """
             Validate that the loop stepper respects the max loop count.
             """
@@ -128,8 +214,64 @@ class LoopStepper(Stepper):
 
         return new_steppers
 
+    def _consume_repeats(self, token: str) -> list[LoopStepper] | None:
+        """
+        Take a token that repeats the looped phrase, e.g. a run of spaces, in place.
+
+        The repetitions are counted on a single clone rather than branching into
+        and cloning a new stepper for each one. The loop may still stop after any
+        repetition, handing the rest of the token on, so those steppers are kept.
+
+        Returns:
+            The new steppers, or None if the token is not a run of the looped phrase.
+        """
+        state_machine = self.state_machine
+        phrase_state_machine = state_machine.state_graph[0][0][0]
+        if (
+            state_machine.separator_state_machine is not None
+            or not isinstance(phrase_state_machine, PhraseStateMachine)
+            or not phrase_state_machine.is_case_sensitive
+            or (self.sub_stepper is not None and self.sub_stepper.is_within_value())
+        ):
+            return None
+
+        phrase = phrase_state_machine.phrase
+        repeat_count, remainder = divmod(len(token), len(phrase))
+        if (
+            repeat_count < 2
+            or remainder
+            or token != phrase * repeat_count
+            or not self._is_below_max_loop_count(self.loop_count + repeat_count)
+        ):
+            return None
+
+        completed_phrase = phrase_state_machine.get_new_stepper().consume(phrase)[0]
+        stepper = self.clone()
+        new_steppers: list[LoopStepper] = []
+        for taken in range(repeat_count):
+            if taken and stepper.has_reached_accept_state():
+                stopped_stepper = stepper.clone()
+                stopped_stepper.remaining_input = token[taken * len(phrase) :]
+                stopped_stepper.is_stopped = True
+                new_steppers.append(stopped_stepper)
+
+            if stepper.sub_stepper is None:
+                edges = state_machine.state_graph[stepper.current_state or 0]
+                stepper.target_state = edges[0][1]
+            stepper.current_state = stepper.target_state
+            stepper.target_state = None
+            stepper.sub_stepper = None
+            stepper.add_to_history(completed_phrase)
+
+        new_steppers.append(stepper)
+        return new_steppers
+
+    def _is_below_max_loop_count(self, loop_count: int) -> bool:
+        max_loop_count = self.state_machine.max_loop_count
+        return max_loop_count < 0 or loop_count < max_loop_count
+
     def can_accept_more_input(self) -> bool:
-        if not super().can_accept_more_input():
+        if self.is_stopped or not super().can_accept_more_input():
             return False
 
         if self.state_machine.max_loop_count > 0:
@@ -138,7 +280,7 @@ class LoopStepper(Stepper):
         return True
 
     def should_start_step(self, token: str) -> bool:
-        if self.loop_count >= self.state_machine.max_loop_count:
+        if self.is_stopped or self.loop_count >= self.state_machine.max_loop_count:
             return False
 
         return super().should_start_step(token)
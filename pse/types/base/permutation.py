@@ -0,0 +1,83 @@
+from __future__ import annotations
+
+from pse_core import Edge, StateId
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
+
+
+class PermutationStateMachine(StateMachine):
+    """
+    Accepts each of several state machines exactly once, in any order.
+
+    Unlike a repeated choice, an alternative that has already been
+    accepted is never offered again.
+    """
+
+    def __init__(self, state_machines: list[StateMachine], is_optional: bool = False) -> None:
+        """
+        Args:
+            state_machines: The state machines to accept, one occurrence each.
+            is_optional: Whether the whole permutation may be omitted.
+        """
+        if not state_machines:
+            raise ValueError("At least one state machine is required.")
+
+        self.state_machines = state_machines
+        # each state is the bit set of the state machines accepted so far, so a
+        # branch's position is read off its target state rather than matched;
+        # there are 2^n such states, so edges are computed as states are reached
+        self.full_state = (1 << len(state_machines)) - 1
+        super().__init__(
+            end_states=[self.full_state],
+            is_optional=is_optional,
+        )
+
+    def get_edges(self, state: StateId) -> list[Edge]:
+        if not isinstance(state, int) or not 0 <= state < self.full_state:
+            return []
+        return [
+            (state_machine, state | 1 << index)
+            for index, state_machine in enumerate(self.state_machines)
+            if not state & 1 << index
+        ]
+
+    def get_new_stepper(self, state: StateId | None = None) -> PermutationStepper:
+        return PermutationStepper(self, state)
+
+    def __str__(self) -> str:
+        return "Permutation"
+
+
+class PermutationStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: PermutationStateMachine,
+        current_state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, current_state)
+        self.state_machine: PermutationStateMachine = state_machine
+        self.completed: list[int] = []
+
+    def clone(self) -> PermutationStepper:
+        cloned_stepper = super().clone()
+        cloned_stepper.completed = self.completed.copy()
+        return cloned_stepper
+
+    def add_to_history(self, stepper: Stepper) -> None:
+        # the branch's bit is set in its target state, whether or not it is current yet
+        state = 0
+        for accepted_state in (self.current_state, self.target_state):
+            if isinstance(accepted_state, int):
+                state |= accepted_state
+        self.completed.extend(
+            index
+            for index in range(len(self.state_machine.state_machines))
+            if state & 1 << index and index not in self.completed
+        )
+        super().add_to_history(stepper)
+
+    def get_completed_indices(self) -> list[int]:
+        """
+        The indices of the accepted state machines, in the order they were accepted.
+        """
+        return list(self.completed)
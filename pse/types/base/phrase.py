@@ -143,6 +143,10 @@ class PhraseStepper(Stepper):
         # Determine maximum possible match length
         max_length = min(len(token), len(remaining_phrase))
 
+        if not self.state_machine.is_case_sensitive:
+            token = token.lower()
+            remaining_phrase = remaining_phrase.lower()
+
         # Find the longest matching prefix using string slicing
         # This is more efficient than character-by-character comparison
         for i in range(max_length + 1):
@@ -0,0 +1,72 @@
+from __future__ import annotations
+
+from collections.abc import Callable, Iterable
+
+from pse_core import StateGraph, StateId
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
+
+from pse.types.base.phrase import PhraseStateMachine
+
+
+class TokenIdStateMachine(StateMachine):
+    """
+    Accepts exactly one token from an explicit set of token ids.
+
+    The engine masks logits straight to `allowed_ids` while a stepper is
+    waiting on this state machine, bypassing the vocabulary trie.
+    The decoded text of each id is still consumed, so it composes with text state machines.
+    """
+
+    def __init__(
+        self,
+        allowed_ids: Iterable[int],
+        decode: Callable[[list[int]], str],
+    ) -> None:
+        """
+        Args:
+            allowed_ids: The token ids that may be emitted.
+            decode: Decodes a list of token ids to text, e.g. `tokenizer.decode`.
+        """
+        self.allowed_ids: frozenset[int] = frozenset(allowed_ids)
+        if not self.allowed_ids:
+            raise ValueError("Allowed token ids must be provided.")
+
+        # lossy decodes may map several ids to the same text
+        self.token_texts: dict[str, set[int]] = {}
+        for token_id in self.allowed_ids:
+            text = decode([token_id])
+            if text:
+                self.token_texts.setdefault(text, set()).add(token_id)
+
+        if not self.token_texts:
+            raise ValueError("Allowed token ids must decode to non-empty text.")
+
+        state_graph: StateGraph = {
+            0: [(PhraseStateMachine(text), "$") for text in self.token_texts]
+        }
+        super().__init__(state_graph)
+
+    def get_new_stepper(self, state: StateId | None = None) -> TokenIdStepper:
+        return TokenIdStepper(self, state)
+
+    def __str__(self) -> str:
+        return "TokenId"
+
+
+class TokenIdStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: TokenIdStateMachine,
+        current_state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, current_state)
+        self.state_machine: TokenIdStateMachine = state_machine
+
+    def get_allowed_token_ids(self) -> frozenset[int] | None:
+        """
+        The token ids this stepper allows next, or None once it has started consuming.
+        """
+        if self.current_state != self.state_machine.start_state or self.get_raw_value():
+            return None
+        return self.state_machine.allowed_ids
@@ -0,0 +1,87 @@
+from __future__ import annotations
+
+from collections.abc import Iterable
+from typing import Any
+
+from pse_core import StateGraph, StateId
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
+
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+from pse.types.enum import EnumStateMachine
+from pse.types.json.json_number import NumberSchemaStateMachine
+
+HEX_DIGITS = "0123456789abcdefABCDEF"
+# the CSS level 1 color keywords
+BASIC_COLOR_NAMES = [
+    "black", "silver", "gray", "white", "maroon", "red", "purple", "fuchsia",
+    "green", "lime", "olive", "yellow", "navy", "blue", "teal", "aqua",
+]  # fmt: skip
+
+
+def color_channel() -> StateMachine:
+    """
+    An integer channel value from 0 to 255.
+    """
+    return NumberSchemaStateMachine({"type": "integer", "minimum": 0, "maximum": 255})
+
+
+class ColorStateMachine(StateMachine):
+    """
+    Accepts a color: `#RGB`, `#RRGGBB`, `rgb(r, g, b)`, or one of the named colors.
+
+    Each `rgb` channel must be an integer from 0 to 255.
+    """
+
+    def __init__(self, named_colors: Iterable[str] | None = None) -> None:
+        """
+        Args:
+            named_colors: Color names to accept as-is, e.g. `BASIC_COLOR_NAMES`.
+                No names are accepted by default.
+        """
+        self.named_colors = list(named_colors or [])
+        channel_separator = ChainStateMachine(
+            [
+                CharacterStateMachine(" ", is_optional=True),
+                PhraseStateMachine(","),
+                CharacterStateMachine(" ", is_optional=True),
+            ]
+        )
+        state_graph: StateGraph = {
+            0: [(PhraseStateMachine("#"), 1), (PhraseStateMachine("rgb("), 3)],
+            # #RGB, optionally extended to #RRGGBB
+            1: [(CharacterStateMachine(HEX_DIGITS, exact_length=3), 2)],
+            2: [(CharacterStateMachine(HEX_DIGITS, exact_length=3), "$")],
+            3: [(color_channel(), 4)],
+            4: [(channel_separator, 5)],
+            5: [(color_channel(), 6)],
+            6: [(channel_separator, 7)],
+            7: [(color_channel(), 8)],
+            8: [(PhraseStateMachine(")"), "$")],
+        }
+        if self.named_colors:
+            state_graph[0].append(
+                (EnumStateMachine(self.named_colors, require_quotes=False), "$")
+            )
+        super().__init__(state_graph, end_states=[2, "$"])
+
+    def get_new_stepper(self, state: StateId | None = None) -> ColorStepper:
+        return ColorStepper(self, state)
+
+    def __str__(self) -> str:
+        return "Color"
+
+
+class ColorStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: ColorStateMachine,
+        current_state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, current_state)
+        self.state_machine: ColorStateMachine = state_machine
+
+    def get_current_value(self) -> Any:
+        return self.get_raw_value() or None
@@ -0,0 +1,86 @@
+from __future__ import annotations
+
+from typing import Any
+
+from pse_core import StateGraph, StateId
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
+
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+from pse.types.json.json_number import NumberSchemaStateMachine
+
+# (minimum, maximum) per field; both 0 and 7 are Sunday
+CRON_FIELD_BOUNDS: list[tuple[int, int]] = [
+    (0, 59),  # minute
+    (0, 23),  # hour
+    (1, 31),  # day of month
+    (1, 12),  # month
+    (0, 7),  # day of week
+]
+
+
+def bounded_integer(minimum: int, maximum: int) -> StateMachine:
+    return NumberSchemaStateMachine(
+        {"type": "integer", "minimum": minimum, "maximum": maximum}
+    )
+
+
+def cron_field(minimum: int, maximum: int) -> StateMachine:
+    """
+    One cron field: a comma-separated list of `*`, values or ranges, each with an optional `/step`.
+
+    Values must lie within the field's bounds; steps must be at least 1.
+    """
+    state_graph: StateGraph = {
+        0: [
+            (PhraseStateMachine("*"), 1),
+            (bounded_integer(minimum, maximum), 2),
+        ],
+        1: [(PhraseStateMachine("/"), 4), (PhraseStateMachine(","), 0)],
+        # after a single value, which may start a range
+        2: [
+            (PhraseStateMachine("-"), 3),
+            (PhraseStateMachine("/"), 4),
+            (PhraseStateMachine(","), 0),
+        ],
+        3: [(bounded_integer(minimum, maximum), 1)],
+        4: [(bounded_integer(1, maximum), 5)],
+        5: [(PhraseStateMachine(","), 0)],
+    }
+    return StateMachine(state_graph, end_states=[1, 2, 5])
+
+
+class CronStateMachine(StateMachine):
+    """
+    Accepts a standard 5-field cron expression: minute, hour, day of month, month
+    and day of week, separated by whitespace, e.g. `*/5 0 * * 1-5`.
+    """
+
+    def __init__(self) -> None:
+        state_machines: list[StateMachine] = []
+        for minimum, maximum in CRON_FIELD_BOUNDS:
+            if state_machines:
+                state_machines.append(CharacterStateMachine(" \t", char_min=1))
+            state_machines.append(cron_field(minimum, maximum))
+        super().__init__({0: [(ChainStateMachine(state_machines), "$")]})
+
+    def get_new_stepper(self, state: StateId | None = None) -> CronStepper:
+        return CronStepper(self, state)
+
+    def __str__(self) -> str:
+        return "Cron"
+
+
+class CronStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: CronStateMachine,
+        current_state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, current_state)
+        self.state_machine: CronStateMachine = state_machine
+
+    def get_current_value(self) -> Any:
+        return self.get_raw_value() or None
@@ -0,0 +1,136 @@
+from __future__ import annotations
+
+import string
+from typing import Any
+
+from pse_core import StateGraph, StateId
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
+
+from pse.types.base.any import AnyStateMachine
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.loop import LoopStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+
+DEFAULT_CURRENCIES = ["$", "€", "£", "¥", "USD", "EUR", "GBP", "JPY"]
+# (thousands separator, decimal separator) per locale
+LOCALE_SEPARATORS: dict[str, tuple[str, str]] = {
+    "en_US": (",", "."),
+    "en_GB": (",", "."),
+    "de_DE": (".", ","),
+    "fr_FR": (" ", ","),
+    "de_CH": ("'", "."),
+}
+
+
+class CurrencyStateMachine(StateMachine):
+    """
+    Accepts a currency amount, e.g. `$1,234.56` or `EUR 12.00`: an optional
+    currency symbol or code, a whole number with optional thousands separators,
+    and a fraction with exactly `decimal_places` digits.
+
+    Thousands separators, when used, must group every three digits.
+    """
+
+    def __init__(
+        self,
+        currencies: list[str] | None = None,
+        decimal_places: int = 2,
+        thousands_separator: str = ",",
+        decimal_separator: str = ".",
+    ) -> None:
+        """
+        Args:
+            currencies: The symbols and codes that may precede the amount,
+                optionally followed by a space. Defaults to common symbols and ISO codes.
+            decimal_places: The exact number of digits after the decimal separator;
+                0 for whole amounts only.
+            thousands_separator: The separator between groups of three digits.
+            decimal_separator: The separator before the fraction.
+        """
+        if decimal_places < 0:
+            raise ValueError("decimal_places must be at least 0")
+        if thousands_separator == decimal_separator:
+            raise ValueError("The thousands and decimal separators must differ.")
+
+        self.currencies = currencies or DEFAULT_CURRENCIES
+        self.decimal_places = decimal_places
+        self.thousands_separator = thousands_separator
+        self.decimal_separator = decimal_separator
+        currency = ChainStateMachine(
+            [
+                AnyStateMachine([PhraseStateMachine(currency) for currency in self.currencies]),
+                CharacterStateMachine(" ", char_limit=1, is_optional=True),
+            ],
+            is_optional=True,
+        )
+        ungrouped = CharacterStateMachine(string.digits, char_min=1)
+        grouped = ChainStateMachine(
+            [
+                CharacterStateMachine(string.digits, char_min=1, char_limit=3),
+                LoopStateMachine(
+                    ChainStateMachine(
+                        [
+                            PhraseStateMachine(thousands_separator),
+                            CharacterStateMachine(string.digits, exact_length=3),
+                        ]
+                    )
+                ),
+            ]
+        )
+        state_graph: StateGraph = {
+            0: [(currency, 1)],
+            1: [(AnyStateMachine([ungrouped, grouped]), 2)],
+        }
+        end_states: list[StateId] = [2]
+        if decimal_places:
+            fraction = ChainStateMachine(
+                [
+                    PhraseStateMachine(decimal_separator),
+                    CharacterStateMachine(string.digits, exact_length=decimal_places),
+                ]
+            )
+            state_graph[2] = [(fraction, "$")]
+            end_states = ["$"]
+        super().__init__(state_graph, end_states=end_states)
+
+    @classmethod
+    def for_locale(cls, locale: str, **kwargs: Any) -> CurrencyStateMachine:
+        """
+        Use the separators of a locale in `LOCALE_SEPARATORS`, e.g. "de_DE" for `1.234,56`.
+
+        Any other keyword arguments are passed on to the constructor.
+
+        Raises:
+            ValueError: If the locale is not known.
+        """
+        if locale not in LOCALE_SEPARATORS:
+            raise ValueError(
+                f"Unknown locale: {locale}. Known locales: {', '.join(LOCALE_SEPARATORS)}"
+            )
+        thousands_separator, decimal_separator = LOCALE_SEPARATORS[locale]
+        return cls(
+            thousands_separator=thousands_separator,
+            decimal_separator=decimal_separator,
+            **kwargs,
+        )
+
+    def get_new_stepper(self, state: StateId | None = None) -> CurrencyStepper:
+        return CurrencyStepper(self, state)
+
+    def __str__(self) -> str:
+        return "Currency"
+
+
+class CurrencyStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: CurrencyStateMachine,
+        current_state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, current_state)
+        self.state_machine: CurrencyStateMachine = state_machine
+
+    def get_current_value(self) -> Any:
+        return self.get_raw_value() or None
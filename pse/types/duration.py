@@ -0,0 +1,78 @@
+from __future__ import annotations
+
+from typing import Any
+
+from pse_core import StateGraph, StateId
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
+
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+
+DIGITS = "0123456789"
+
+
+def component(designator: str, allow_fraction: bool = False) -> StateMachine:
+    """
+    A duration component: a number followed by its designator, e.g. `10D`.
+    """
+    if not allow_fraction:
+        return ChainStateMachine(
+            [CharacterStateMachine(DIGITS, char_min=1), PhraseStateMachine(designator)]
+        )
+
+    return StateMachine(
+        {
+            0: [(CharacterStateMachine(DIGITS, char_min=1), 1)],
+            1: [(PhraseStateMachine("."), 2), (PhraseStateMachine(designator), "$")],
+            2: [(CharacterStateMachine(DIGITS, char_min=1), 3)],
+            3: [(PhraseStateMachine(designator), "$")],
+        }
+    )
+
+
+class DurationStateMachine(StateMachine):
+    """
+    Accepts an ISO-8601 duration: `P[n]Y[n]M[n]DT[n]H[n]M[n]S`.
+
+    Components must appear in order and at least one is required, both
+    overall and after `T`; only the seconds may have a fraction.
+    """
+
+    def __init__(self) -> None:
+        years, months, days = component("Y"), component("M"), component("D")
+        time_separator = PhraseStateMachine("T")
+        hours, minutes, seconds = component("H"), component("M"), component("S", True)
+        state_graph: StateGraph = {
+            0: [(PhraseStateMachine("P"), 1)],
+            # date components, each state remembering the last one seen
+            1: [(years, 2), (months, 3), (days, 4), (time_separator, 5)],
+            2: [(months, 3), (days, 4), (time_separator, 5)],
+            3: [(days, 4), (time_separator, 5)],
+            4: [(time_separator, 5)],
+            # time components
+            5: [(hours, 6), (minutes, 7), (seconds, 8)],
+            6: [(minutes, 7), (seconds, 8)],
+            7: [(seconds, 8)],
+        }
+        super().__init__(state_graph, end_states=[2, 3, 4, 6, 7, 8])
+
+    def get_new_stepper(self, state: StateId | None = None) -> DurationStepper:
+        return DurationStepper(self, state)
+
+    def __str__(self) -> str:
+        return "Duration"
+
+
+class DurationStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: DurationStateMachine,
+        current_state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, current_state)
+        self.state_machine: DurationStateMachine = state_machine
+
+    def get_current_value(self) -> Any:
+        return self.get_raw_value() or None
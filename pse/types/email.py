@@ -0,0 +1,66 @@
+from __future__ import annotations
+
+import string
+from typing import Any
+
+from pse_core import StateGraph, StateId
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
+
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+
+# RFC 5322 atom characters, without the quoted-string and comment forms
+LOCAL_CHARS = string.ascii_letters + string.digits + "!#$%&'*+/=?^_`{|}~-"
+LABEL_CHARS = string.ascii_letters + string.digits
+
+
+def domain_label() -> StateMachine:
+    """
+    A domain label: letters and digits, with hyphens only between them.
+    """
+    state_graph: StateGraph = {
+        0: [(CharacterStateMachine(LABEL_CHARS, char_min=1), 1)],
+        1: [(CharacterStateMachine("-", char_min=1), 0)],
+    }
+    return StateMachine(state_graph, end_states=[1])
+
+
+class EmailStateMachine(StateMachine):
+    """
+    Accepts a pragmatic subset of RFC 5322 email addresses, e.g. `a.b@example.com`.
+
+    The local part is dot-separated atoms, with no leading, trailing or
+    doubled dots. The domain is at least two dot-separated labels.
+    """
+
+    def __init__(self) -> None:
+        dot = PhraseStateMachine(".")
+        state_graph: StateGraph = {
+            0: [(CharacterStateMachine(LOCAL_CHARS, char_min=1), 1)],
+            1: [(dot, 0), (PhraseStateMachine("@"), 2)],
+            2: [(domain_label(), 3)],
+            3: [(dot, 4)],
+            4: [(domain_label(), 5)],
+            5: [(dot, 4)],
+        }
+        super().__init__(state_graph, end_states=[5])
+
+    def get_new_stepper(self, state: StateId | None = None) -> EmailStepper:
+        return EmailStepper(self, state)
+
+    def __str__(self) -> str:
+        return "Email"
+
+
+class EmailStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: EmailStateMachine,
+        current_state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, current_state)
+        self.state_machine: EmailStateMachine = state_machine
+
+    def get_current_value(self) -> Any:
+        return self.get_raw_value() or None
@@ -1,5 +1,8 @@
 from __future__ import annotations
 
+import json
+from typing import Any
+
 from pse_core import StateGraph, StateId
 from pse_core.state_machine import StateMachine
 from pse_core.stepper import Stepper
@@ -10,27 +13,43 @@ from pse.types.base.phrase import PhraseStateMachine
 
 class EnumStateMachine(StateMachine):
     """
-    Accept one of several constant strings.
+    Accept one of several constant values.
+
+    String values are (optionally) quoted; numbers, booleans and null
+    are accepted as their bare JSON serialization.
+    With `case_insensitive`, string values match in any casing
+    but the value is always the canonical one from `enum_values`.
     """
 
-    def __init__(self, enum_values: list[str], require_quotes: bool = True) -> None:
+    def __init__(
+        self,
+        enum_values: list[Any],
+        require_quotes: bool = True,
+        case_insensitive: bool = False,
+    ) -> None:
         if not enum_values:
             raise ValueError("Enum values must be provided.")
 
         state_graph: StateGraph = {0: []}
-        unique_enum_values = list(set(enum_values))
-        for value in unique_enum_values:
-            sm = (
-                PhraseStateMachine(value)
-                if not require_quotes
-                else ChainStateMachine(
+        # dedupe on the serialized form, since True == 1 in Python
+        unique_enum_values: dict[str, Any] = {}
+        for value in enum_values:
+            key = json.dumps(value)
+            unique_enum_values.setdefault(key, value)
+
+        for key, value in unique_enum_values.items():
+            if not isinstance(value, str):
+                sm = PhraseStateMachine(key)
+            elif not require_quotes:
+                sm = PhraseStateMachine(value, is_case_sensitive=not case_insensitive)
+            else:
+                sm = ChainStateMachine(
                     [
                         PhraseStateMachine('"'),
-                        PhraseStateMachine(value),
+                        PhraseStateMachine(value, is_case_sensitive=not case_insensitive),
                         PhraseStateMachine('"'),
                     ]
                 )
-            )
             state_graph[0].append((sm, "$"))
 
         super().__init__(state_graph)
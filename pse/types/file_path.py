@@ -0,0 +1,105 @@
+from __future__ import annotations
+
+import string
+from enum import Enum
+from typing import Any
+
+from pse_core import StateGraph, StateId
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
+
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+
+CONTROL_CHARS = "".join(chr(c) for c in range(0x20))
+
+
+class PathStyle(Enum):
+    """
+    Which operating system's path syntax is accepted.
+
+    UNIX: `/` separates segments; absolute paths start with `/`.
+    WINDOWS: `\\` or `/` separates segments; absolute paths start with a drive, e.g. `C:\\`.
+    """
+
+    UNIX = "unix"
+    WINDOWS = "windows"
+
+
+# characters never allowed within a segment; `:` is excluded on Unix for portability
+FORBIDDEN_SEGMENT_CHARS: dict[PathStyle, str] = {
+    PathStyle.UNIX: "/:" + CONTROL_CHARS,
+    PathStyle.WINDOWS: '\\/<>:"|?*' + CONTROL_CHARS,
+}
+SEPARATORS: dict[PathStyle, str] = {
+    PathStyle.UNIX: "/",
+    PathStyle.WINDOWS: "\\/",
+}
+
+
+class FilePathStateMachine(StateMachine):
+    """
+    Accepts a file path: segments joined by the style's separators.
+
+    Empty segments (`a//b`) are rejected; a trailing separator is allowed.
+    """
+
+    def __init__(
+        self,
+        style: PathStyle = PathStyle.UNIX,
+        absolute: bool | None = None,
+    ) -> None:
+        """
+        Args:
+            style: The operating system whose path syntax is accepted.
+            absolute: True to require an absolute path, False to require a
+                relative one, None to accept either.
+        """
+        self.style = style
+        self.absolute = absolute
+        separator = CharacterStateMachine(SEPARATORS[style], exact_length=1)
+        segment = CharacterStateMachine(
+            blacklist_charset=FORBIDDEN_SEGMENT_CHARS[style], char_min=1
+        )
+        if style == PathStyle.WINDOWS:
+            root = ChainStateMachine(
+                [
+                    CharacterStateMachine(string.ascii_letters, exact_length=1),
+                    PhraseStateMachine(":"),
+                    separator,
+                ]
+            )
+        else:
+            root = separator
+
+        state_graph: StateGraph = {
+            0: [],
+            # 1: after a separator, 2: after a segment
+            1: [(segment, 2)],
+            2: [(separator, 1)],
+        }
+        if absolute is not False:
+            state_graph[0].append((root, 1))
+        if absolute is not True:
+            state_graph[0].append((segment, 2))
+        super().__init__(state_graph, end_states=[1, 2])
+
+    def get_new_stepper(self, state: StateId | None = None) -> FilePathStepper:
+        return FilePathStepper(self, state)
+
+    def __str__(self) -> str:
+        return "FilePath"
+
+
+class FilePathStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: FilePathStateMachine,
+        current_state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, current_state)
+        self.state_machine: FilePathStateMachine = state_machine
+
+    def get_current_value(self) -> Any:
+        return self.get_raw_value() or None
@@ -73,7 +73,9 @@ class LarkGrammarStepper(CharacterStepper):
             Returns empty list if no valid transitions are possible.
         """
         valid_input, remaining_input = self.get_valid_prefix(token)
-        assert valid_input is not None
+        if valid_input is None:
+            return []
+
         return [
             self.step(
                 self.get_raw_value() + valid_input,
@@ -0,0 +1,124 @@
+from __future__ import annotations
+
+import keyword
+import string
+from dataclasses import dataclass
+
+from pse_core.stepper import Stepper
+
+from pse.types.base.character import (
+    CharacterStateMachine,
+    CharacterStepper,
+)
+
+
+@dataclass(frozen=True)
+class IdentifierRules:
+    """
+    The identifier rules of a programming language.
+
+    Attributes:
+        first_charset: Characters an identifier may start with.
+        charset: Characters allowed anywhere in an identifier.
+        keywords: Reserved words that are not valid identifiers.
+    """
+
+    first_charset: str
+    charset: str
+    keywords: frozenset[str]
+
+
+_ASCII_IDENTIFIER_START = string.ascii_letters + "_"
+_ASCII_IDENTIFIER = _ASCII_IDENTIFIER_START + string.digits
+
+IDENTIFIER_RULES: dict[str, IdentifierRules] = {
+    "python": IdentifierRules(
+        _ASCII_IDENTIFIER_START,
+        _ASCII_IDENTIFIER,
+        frozenset(keyword.kwlist),
+    ),
+    "rust": IdentifierRules(
+        _ASCII_IDENTIFIER_START,
+        _ASCII_IDENTIFIER,
+        frozenset(
+            [
+                # strict keywords
+                "as", "async", "await", "break", "const", "continue", "crate",
+                "dyn", "else", "enum", "extern", "false", "fn", "for", "if",
+                "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+                "pub", "ref", "return", "self", "Self", "static", "struct",
+                "super", "trait", "true", "type", "unsafe", "use", "where",
+                "while",
+                # reserved keywords
+                "abstract", "become", "box", "do", "final", "macro",
+                "override", "priv", "try", "typeof", "unsized", "virtual",
+                "yield",
+                # not an identifier on its own
+                "_",
+            ]
+        ),
+    ),
+}
+
+
+class IdentifierStateMachine(CharacterStateMachine):
+    """
+    Accepts an identifier of a programming language.
+
+    The first character must be in the language's start charset, the rest in its
+    identifier charset, and the identifier cannot be a reserved keyword.
+    """
+
+    def __init__(
+        self,
+        language: str = "python",
+        rules: IdentifierRules | None = None,
+    ) -> None:
+        """
+        Args:
+            language: The language whose rules to use, a key of `IDENTIFIER_RULES`.
+            rules: Custom rules; overrides `language` when given.
+        """
+        if rules is None:
+            if language not in IDENTIFIER_RULES:
+                raise ValueError(f"No identifier rules for language: {language}")
+            rules = IDENTIFIER_RULES[language]
+
+        super().__init__(rules.charset, char_min=1)
+        self.language = language
+        self.rules = rules
+
+    def get_new_stepper(self, state: int | str) -> IdentifierStepper:
+        return IdentifierStepper(self)
+
+    def __str__(self) -> str:
+        return "Identifier"
+
+
+class IdentifierStepper(CharacterStepper):
+    def __init__(
+        self, state_machine: IdentifierStateMachine, value: str | None = None
+    ) -> None:
+        super().__init__(state_machine, value)
+        self.state_machine: IdentifierStateMachine = state_machine
+
+    def get_valid_continuations(self, depth: int = 0) -> list[str]:
+        if not self.get_raw_value():
+            return list(self.state_machine.rules.first_charset)
+        return super().get_valid_continuations(depth)
+
+    def should_start_step(self, token: str) -> bool:
+        if not self.get_raw_value() and token[:1] not in self.state_machine.rules.first_charset:
+            return False
+        return super().should_start_step(token)
+
+    def consume(self, token: str) -> list[Stepper]:
+        if not self.should_start_step(token):
+            return []
+        return super().consume(token)
+
+    def has_reached_accept_state(self) -> bool:
+        return (
+            super().has_reached_accept_state()
+            and self.get_raw_value() not in self.state_machine.rules.keywords
+        )
@@ -1,3 +1,4 @@
+import re
 from collections.abc import Callable, Sequence
 from typing import Any, TypeAlias
 
@@ -10,12 +11,23 @@ from pse.types.base.encapsulated import EncapsulatedStateMachine
 from pse.types.base.phrase import PhraseStateMachine
 from pse.types.base.wait_for import WaitFor
 from pse.types.boolean import BooleanStateMachine
+from pse.types.duration import DurationStateMachine
+from pse.types.email import EmailStateMachine
 from pse.types.enum import EnumStateMachine
 from pse.types.json.any_json_schema import AnySchemaStateMachine
 from pse.types.json.json_number import NumberSchemaStateMachine
-from pse.types.json.json_string import StringSchemaStateMachine
+from pse.types.json.json_string import (
+    FormatStringStateMachine,
+    StringSchemaStateMachine,
+)
 from pse.types.json.json_value import JsonStateMachine
+from pse.types.json_pointer import JsonPointerStateMachine
+from pse.types.number import CanonicalForm
 from pse.types.object import ObjectStateMachine
+from pse.types.uri import UriStateMachine
+from pse.types.whitespace import DEFAULT_MAX_WHITESPACE
+from pse.util.errors import PseGrammarError
+from pse.util.names import set_name
 
 SchemaDefinition: TypeAlias = (
     type[BaseModel] | dict[str, Any] | Callable[..., Any] | str
@@ -25,14 +37,50 @@ JSONSchemaSource: TypeAlias = SchemaDefinition | Sequence[SchemaDefinition]
 The different object types that can be used as a schema in the structuring engine.
 """
 
+# string formats with a grammar of their own, compiled in place of a plain string
+STRING_FORMAT_STATE_MACHINES: dict[str, Callable[[dict[str, Any]], StateMachine]] = {
+    "duration": lambda _: DurationStateMachine(),
+    "email": lambda _: EmailStateMachine(),
+    "uri": lambda _: UriStateMachine(),
+    # the empty pointer refers to the whole document
+    "json-pointer": lambda schema: JsonPointerStateMachine(
+        is_optional=_allows_empty_string(schema)
+    ),
+}
+STRING_CONSTRAINT_KEYWORDS = frozenset({"minLength", "maxLength", "pattern"})
+
 
 def json_schema_state_machine(
     schema: JSONSchemaSource,
     delimiters: tuple[str, str] | None = None,
     buffer_length: int = -1,
+    max_whitespace: int = DEFAULT_MAX_WHITESPACE,
+    emit_defaults: bool = False,
+    exact_decimal: bool = False,
+    canonical_form: CanonicalForm = CanonicalForm.ANY,
+    max_significant_figures: int | None = None,
 ) -> tuple[dict[str, Any], StateMachine]:
+    """
+    Compile a JSON schema into a state machine.
+
+    With `emit_defaults`, required properties that have a `default` stay required
+    instead of becoming optional, so the engine can fill them in when the model
+    closes their object early. With `exact_decimal`, numbers are parsed as `Decimal`,
+    `canonical_form` restricts which spelling of a number is accepted, and
+    `max_significant_figures` caps the digits of every number's mantissa.
+    """
     json_schema = _generate_json_schema(schema)
-    json_state_machine = _json_schema_to_state_machine(json_schema)
+    validate_references(json_schema)
+    context = {
+        "defs": {"#": json_schema},
+        "path": "",
+        "max_whitespace": max_whitespace,
+        "emit_defaults": emit_defaults,
+        "exact_decimal": exact_decimal,
+        "canonical_form": canonical_form,
+        "max_significant_figures": max_significant_figures,
+    }
+    json_state_machine = _json_schema_to_state_machine(json_schema, context)
     if delimiters:
         return (
             json_schema,
@@ -55,6 +103,98 @@ def json_schema_state_machine(
     return (json_schema, json_state_machine)
 
 
+def tool_call_state_machine(
+    name: str,
+    params_schema: SchemaDefinition,
+    max_whitespace: int = DEFAULT_MAX_WHITESPACE,
+) -> tuple[dict[str, Any], StateMachine]:
+    """
+    Build a state machine for an OpenAI-style tool call.
+
+    Accepts `{"name": "<name>", "arguments": <params>}`, with the name fixed
+    and the arguments constrained by the tool's parameter schema.
+
+    Args:
+        name: The name of the tool being called.
+        params_schema: The schema of the tool's arguments.
+        max_whitespace: Maximum optional whitespace between tokens.
+
+    Returns:
+        The tool call's JSON schema and its state machine.
+    """
+    tool_call_schema = {
+        "type": "object",
+        "properties": {
+            "name": {"const": name},
+            "arguments": _generate_json_schema(params_schema),
+        },
+        "required": ["name", "arguments"],
+        "additionalProperties": False,
+    }
+    return json_schema_state_machine(tool_call_schema, max_whitespace=max_whitespace)
+
+
+def example_state_machine(
+    example: str | Any,
+    max_whitespace: int = DEFAULT_MAX_WHITESPACE,
+) -> tuple[dict[str, Any], StateMachine]:
+    """
+    Build a state machine for documents shaped like an example.
+
+    Args:
+        example: The example document, as JSON text or a parsed value.
+        max_whitespace: Maximum optional whitespace between tokens.
+
+    Returns:
+        The inferred JSON schema and its state machine.
+    """
+    from pse.types.json.schema_sources.from_example import example_to_schema
+
+    return json_schema_state_machine(
+        example_to_schema(example), max_whitespace=max_whitespace
+    )
+
+
+def validate_references(schema: dict[str, Any]) -> None:
+    """
+    Check that every "$ref" in the schema resolves before anything is compiled.
+
+    Property schemas are compiled lazily, so a dangling reference would
+    otherwise only surface mid-generation.
+
+    Raises:
+        PseGrammarError: Naming the first reference that cannot be resolved.
+    """
+    references: list[str] = []
+    definition_names: set[str] = set()
+
+    def collect(node: Any) -> None:
+        if isinstance(node, list):
+            for item in node:
+                collect(item)
+            return
+        if not isinstance(node, dict):
+            return
+
+        if isinstance(node.get("$ref"), str):
+            references.append(node["$ref"])
+        if isinstance(node.get("$defs"), dict):
+            definition_names.update(node["$defs"])
+        for key, value in node.items():
+            # literal values are data, not schemas
+            if key not in ("const", "enum", "default", "examples"):
+                collect(value)
+
+    collect(schema)
+    for reference in references:
+        if reference == "#":
+            continue
+        name = reference.rsplit("/", 1)[-1]
+        if reference.startswith("#/$defs/") and name in definition_names:
+            continue
+        raise PseGrammarError(f"definition not found: {reference}", reference)
+
+
 def _generate_json_schema(source: JSONSchemaSource) -> dict[str, Any]:
     """
     Convert the given schema into an object that can be used by the engine.
@@ -135,7 +275,7 @@ def _json_schema_to_state_machine(
         return AnySchemaStateMachine(merged_schemas, context)
 
     if not schema_type:
-        if "properties" in schema:
+        if "properties" in schema or "patternProperties" in schema:
             schema_type = "object"
         elif "items" in schema:
             schema_type = "array"
@@ -147,7 +287,12 @@ def _json_schema_to_state_machine(
     elif schema_type == "null":
         state_machine = PhraseStateMachine("null", is_optional=True)
     elif schema_type in ["number", "integer"]:
-        state_machine = NumberSchemaStateMachine(schema)
+        state_machine = NumberSchemaStateMachine(
+            schema,
+            exact_decimal=context.get("exact_decimal", False),
+            canonical_form=context.get("canonical_form", CanonicalForm.ANY),
+            max_significant_figures=context.get("max_significant_figures"),
+        )
     elif schema_type == "string" or "enum" in schema or "const" in schema:
         if "enum" in schema:
             state_machine = EnumStateMachine(schema["enum"])
@@ -159,9 +304,19 @@ def _json_schema_to_state_machine(
                     PhraseStateMachine('"'),
                 ]
             )
+        elif schema.get("format") in STRING_FORMAT_STATE_MACHINES:
+            state_machine = ChainStateMachine(
+                [
+                    PhraseStateMachine('"'),
+                    _string_format_state_machine(schema),
+                    PhraseStateMachine('"'),
+                ]
+            )
         else:
             state_machine = StringSchemaStateMachine(schema)
-    elif schema_type == "object" and "properties" in schema:
+    elif schema_type == "object" and (
+        "properties" in schema or "patternProperties" in schema
+    ):
         state_machine = ObjectSchemaStateMachine(schema, context)
     elif schema_type == "array" and "items" in schema:
         state_machine = ArraySchemaStateMachine(schema, context)
@@ -169,15 +324,42 @@ def _json_schema_to_state_machine(
         schema["uniqueItems"] = True
         state_machine = ArraySchemaStateMachine(schema, context)
     elif schema_type == "array" or schema_type == "tuple":
-        state_machine = ArrayStateMachine()
+        state_machine = ArrayStateMachine(
+            max_whitespace=context.get("max_whitespace", DEFAULT_MAX_WHITESPACE)
+        )
     elif schema_type == "object":
-        state_machine = ObjectStateMachine()
+        state_machine = ObjectStateMachine(
+            max_whitespace=context.get("max_whitespace", DEFAULT_MAX_WHITESPACE)
+        )
     else:
         state_machine = JsonStateMachine()
 
+    return set_name(state_machine, schema.get("title"))
+
+
+def _string_format_state_machine(schema: dict[str, Any]) -> StateMachine:
+    """
+    Compile the grammar of a string format, held to any length and pattern constraints.
+    """
+    state_machine = STRING_FORMAT_STATE_MACHINES[schema["format"]](schema)
+    if STRING_CONSTRAINT_KEYWORDS & schema.keys():
+        return FormatStringStateMachine(state_machine, schema)
     return state_machine
 
 
+def _allows_empty_string(schema: dict[str, Any]) -> bool:
+    """
+    Whether the schema's length and pattern constraints allow an empty string.
+    """
+    if schema.get("minLength"):
+        return False
+    try:
+        return "pattern" not in schema or re.match(schema["pattern"], "") is not None
+    except re.error:
+        # reported when the constraints are compiled
+        return False
+
+
 def process_json_schema(
     schema: dict[str, Any] | None,
     definitions: dict[str, dict[str, Any]],
@@ -205,7 +387,9 @@ def process_json_schema(
             visited[schema_reference] = []
 
         if schema_reference not in definitions:
-            raise ValueError(f"definition not found: {schema_reference}")
+            raise PseGrammarError(
+                f"definition not found: {schema_reference}", schema_reference
+            )
 
         resolved = process_json_schema(
             definitions.get(schema_reference), definitions, visited
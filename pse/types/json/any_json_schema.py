@@ -4,6 +4,8 @@ from pse_core import StateId
 from pse_core.state_machine import StateMachine
 from pse_core.stepper import Stepper
 
+from pse.types.base.any import get_shared_prefix_state_machine
+
 
 class AnySchemaStateMachine(StateMachine):
     """
@@ -27,11 +29,23 @@ class AnySchemaStateMachine(StateMachine):
             sm = _json_schema_to_state_machine(schema, context)
             self.state_machines.append(sm)
 
+        # schemas compiling to chains with the same leading links share one path
+        self.merged = get_shared_prefix_state_machine(self.state_machines)
+        if self.merged is not None:
+            super().__init__(
+                state_graph=self.merged.state_graph,
+                end_states=self.merged.end_states,
+                is_optional=self.merged.is_optional,
+            )
+            return
+
         super().__init__(
             {0: [(state_machine, "$") for state_machine in self.state_machines]}
         )
 
     def get_steppers(self, state: StateId | None = None) -> list[Stepper]:
+        if self.merged is not None:
+            return super().get_steppers(state)
         steppers = []
         for edge, _ in self.get_edges(state or 0):
             steppers.extend(edge.get_steppers())
@@ -3,49 +3,55 @@ from __future__ import annotations
 from typing import Any
 
 from pse_core import StateId
+from pse_core.state_machine import StateMachine
 from pse_core.stepper import Stepper
 
 from pse.types.array import ArrayStateMachine, ArrayStepper
 from pse.types.base.chain import ChainStateMachine
 from pse.types.base.phrase import PhraseStateMachine
 from pse.types.json import _json_schema_to_state_machine
-from pse.types.whitespace import WhitespaceStateMachine
+from pse.types.whitespace import DEFAULT_MAX_WHITESPACE, WhitespaceStateMachine
 
 
 class ArraySchemaStateMachine(ArrayStateMachine):
     def __init__(self, schema: dict[str, Any], context: dict[str, Any]) -> None:
         self.schema = schema
         self.context = context
+        self.item_state_machine = _json_schema_to_state_machine(
+            self.schema["items"], self.context
+        )
+        # item state machines restricted to the keys of a first item, by those keys
+        self._keyed_item_state_machines: dict[tuple[str, ...], StateMachine] = {}
+        max_whitespace = context.get("max_whitespace", DEFAULT_MAX_WHITESPACE)
         super().__init__(
             {
                 0: [
                     (PhraseStateMachine("["), 1),
                 ],
                 1: [
-                    (WhitespaceStateMachine(), 2),
+                    (WhitespaceStateMachine(max_whitespace=max_whitespace), 2),
                     (PhraseStateMachine("]"), "$"),
                 ],
                 2: [
-                    (
-                        _json_schema_to_state_machine(
-                            self.schema["items"], self.context
-                        ),
-                        3,
-                    ),
+                    (self.item_state_machine, 3),
                 ],
                 3: [
-                    (WhitespaceStateMachine(), 4),
+                    (WhitespaceStateMachine(max_whitespace=max_whitespace), 4),
                 ],
                 4: [
                     (
                         ChainStateMachine(
-                            [PhraseStateMachine(","), WhitespaceStateMachine()]
+                            [
+                                PhraseStateMachine(","),
+                                WhitespaceStateMachine(max_whitespace=max_whitespace),
+                            ]
                         ),
                         2,
                     ),
                     (PhraseStateMachine("]"), "$"),
                 ],
-            }
+            },
+            max_whitespace=max_whitespace,
         )
 
     def get_transitions(self, stepper: Stepper) -> list[tuple[Stepper, StateId]]:
@@ -67,14 +73,28 @@ class ArraySchemaStateMachine(ArrayStateMachine):
 
             if len(stepper.get_current_value()) < self.max_items():
                 for transition in ChainStateMachine(
-                    [PhraseStateMachine(","), WhitespaceStateMachine()]
+                    [
+                        PhraseStateMachine(","),
+                        WhitespaceStateMachine(max_whitespace=self.max_whitespace),
+                    ]
                 ).get_steppers():
                     transitions.append((transition, 2))
 
             return transitions
+        elif (
+            stepper.current_state == 2
+            and self.consistent_keys()
+            and stepper.get_current_value()
+        ):
+            item_state_machine = self.get_keyed_item_state_machine(
+                stepper.get_current_value()[0]
+            )
+            return [(transition, 3) for transition in item_state_machine.get_steppers()]
         elif stepper.current_state == 1 and self.min_items() > 0:
             transitions = []
-            for transition in WhitespaceStateMachine().get_steppers():
+            for transition in WhitespaceStateMachine(
+                max_whitespace=self.max_whitespace
+            ).get_steppers():
                 transitions.append((transition, 2))
             return transitions
         else:
@@ -101,6 +121,69 @@ class ArraySchemaStateMachine(ArrayStateMachine):
         """
         return self.schema.get("uniqueItems", False)
 
+    def monotonic(self) -> str | None:
+        """
+        Returns the required ordering of numeric items: "increasing" for strictly
+        increasing, "non-decreasing" to also allow repeats, or None for any order.
+        """
+        monotonic = self.schema.get("monotonic")
+        if monotonic not in (None, "increasing", "non-decreasing"):
+            raise ValueError(f"Unsupported monotonic ordering: {monotonic!r}")
+        return monotonic
+
+    def consistent_keys(self) -> bool:
+        """
+        Returns whether every object item must have the same keys as the first,
+        according to the schema
+        """
+        return self.schema.get("consistentKeys", False)
+
+    def get_keyed_item_state_machine(self, first: Any) -> StateMachine:
+        """
+        Get the item state machine offering exactly the keys of the first item.
+
+        Every key is required and no others are offered, so an item can only
+        go on with keys it is able to complete with. Non-object items and item
+        schemas that are not objects are not restricted.
+        """
+        from pse.types.json.json_object import ObjectSchemaStateMachine
+
+        item = self.item_state_machine
+        if not isinstance(first, dict) or not isinstance(
+            item, ObjectSchemaStateMachine
+        ):
+            return item
+
+        keys = tuple(first)
+        if keys not in self._keyed_item_state_machines:
+            properties: dict[str, Any] = {}
+            # schema properties keep their schema order, the rest follow as parsed
+            for key in [
+                *(prop_name for prop_name in item.properties if prop_name in first),
+                *(key for key in keys if key not in item.properties),
+            ]:
+                if key in item.properties:
+                    properties[key] = item.properties[key]
+                elif patterns := item.get_matching_patterns(key):
+                    properties[key] = item.pattern_properties[patterns[0]]
+                elif isinstance(item.additional_properties, dict):
+                    properties[key] = item.additional_properties
+                else:
+                    properties[key] = {}
+
+            schema = {
+                **item.schema,
+                "properties": properties,
+                "required": list(properties),
+                "additionalProperties": False,
+            }
+            schema.pop("patternProperties", None)
+            state_machine = ObjectSchemaStateMachine(schema, item.context)
+            # nullable and defaulted properties are required all the same
+            state_machine.required_property_names = list(properties)
+            self._keyed_item_state_machines[keys] = state_machine
+        return self._keyed_item_state_machines[keys]
+
     def __str__(self) -> str:
         return "JSON" + super().__str__()
 
@@ -116,6 +199,20 @@ class ArraySchemaStepper(ArrayStepper):
         super().__init__(state_machine, current_state)
         self.state_machine: ArraySchemaStateMachine = state_machine
 
+    def should_complete_step(self) -> bool:
+        if not super().should_complete_step():
+            return False
+
+        if self.target_state == 3 and self.sub_stepper and self.value:
+            # an item is completing; hold it against the previous ones
+            item = self.sub_stepper.get_current_value()
+            if self.state_machine.consistent_keys() and not has_same_keys(
+                self.value[0], item
+            ):
+                return False
+            return is_in_order(self.value[-1], item, self.state_machine.monotonic())
+        return True
+
     def add_to_history(self, stepper: Stepper) -> None:
         """
         Adds an item to the array.
@@ -126,3 +223,26 @@ class ArraySchemaStepper(ArrayStepper):
                 return
 
         super().add_to_history(stepper)
+
+
+def is_in_order(previous: Any, item: Any, monotonic: str | None) -> bool:
+    """
+    Whether `item` may follow `previous` under the ordering; non-numeric items are not ordered.
+    """
+    if monotonic is None:
+        return True
+    if not all(
+        isinstance(value, int | float) and not isinstance(value, bool)
+        for value in (previous, item)
+    ):
+        return True
+    return item > previous if monotonic == "increasing" else item >= previous
+
+
+def has_same_keys(first: Any, item: Any) -> bool:
+    """
+    Whether `item` has exactly the keys of `first`; non-object items are not compared.
+    """
+    if not isinstance(first, dict) or not isinstance(item, dict):
+        return True
+    return first.keys() == item.keys()
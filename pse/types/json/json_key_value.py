@@ -7,9 +7,11 @@ from pse_core import StateId
 from pse.types.base.chain import ChainStateMachine
 from pse.types.base.phrase import PhraseStateMachine
 from pse.types.json import _json_schema_to_state_machine
+from pse.types.json.json_string import StringSchemaStateMachine
 from pse.types.key_value import KeyValueStateMachine, KeyValueStepper
 from pse.types.string import StringStateMachine
-from pse.types.whitespace import WhitespaceStateMachine
+from pse.types.whitespace import DEFAULT_MAX_WHITESPACE, WhitespaceStateMachine
+from pse.util.names import get_name, set_name
 
 
 class KeyValueSchemaStateMachine(KeyValueStateMachine):
@@ -18,6 +20,7 @@ class KeyValueSchemaStateMachine(KeyValueStateMachine):
         prop_name (str): The name of the property.
         prop_schema (Dict[str, Any]): The schema of the property.
         context (Dict[str, Any]): The parsing context.
+        key_pattern (str): A regex the key must match, used when prop_name is None.
     """
 
     def __init__(
@@ -25,13 +28,17 @@ class KeyValueSchemaStateMachine(KeyValueStateMachine):
         prop_name: str | None,
         prop_schema: dict[str, Any],
         context: dict[str, Any],
+        key_pattern: str | None = None,
     ):
         self.prop_name = prop_name
+        self.key_pattern = key_pattern
         self.prop_schema = prop_schema
         self.prop_context = {
+            **context,
             "defs": context.get("defs", {}),
             "path": f"{context.get('path', '')}/{prop_name}",
         }
+        max_whitespace = context.get("max_whitespace", DEFAULT_MAX_WHITESPACE)
         if self.prop_name:
             key_value_sm = ChainStateMachine(
                 [
@@ -40,17 +47,28 @@ class KeyValueSchemaStateMachine(KeyValueStateMachine):
                     PhraseStateMachine('"'),
                 ]
             )
+        elif self.key_pattern:
+            # like JSON Schema patterns, the key pattern may match anywhere in the key
+            key_value_sm = StringSchemaStateMachine(
+                {"type": "string", "pattern": f".*?(?:{self.key_pattern})"}
+            )
         else:
             key_value_sm = StringStateMachine()
 
-        is_optional = self.prop_schema.get("nullable", False) or "default" in self.prop_schema
+        value_sm = _json_schema_to_state_machine(self.prop_schema, self.prop_context)
+        if get_name(value_sm) is None:
+            set_name(value_sm, self.prop_name)
+
+        is_optional = self.prop_schema.get("nullable", False) or (
+            "default" in self.prop_schema and not context.get("emit_defaults", False)
+        )
         super().__init__(
             [
                 key_value_sm,
-                WhitespaceStateMachine(),
+                WhitespaceStateMachine(max_whitespace=max_whitespace),
                 PhraseStateMachine(":"),
-                WhitespaceStateMachine(),
-                _json_schema_to_state_machine(self.prop_schema, self.prop_context),
+                WhitespaceStateMachine(max_whitespace=max_whitespace),
+                value_sm,
             ],
             is_optional=is_optional,
         )
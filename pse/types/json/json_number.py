@@ -1,9 +1,11 @@
 from __future__ import annotations
 
-from pse_core import StateId
-from pse_core.stepper import Stepper
+from decimal import Decimal
 
-from pse.types.number import NumberStateMachine
+from pse_core import Edge, StateId
+
+from pse.types.base.phrase import PhraseStateMachine
+from pse.types.number import CanonicalForm, NumberStateMachine, NumberStepper
 
 
 class NumberSchemaStateMachine(NumberStateMachine):
@@ -11,8 +13,18 @@ class NumberSchemaStateMachine(NumberStateMachine):
     Accept a JSON number that conforms to a JSON schema
     """
 
-    def __init__(self, schema):
-        super().__init__()
+    def __init__(
+        self,
+        schema,
+        exact_decimal: bool = False,
+        canonical_form: CanonicalForm = CanonicalForm.ANY,
+        max_significant_figures: int | None = None,
+    ):
+        super().__init__(
+            exact_decimal=exact_decimal,
+            canonical_form=canonical_form,
+            max_significant_figures=max_significant_figures,
+        )
         self.schema = schema
         self.is_integer = schema["type"] == "integer"
         self.requires_validation = any(
@@ -25,38 +37,74 @@ class NumberSchemaStateMachine(NumberStateMachine):
                 "multipleOf",
             ]
         )
+        self.minimum: float | None = schema.get("minimum")
+        self.maximum: float | None = schema.get("maximum")
+        self.exclusive_minimum = self._get_exclusive_bound("exclusiveMinimum", "minimum")
+        self.exclusive_maximum = self._get_exclusive_bound("exclusiveMaximum", "maximum")
+
+    def _get_exclusive_bound(self, keyword: str, inclusive_keyword: str) -> float | None:
+        """
+        Read an exclusive bound, in either the numeric form or the older boolean
+        form that makes the inclusive bound exclusive.
+        """
+        bound = self.schema.get(keyword)
+        if isinstance(bound, bool):
+            return self.schema.get(inclusive_keyword) if bound else None
+        return bound
+
+    def get_edges(self, state: StateId) -> list[Edge]:
+        """
+        Prune the sign up front when the bounds rule out one side of zero.
+        """
+        if state == 0:
+            if (
+                self.exclusive_minimum is not None and self.exclusive_minimum >= 0
+            ) or (self.minimum is not None and self.minimum > 0):
+                # negative numbers can never be valid
+                return self.get_edges(1)
+            if (
+                self.exclusive_maximum is not None and self.exclusive_maximum <= 0
+            ) or (self.maximum is not None and self.maximum < 0):
+                # only negative numbers can be valid
+                return [(PhraseStateMachine("-"), 1)]
+
+        return super().get_edges(state)
 
     def get_new_stepper(self, state: StateId | None = None) -> NumberSchemaStepper:
         return NumberSchemaStepper(self, state)
 
-    def validate_value(self, value: float) -> bool:
+    def validate_value(self, value: float | Decimal) -> bool:
         """
         Validate the number value according to the schema
         """
-        if not isinstance(value, int | float):
+        if not isinstance(value, int | float | Decimal):
             return True
 
-        if "minimum" in self.schema and value < self.schema["minimum"]:
+        if self.minimum is not None and value < self.minimum:
             return False
-
-        if (
-            "exclusiveMinimum" in self.schema
-            and value <= self.schema["exclusiveMinimum"]
-        ):
+        if self.exclusive_minimum is not None and value <= self.exclusive_minimum:
             return False
-        if "maximum" in self.schema and value > self.schema["maximum"]:
+        if self.maximum is not None and value > self.maximum:
             return False
-        if (
-            "exclusiveMaximum" in self.schema
-            and value >= self.schema["exclusiveMaximum"]
-        ):
+        if self.exclusive_maximum is not None and value >= self.exclusive_maximum:
             return False
         if "multipleOf" in self.schema:
             divisor = self.schema["multipleOf"]
-            if value / divisor != value // divisor:
+            if isinstance(value, Decimal):
+                # exact, so 0.3 is a multiple of 0.1
+                if value % Decimal(str(divisor)) != 0:
+                    return False
+            elif value / divisor != value // divisor:
                 return False
 
-        if self.is_integer and not (isinstance(value, int) or value.is_integer()):
+        if self.is_integer and not (
+            isinstance(value, int)
+            or (
+                value == value.to_integral_value()
+                if isinstance(value, Decimal)
+                else value.is_integer()
+            )
+        ):
             return False
 
         return True
@@ -65,7 +113,7 @@ class NumberSchemaStateMachine(NumberStateMachine):
         return "JSON" + super().__str__()
 
 
-class NumberSchemaStepper(Stepper):
+class NumberSchemaStepper(NumberStepper):
     """ """
 
     def __init__(
@@ -1,5 +1,7 @@
 from __future__ import annotations
 
+import json
+import re
 from typing import Any
 
 from pse_core import StateId
@@ -8,10 +10,19 @@ from pse_core.stepper import Stepper
 
 from pse.types.base.chain import ChainStateMachine
 from pse.types.base.phrase import PhraseStateMachine
+from pse.types.json import _json_schema_to_state_machine
 from pse.types.json.json_key_value import KeyValueSchemaStateMachine
 from pse.types.key_value import KeyValueStateMachine
-from pse.types.object import ObjectStateMachine
-from pse.types.whitespace import WhitespaceStateMachine
+from pse.types.object import ObjectStateMachine, ObjectStepper
+from pse.types.whitespace import DEFAULT_MAX_WHITESPACE, WhitespaceStateMachine
+
+# `$data` keywords whose value must be a count, or a number
+COUNT_KEYWORDS = frozenset(
+    {"minItems", "maxItems", "minLength", "maxLength", "minProperties", "maxProperties"}
+)
+NUMBER_KEYWORDS = frozenset(
+    {"minimum", "maximum", "exclusiveMinimum", "exclusiveMaximum", "multipleOf"}
+)
 
 
 class ObjectSchemaStateMachine(ObjectStateMachine):
@@ -22,24 +33,45 @@ class ObjectSchemaStateMachine(ObjectStateMachine):
     ):
         self.schema = schema
         self.context = context
-        self.properties: dict[str, Any] = schema.get("properties", {})
-        self.required_property_names: list[str] = schema.get("required", [])
+        # copies, so editing the compiled object never changes the caller's schema
+        self.properties: dict[str, Any] = dict(schema.get("properties", {}))
+        self.required_property_names: list[str] = list(schema.get("required", []))
         self.additional_properties: dict[str, Any] | bool = schema.get(
             "additionalProperties", {}
         )
+        self.pattern_properties: dict[str, Any] = schema.get("patternProperties", {})
+        self._compiled_patterns: dict[str, re.Pattern] = {
+            pattern: re.compile(pattern) for pattern in self.pattern_properties
+        }
+        self._pattern_state_machines: dict[str, StateMachine] = {}
+        self._pattern_property_state_machines: list[KeyValueSchemaStateMachine] | None = None
+        self._additional_property_state_machine: StateMachine | None = None
         self.ordered_properties: bool = schema.get("orderedProperties", True)
+        self.sorted_keys: bool = schema.get("sortedKeys", False)
+        self.emit_defaults: bool = context.get("emit_defaults", False)
+        self._property_state_machines: dict[str, KeyValueSchemaStateMachine] = {}
+        # properties with `$data` references, by name and resolved schema
+        self._resolved_state_machines: dict[tuple[str, str], KeyValueSchemaStateMachine] = {}
         if any(prop not in self.properties for prop in self.required_property_names):
             raise ValueError("Required property not defined in schema")
+        for property_schema in self.properties.values():
+            if isinstance(property_schema, dict):
+                validate_data_references(property_schema)
 
         for property_name, property_schema in self.properties.items():
             if property_name in self.required_property_names and property_schema:
-                if (
-                    property_schema.get("nullable", False)
-                    or "default" in property_schema
+                if property_schema.get("nullable", False) or (
+                    "default" in property_schema and not self.emit_defaults
                 ):
                     self.required_property_names.remove(property_name)
 
-        super().__init__(schema.get("nullable", False))
+        super().__init__(
+            schema.get("nullable", False),
+            max_whitespace=context.get("max_whitespace", DEFAULT_MAX_WHITESPACE),
+        )
+
+    def get_new_stepper(self, state: StateId | None = None) -> ObjectSchemaStepper:
+        return ObjectSchemaStepper(self, state)
 
     def get_transitions(self, stepper: Stepper) -> list[tuple[Stepper, StateId]]:
         """Retrieve transition steppers from the current state.
@@ -59,9 +91,16 @@ class ObjectSchemaStateMachine(ObjectStateMachine):
                 for transition in PhraseStateMachine("}").get_steppers():
                     transitions.append((transition, "$"))
 
-            if len(value) < len(self.properties) or self.additional_properties:
+            if (
+                len(value) < len(self.properties)
+                or self.additional_properties
+                or self.pattern_properties
+            ):
                 for transition in ChainStateMachine(
-                    [PhraseStateMachine(","), WhitespaceStateMachine()]
+                    [
+                        PhraseStateMachine(","),
+                        WhitespaceStateMachine(max_whitespace=self.max_whitespace),
+                    ]
                 ).get_steppers():
                     transitions.append((transition, 2))
         else:
@@ -69,35 +108,225 @@ class ObjectSchemaStateMachine(ObjectStateMachine):
 
         return transitions
 
+    def add_property(
+        self,
+        prop_name: str,
+        prop_schema: dict[str, Any],
+        required: bool = False,
+    ) -> None:
+        """
+        Add a property to the compiled object in place.
+
+        Only the cached state machine for this property is invalidated;
+        every other property keeps its compiled state machine. The schema the
+        object was compiled from is left as it was, but a state machine shared
+        through a `SchemaCache` changes for every engine holding it.
+
+        Args:
+            prop_name: The name of the property to add.
+            prop_schema: The schema of the property's value.
+            required: Whether the property must be present.
+        """
+        self.properties[prop_name] = prop_schema
+        self.schema = {**self.schema, "properties": self.properties}
+        self._property_state_machines.pop(prop_name, None)
+        is_nullable = prop_schema.get("nullable", False) or (
+            "default" in prop_schema and not self.emit_defaults
+        )
+        if required and not is_nullable and prop_name not in self.required_property_names:
+            self.required_property_names.append(prop_name)
+
+    def remove_property(self, prop_name: str) -> None:
+        """
+        Remove a property from the compiled object in place.
+
+        Args:
+            prop_name: The name of the property to remove.
+
+        Raises:
+            KeyError: If the property is not defined on this object.
+        """
+        if prop_name not in self.properties:
+            raise KeyError(f"Property not defined in schema: {prop_name}")
+
+        del self.properties[prop_name]
+        self.schema = {**self.schema, "properties": self.properties}
+        self._property_state_machines.pop(prop_name, None)
+        if prop_name in self.required_property_names:
+            self.required_property_names.remove(prop_name)
+
+    def get_default_fill(self, value: dict[str, Any]) -> str:
+        """
+        Get the text that adds every missing required property with a default.
+
+        Only applies when compiled with `emit_defaults`; the text starts with the
+        separator, so it can be inserted right before the closing brace.
+
+        Args:
+            value: The properties parsed so far.
+
+        Returns:
+            The fill text, or an empty string if nothing needs filling.
+        """
+        if not self.emit_defaults:
+            return ""
+
+        space = " " if self.max_whitespace else ""
+        fill = ""
+        for prop_name, prop_schema in self.properties.items():
+            if (
+                prop_name in value
+                or prop_name not in self.required_property_names
+                or "default" not in prop_schema
+            ):
+                continue
+            default = json.dumps(prop_schema["default"])
+            fill += f",{space}{json.dumps(prop_name)}:{space}{default}"
+
+        # the first property of an empty object has no separator
+        return fill if value else fill.removeprefix(",")
+
+    def get_property_state_machine(self, prop_name: str) -> KeyValueSchemaStateMachine:
+        """
+        Get the compiled state machine for a property, compiling it on first use.
+        """
+        if prop_name not in self._property_state_machines:
+            self._property_state_machines[prop_name] = KeyValueSchemaStateMachine(
+                prop_name,
+                self.properties[prop_name],
+                self.context,
+            )
+        return self._property_state_machines[prop_name]
+
+    def get_resolved_property_state_machine(
+        self, prop_name: str, resolved_schema: dict[str, Any]
+    ) -> KeyValueSchemaStateMachine:
+        """
+        Get the state machine for a property whose `$data` references are resolved,
+        compiled once per distinct resolved schema.
+        """
+        key = (prop_name, json.dumps(resolved_schema, sort_keys=True, default=str))
+        if key not in self._resolved_state_machines:
+            self._resolved_state_machines[key] = KeyValueSchemaStateMachine(
+                prop_name, resolved_schema, self.context
+            )
+        return self._resolved_state_machines[key]
+
     def get_property_state_machines(self, value: dict[str, Any]) -> list[StateMachine]:
         property_state_machines: list[StateMachine] = []
-        for prop_name, prop_schema in self.properties.items():
+        for prop_name in self.get_property_names(value):
+            prop_schema = self.properties[prop_name]
             if prop_name not in value:
-                property = KeyValueSchemaStateMachine(
-                    prop_name,
-                    prop_schema,
-                    self.context,
-                )
+                if has_data_references(prop_schema):
+                    property = self.get_resolved_property_state_machine(
+                        prop_name, resolve_data_references(prop_schema, value)
+                    )
+                else:
+                    property = self.get_property_state_machine(prop_name)
                 property_state_machines.append(property)
-                if self.ordered_properties:
+                if self.ordered_properties and not self.sorted_keys:
                     break
 
-        if (
-            all(prop_name in value for prop_name in self.required_property_names)
-            and self.additional_properties
-        ):
-            # non-schema kv property to represent the additional properties
+        if not all(prop_name in value for prop_name in self.required_property_names):
+            return property_state_machines
+
+        # keys matching several patterns are checked against all of them on completion
+        property_state_machines.extend(self.get_pattern_property_state_machines())
+        if additional_property := self.get_additional_property_state_machine():
+            property_state_machines.append(additional_property)
+
+        return property_state_machines
+
+    def get_pattern_property_state_machines(self) -> list[KeyValueSchemaStateMachine]:
+        """
+        Get the compiled state machines for the patternProperties, compiling them on first use.
+
+        Compiling lazily keeps a pattern schema that refers back to this object finite.
+        """
+        if self._pattern_property_state_machines is None:
+            self._pattern_property_state_machines = [
+                KeyValueSchemaStateMachine(
+                    None,
+                    pattern_schema,
+                    self.context,
+                    key_pattern=pattern,
+                )
+                for pattern, pattern_schema in self.pattern_properties.items()
+            ]
+        return self._pattern_property_state_machines
+
+    def get_additional_property_state_machine(self) -> StateMachine | None:
+        """
+        Get the compiled state machine for additional properties, compiling it on first use.
+
+        Returns:
+            The state machine, or None if additional properties are not allowed.
+        """
+        if not self.additional_properties:
+            return None
+        if self._additional_property_state_machine is None:
             if isinstance(self.additional_properties, dict):
-                property = KeyValueSchemaStateMachine(
+                self._additional_property_state_machine = KeyValueSchemaStateMachine(
                     None,
                     self.additional_properties,
                     self.context,
                 )
             else:
-                property = KeyValueStateMachine()
-            property_state_machines.append(property)
+                # non-schema kv property to represent the additional properties
+                self._additional_property_state_machine = KeyValueStateMachine(
+                    max_whitespace=self.max_whitespace
+                )
+        return self._additional_property_state_machine
 
-        return property_state_machines
+    def get_property_names(self, value: dict[str, Any]) -> list[str]:
+        """
+        Get the names of the schema properties in the order they may be offered.
+
+        With `sortedKeys`, properties are offered alphabetically in place of the
+        schema order, and only those sorting after the last parsed key and no
+        later than the first missing required key, which could not follow them.
+        """
+        if not self.sorted_keys:
+            return list(self.properties)
+
+        last_key = next(reversed(value), None)
+        missing_required = [
+            prop_name for prop_name in self.required_property_names if prop_name not in value
+        ]
+        next_required = min(missing_required, default=None)
+        return [
+            prop_name
+            for prop_name in sorted(self.properties)
+            if (last_key is None or prop_name > last_key)
+            and (next_required is None or prop_name <= next_required)
+        ]
+
+    def get_matching_patterns(self, prop_name: str) -> list[str]:
+        """
+        Get the patternProperties patterns matching anywhere in the key, in schema order.
+        """
+        return [
+            pattern
+            for pattern, compiled in self._compiled_patterns.items()
+            if compiled.search(prop_name)
+        ]
+
+    def matches_pattern_schema(self, pattern: str, prop_value: Any) -> bool:
+        """
+        Whether a parsed value satisfies the value schema of a patternProperties pattern.
+        """
+        if pattern not in self._pattern_state_machines:
+            self._pattern_state_machines[pattern] = _json_schema_to_state_machine(
+                self.pattern_properties[pattern], self.context
+            )
+        state_machine = self._pattern_state_machines[pattern]
+        steppers = state_machine.advance_all_basic(
+            state_machine.get_steppers(), json.dumps(prop_value)
+        )
+        return any(
+            stepper.has_reached_accept_state() and not stepper.remaining_input
+            for stepper in steppers
+        )
 
     def __eq__(self, other: object) -> bool:
         return (
@@ -108,3 +337,120 @@ class ObjectSchemaStateMachine(ObjectStateMachine):
 
     def __str__(self) -> str:
         return "JSON" + super().__str__()
+
+
+class ObjectSchemaStepper(ObjectStepper):
+    def __init__(
+        self,
+        state_machine: ObjectSchemaStateMachine,
+        current_state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, current_state)
+        self.state_machine: ObjectSchemaStateMachine = state_machine
+
+    def should_complete_step(self) -> bool:
+        if not super().should_complete_step():
+            return False
+
+        if self.target_state != 3 or not self.sub_stepper:
+            return True
+
+        prop_name, prop_value = self.sub_stepper.get_current_value()
+        if self.state_machine.sorted_keys and not all(prop_name > key for key in self.value):
+            # pattern and additional properties take any key, so hold it to the order here
+            return False
+
+        property_state_machine = self.sub_stepper.state_machine
+        if property_state_machine is self.state_machine._additional_property_state_machine:
+            # a key matching a pattern is only valid against that pattern's schema
+            return not self.state_machine.get_matching_patterns(prop_name)
+
+        key_pattern = getattr(property_state_machine, "key_pattern", None)
+        if key_pattern is None:
+            return True
+        # a key matching several patterns must satisfy every one of their schemas;
+        # only the first matching pattern's branch completes, the others would repeat it
+        matching = self.state_machine.get_matching_patterns(prop_name)
+        return bool(matching) and key_pattern == matching[0] and all(
+            self.state_machine.matches_pattern_schema(pattern, prop_value)
+            for pattern in matching[1:]
+        )
+
+
+def has_data_references(schema: dict[str, Any]) -> bool:
+    """
+    Whether any keyword of the schema takes its value from another property.
+    """
+    return any(_is_data_reference(keyword) for keyword in schema.values())
+
+
+def resolve_data_references(schema: dict[str, Any], value: dict[str, Any]) -> dict[str, Any]:
+    """
+    Replace `{"$data": "1/<property>"}` keyword values with the value of a sibling property.
+
+    References follow the `$data` proposal, with a relative JSON pointer from the
+    property to its sibling, e.g. `"maxItems": {"$data": "1/count"}`.
+    Keywords referring to a property that has not been parsed yet, or whose
+    value has the wrong type for the keyword (e.g. a string for `minItems`),
+    are dropped, leaving that keyword unconstrained.
+
+    Args:
+        schema: The schema of the property's value.
+        value: The properties parsed so far.
+
+    Returns:
+        A copy of the schema with every reference resolved.
+
+    Raises:
+        ValueError: If a reference is not a sibling property pointer.
+    """
+    resolved: dict[str, Any] = {}
+    for keyword, keyword_value in schema.items():
+        if not _is_data_reference(keyword_value):
+            resolved[keyword] = keyword_value
+            continue
+
+        sibling = _get_data_reference_sibling(keyword_value["$data"])
+        if sibling in value and _is_valid_keyword_value(keyword, value[sibling]):
+            resolved[keyword] = value[sibling]
+    return resolved
+
+
+def validate_data_references(schema: dict[str, Any]) -> None:
+    """
+    Check that every `$data` reference of the schema points to a sibling property.
+
+    Raises:
+        ValueError: If a reference is not a sibling property pointer.
+    """
+    for keyword_value in schema.values():
+        if _is_data_reference(keyword_value):
+            _get_data_reference_sibling(keyword_value["$data"])
+
+
+def _get_data_reference_sibling(pointer: str) -> str:
+    if not pointer.startswith("1/") or "/" in pointer[2:]:
+        raise ValueError(f"Unsupported $data reference: {pointer}")
+    return pointer[2:].replace("~1", "/").replace("~0", "~")
+
+
+def _is_valid_keyword_value(keyword: str, keyword_value: Any) -> bool:
+    if isinstance(keyword_value, bool):
+        return keyword not in COUNT_KEYWORDS | NUMBER_KEYWORDS
+    if keyword in COUNT_KEYWORDS:
+        return isinstance(keyword_value, int) and keyword_value >= 0
+    if keyword in NUMBER_KEYWORDS:
+        return isinstance(keyword_value, int | float)
+    if keyword == "pattern":
+        return isinstance(keyword_value, str)
+    if keyword == "enum":
+        return isinstance(keyword_value, list)
+    return True
+
+
+def _is_data_reference(keyword_value: Any) -> bool:
+    return (
+        isinstance(keyword_value, dict)
+        and len(keyword_value) == 1
+        and isinstance(keyword_value.get("$data"), str)
+    )
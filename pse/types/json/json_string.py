@@ -5,6 +5,8 @@ import re
 
 import regex
 from pse_core import StateId
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
 
 from pse.types.string import StringStateMachine, StringStepper
 
@@ -24,9 +26,13 @@ class StringSchemaStateMachine(StringStateMachine):
         self,
         schema: dict,
     ):
+        min_length, max_length = schema.get("minLength"), schema.get("maxLength")
         super().__init__(
-            min_length=schema.get("minLength"),
-            max_length=schema.get("maxLength"),
+            min_length=min_length,
+            max_length=max_length,
+            exact_length=min_length
+            if min_length is not None and min_length == max_length
+            else None,
         )
         self.schema = schema or {}
         self.pattern: re.Pattern | None = None
@@ -147,10 +153,7 @@ class StringSchemaStepper(StringStepper):
         """
         if value.startswith('"'):
             value = value[1:]
-        if value.endswith('"'):
-            first_quote = value.index('"')
-            value = value[: first_quote]
-        return value
+        return strip_after_closing_quote(value)
 
     def get_valid_prefix(self, s: str) -> str | None:
         """
@@ -165,7 +168,8 @@ class StringSchemaStepper(StringStepper):
             return s
 
         current_value = self.sub_stepper.get_raw_value()
-        quotes_removed_s = self.clean_value(s)
+        # within a value the string is already open, so a quote closes it
+        quotes_removed_s = strip_after_closing_quote(s)
 
         left, right = 0, len(quotes_removed_s)
         best_match = None
@@ -228,3 +232,84 @@ class StringSchemaStepper(StringStepper):
                 return False
 
         return True
+
+
+class FormatStringStateMachine(StateMachine):
+    """
+    Accept the text of a string format, e.g. a duration, that also meets the
+    schema's `minLength`, `maxLength` and `pattern` constraints.
+    """
+
+    def __init__(self, format_state_machine: StateMachine, schema: dict) -> None:
+        super().__init__({0: [(format_state_machine, "$")]})
+        self.min_length: int = schema.get("minLength", 0)
+        self.max_length: int | None = schema.get("maxLength")
+        self.pattern: re.Pattern | None = None
+        if "pattern" in schema:
+            try:
+                self.pattern = re.compile(schema["pattern"])
+            except re.error as e:
+                raise ValueError(f"Invalid pattern in schema: {e}") from e
+
+    def get_new_stepper(self, state: StateId | None = None) -> FormatStringStepper:
+        return FormatStringStepper(self, state)
+
+    def is_valid_prefix(self, value: str) -> bool:
+        """
+        Whether the text so far can still meet the length and pattern constraints.
+        """
+        if self.max_length is not None and len(value) > self.max_length:
+            return False
+        return self.pattern is None or bool(
+            regex.match(self.pattern.pattern, value, partial=True)
+        )
+
+    def is_valid_value(self, value: str) -> bool:
+        """
+        Whether the finished text meets the length and pattern constraints.
+        """
+        return self.is_valid_prefix(value) and len(value) >= self.min_length and (
+            self.pattern is None or bool(self.pattern.match(value))
+        )
+
+    def __str__(self) -> str:
+        return "FormatString"
+
+
+class FormatStringStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: FormatStringStateMachine,
+        current_state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, current_state)
+        self.state_machine: FormatStringStateMachine = state_machine
+
+    def consume(self, token: str) -> list[Stepper]:
+        return [
+            stepper
+            for stepper in super().consume(token)
+            if self.state_machine.is_valid_prefix(stepper.get_raw_value())
+        ]
+
+    def should_complete_step(self) -> bool:
+        if not super().should_complete_step():
+            return False
+        value = self.sub_stepper.get_raw_value() if self.sub_stepper else ""
+        return self.state_machine.is_valid_value(value)
+
+
+def strip_after_closing_quote(value: str) -> str:
+    """
+    Cut an open string's text at its first unescaped quote, which closes it;
+    anything after (e.g. `"}`) is not part of the string.
+    """
+    is_escaped = False
+    for index, char in enumerate(value):
+        if is_escaped:
+            is_escaped = False
+        elif char == "\\":
+            is_escaped = True
+        elif char == '"':
+            return value[:index]
+    return value
@@ -0,0 +1,119 @@
+from __future__ import annotations
+
+import json
+import logging
+import threading
+from collections import OrderedDict
+from typing import Any
+
+from pse_core.state_machine import StateMachine
+
+from pse.types.json import (
+    JSONSchemaSource,
+    _generate_json_schema,
+    json_schema_state_machine,
+)
+
+logger = logging.getLogger(__name__)
+
+
+class SchemaCache:
+    """
+    A thread-safe LRU cache of compiled JSON schema state machines.
+
+    The cache is keyed by the canonical JSON of the schema and the compile options,
+    so repeated compiles of an equivalent schema return the same shared state machine.
+    A single instance can be shared by every engine in a process.
+    """
+
+    def __init__(self, max_size: int = 128) -> None:
+        """
+        Args:
+            max_size: The maximum number of compiled schemas to keep.
+        """
+        if max_size < 1:
+            raise ValueError("max_size must be at least 1.")
+
+        self.max_size = max_size
+        self.hits = 0
+        self.misses = 0
+        self._entries: OrderedDict[str, tuple[dict[str, Any], StateMachine]] = (
+            OrderedDict()
+        )
+        self._lock = threading.Lock()
+        # one lock per key being compiled, so a slow compile never blocks other keys
+        self._compile_locks: dict[str, threading.Lock] = {}
+
+    def get_or_compile(
+        self,
+        schema: JSONSchemaSource,
+        **kwargs: Any,
+    ) -> tuple[dict[str, Any], StateMachine]:
+        """
+        Return the compiled state machine for a schema, compiling it on a miss.
+
+        Args:
+            schema: The schema to compile.
+            **kwargs: Compile options, forwarded to `json_schema_state_machine`.
+
+        Returns:
+            The JSON schema and its (possibly shared) state machine.
+        """
+        json_schema = _generate_json_schema(schema)
+        key = self.cache_key(json_schema, **kwargs)
+        if (entry := self._get(key)) is not None:
+            return entry
+
+        with self._lock:
+            compile_lock = self._compile_locks.setdefault(key, threading.Lock())
+        with compile_lock:
+            # another thread may have compiled the schema while this one waited
+            if (entry := self._get(key)) is not None:
+                return entry
+
+            try:
+                entry = json_schema_state_machine(json_schema, **kwargs)
+            finally:
+                with self._lock:
+                    self._compile_locks.pop(key, None)
+            with self._lock:
+                self.misses += 1
+                self._entries[key] = entry
+                if len(self._entries) > self.max_size:
+                    evicted_key, _ = self._entries.popitem(last=False)
+                    logger.debug(f"Evicted compiled schema from cache: {evicted_key[:64]}")
+            return entry
+
+    def _get(self, key: str) -> tuple[dict[str, Any], StateMachine] | None:
+        """
+        Look up a compiled schema, counting a hit and marking it recently used.
+        """
+        with self._lock:
+            if key not in self._entries:
+                return None
+            self.hits += 1
+            self._entries.move_to_end(key)
+            return self._entries[key]
+
+    def clear(self) -> None:
+        """
+        Remove every compiled schema from the cache.
+        """
+        with self._lock:
+            self._entries.clear()
+            self.hits = 0
+            self.misses = 0
+
+    @staticmethod
+    def cache_key(json_schema: dict[str, Any], **kwargs: Any) -> str:
+        """
+        Build a canonical key from a JSON schema and its compile options.
+        """
+        return json.dumps(
+            {"schema": json_schema, "options": kwargs},
+            sort_keys=True,
+            default=str,
+        )
+
+    def __len__(self) -> int:
+        return len(self._entries)
@@ -0,0 +1,98 @@
+"""Save compiled schemas to disk in a versioned container, and load them back."""
+
+from __future__ import annotations
+
+import json
+import os
+import struct
+from typing import Any
+
+from pse_core.state_machine import StateMachine
+
+from pse.types.json import (
+    JSONSchemaSource,
+    _generate_json_schema,
+    json_schema_state_machine,
+)
+from pse.types.json.schema_cache import SchemaCache
+
+SCHEMA_FILE_MAGIC = b"PSEG"
+# bump whenever the compiled representation of a schema changes
+SCHEMA_FILE_VERSION = 1
+_HEADER = struct.Struct(">4sI")
+
+
+def save_versioned(
+    path: str | os.PathLike[str], schema: JSONSchemaSource, **kwargs: Any
+) -> None:
+    """
+    Save a schema and its compile options to a versioned file.
+
+    The file holds magic bytes, the format version and the canonical JSON of the
+    schema and options; state machines are rebuilt from it on load, so a file
+    saved by an incompatible version is refused instead of compiling differently.
+
+    Args:
+        path: The file to write.
+        schema: The schema to save.
+        **kwargs: Compile options, forwarded to `json_schema_state_machine` on load.
+
+    Raises:
+        ValueError: If the schema or an option cannot be written as JSON,
+            e.g. a callable, and so could not be restored on load.
+    """
+    try:
+        payload = json.dumps(
+            {"schema": _generate_json_schema(schema), "options": kwargs},
+            sort_keys=True,
+        )
+    except (TypeError, ValueError) as e:
+        raise ValueError(
+            f"Schema or compile options are not JSON-serializable: {e}"
+        ) from e
+    with open(path, "wb") as file:
+        file.write(_HEADER.pack(SCHEMA_FILE_MAGIC, SCHEMA_FILE_VERSION))
+        file.write(payload.encode())
+
+
+def load_versioned(
+    path: str | os.PathLike[str], schema_cache: SchemaCache | None = None
+) -> tuple[dict[str, Any], StateMachine]:
+    """
+    Load a schema saved with `save_versioned` and compile it.
+
+    Args:
+        path: The file to read.
+        schema_cache: If given, the schema is compiled through this cache.
+
+    Returns:
+        The JSON schema and its state machine.
+
+    Raises:
+        ValueError: If the file is not a schema file, was saved by
+            a different format version, or is corrupt.
+    """
+    with open(path, "rb") as file:
+        data = file.read()
+
+    if len(data) < _HEADER.size:
+        raise ValueError(f"Not a schema file: {path}")
+    magic, version = _HEADER.unpack_from(data)
+    if magic != SCHEMA_FILE_MAGIC:
+        raise ValueError(f"Not a schema file: {path}")
+    if version != SCHEMA_FILE_VERSION:
+        raise ValueError(
+            f"Unsupported schema file version {version} in {path}; "
+            f"expected {SCHEMA_FILE_VERSION}. Recompile and save the schema again."
+        )
+
+    try:
+        payload = json.loads(data[_HEADER.size :].decode())
+        schema: dict[str, Any] = payload["schema"]
+        options: dict[str, Any] = payload["options"]
+    except (UnicodeDecodeError, json.JSONDecodeError, KeyError, TypeError) as e:
+        raise ValueError(f"Corrupt schema file {path}: {e}") from e
+
+    if schema_cache is not None:
+        return schema_cache.get_or_compile(schema, **options)
+    return json_schema_state_machine(schema, **options)
@@ -0,0 +1,53 @@
+import json
+from typing import Any
+
+
+def example_to_schema(example: str | Any) -> dict[str, Any]:
+    """
+    Infer a lenient schema with the same structure as an example document.
+
+    Objects keep exactly the example's keys, all required. Arrays take their
+    item schema from the first element. Integers and floats are both inferred
+    as "number", and values are never pinned to the example's contents.
+
+    Args:
+        example: The example, as JSON text or an already parsed value.
+
+    Returns:
+        dict[str, Any]: A dictionary representing the inferred JSON schema.
+
+    Raises:
+        ValueError: If the example is a string that is not valid JSON.
+    """
+    if isinstance(example, str):
+        try:
+            example = json.loads(example)
+        except json.JSONDecodeError as e:
+            raise ValueError(f"Invalid JSON example: {e}") from e
+
+    return _infer_schema(example)
+
+
+def _infer_schema(value: Any) -> dict[str, Any]:
+    if isinstance(value, dict):
+        return {
+            "type": "object",
+            "properties": {key: _infer_schema(item) for key, item in value.items()},
+            "required": list(value),
+            "additionalProperties": False,
+        }
+    elif isinstance(value, list):
+        if not value:
+            return {"type": "array"}
+        return {"type": "array", "items": _infer_schema(value[0])}
+    # bool is a subclass of int, so it must be checked first
+    elif isinstance(value, bool):
+        return {"type": "boolean"}
+    elif isinstance(value, int | float):
+        return {"type": "number"}
+    elif isinstance(value, str):
+        return {"type": "string"}
+    elif value is None:
+        return {"type": "null"}
+    else:
+        raise ValueError(f"Unsupported example value: {value!r}")
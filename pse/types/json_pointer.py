@@ -0,0 +1,56 @@
+from __future__ import annotations
+
+from typing import Any
+
+from pse_core import StateGraph, StateId
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
+
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+
+# `/` and `~` delimit and escape tokens; the rest would need escaping inside a JSON string
+RESERVED_CHARS = '/~"\\' + "".join(chr(c) for c in range(0x20))
+
+
+class JsonPointerStateMachine(StateMachine):
+    """
+    Accepts an RFC 6901 JSON Pointer, e.g. `/a/b/0` or `/a~1b`.
+
+    Each reference token follows a `/`; a literal `~` must be written `~0`
+    and a literal `/` must be written `~1`. The empty pointer is accepted
+    only when `is_optional` is set.
+    """
+
+    def __init__(self, is_optional: bool = False) -> None:
+        unescaped = CharacterStateMachine(blacklist_charset=RESERVED_CHARS, char_min=1)
+        separator = PhraseStateMachine("/")
+        escape = PhraseStateMachine("~")
+        state_graph: StateGraph = {
+            0: [(separator, 1)],
+            # 1: at the start of a token or right after an escape
+            1: [(unescaped, 3), (escape, 2), (separator, 1)],
+            2: [(CharacterStateMachine("01", exact_length=1), 1)],
+            # 3: right after a run of unescaped characters
+            3: [(escape, 2), (separator, 1)],
+        }
+        super().__init__(state_graph, end_states=[1, 3], is_optional=is_optional)
+
+    def get_new_stepper(self, state: StateId | None = None) -> JsonPointerStepper:
+        return JsonPointerStepper(self, state)
+
+    def __str__(self) -> str:
+        return "JsonPointer"
+
+
+class JsonPointerStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: JsonPointerStateMachine,
+        current_state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, current_state)
+        self.state_machine: JsonPointerStateMachine = state_machine
+
+    def get_current_value(self) -> Any:
+        return self.get_raw_value()
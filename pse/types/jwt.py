@@ -0,0 +1,60 @@
+from __future__ import annotations
+
+import string
+from typing import Any
+
+from pse_core import StateId
+from pse_core.stepper import Stepper
+
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+
+# RFC 4648 base64url alphabet; JWTs omit the `=` padding
+BASE64URL_CHARS = string.ascii_letters + string.digits + "-_"
+
+
+def base64url_segment() -> CharacterStateMachine:
+    """
+    One or more unpadded base64url characters.
+    """
+    return CharacterStateMachine(BASE64URL_CHARS, char_min=1)
+
+
+class JwtStateMachine(ChainStateMachine):
+    """
+    Accepts a JWT-like compact serialization: `header.payload.signature`,
+    three base64url segments separated by `.`.
+
+    Only the shape is checked; segments are not decoded.
+    """
+
+    def __init__(self) -> None:
+        super().__init__(
+            [
+                base64url_segment(),
+                PhraseStateMachine("."),
+                base64url_segment(),
+                PhraseStateMachine("."),
+                base64url_segment(),
+            ]
+        )
+
+    def get_new_stepper(self, state: StateId | None = None) -> JwtStepper:
+        return JwtStepper(self, state)
+
+    def __str__(self) -> str:
+        return "Jwt"
+
+
+class JwtStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: JwtStateMachine,
+        current_state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, current_state)
+        self.state_machine: JwtStateMachine = state_machine
+
+    def get_current_value(self) -> Any:
+        return self.get_raw_value() or None
@@ -11,22 +11,27 @@ from pse_core.stepper import Stepper
 from pse.types.base.chain import ChainStateMachine
 from pse.types.base.phrase import PhraseStateMachine
 from pse.types.string import StringStateMachine
-from pse.types.whitespace import WhitespaceStateMachine
+from pse.types.whitespace import DEFAULT_MAX_WHITESPACE, WhitespaceStateMachine
 
 logger = logging.getLogger()
 
 
 class KeyValueStateMachine(ChainStateMachine):
-    def __init__(self, sequence: list[StateMachine] | None = None, is_optional: bool = False) -> None:
+    def __init__(
+        self,
+        sequence: list[StateMachine] | None = None,
+        is_optional: bool = False,
+        max_whitespace: int = DEFAULT_MAX_WHITESPACE,
+    ) -> None:
         from pse.types.json.json_value import JsonStateMachine
 
         super().__init__(
             sequence
             or [
                 StringStateMachine(),
-                WhitespaceStateMachine(),
+                WhitespaceStateMachine(max_whitespace=max_whitespace),
                 PhraseStateMachine(":"),
-                WhitespaceStateMachine(),
+                WhitespaceStateMachine(max_whitespace=max_whitespace),
                 JsonStateMachine(),
             ],
             is_optional=is_optional,
@@ -0,0 +1,126 @@
+from __future__ import annotations
+
+import string
+from typing import Any
+
+from pse_core import StateId
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
+
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+from pse.types.json.json_number import NumberSchemaStateMachine, NumberSchemaStepper
+
+
+def bounded_number(minimum: float, maximum: float) -> StateMachine:
+    return BoundedNumberStateMachine(
+        {"type": "number", "minimum": minimum, "maximum": maximum}
+    )
+
+
+class BoundedNumberStateMachine(NumberSchemaStateMachine):
+    """
+    Accepts a number within bounds either side of zero, rejecting a prefix as
+    soon as it falls outside them, e.g. `9` then `5` for a maximum of 90.
+    """
+
+    def get_new_stepper(self, state: StateId | None = None) -> BoundedNumberStepper:
+        return BoundedNumberStepper(self, state)
+
+
+class BoundedNumberStepper(NumberSchemaStepper):
+    def __init__(
+        self,
+        state_machine: BoundedNumberStateMachine,
+        current_state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, current_state)
+        self.state_machine: BoundedNumberStateMachine = state_machine
+
+    def should_start_step(self, token: str) -> bool:
+        return self.is_within_bounds(token) and super().should_start_step(token)
+
+    def consume(self, token: str) -> list[Stepper]:
+        if not self.is_within_bounds(token):
+            return []
+        return super().consume(token)
+
+    def is_within_bounds(self, token: str = "") -> bool:
+        """
+        Whether the number so far, extended by the numeric start of `token`,
+        stays within the bounds; more digits only move it further from zero.
+        """
+        raw_value = self.get_raw_value()
+        if "e" in raw_value.lower():
+            # exponent forms are checked once complete
+            return True
+
+        has_point = "." in raw_value
+        numeric_length = 0
+        while numeric_length < len(token):
+            char = token[numeric_length]
+            if char == "-" and not raw_value and numeric_length == 0:
+                pass
+            elif char == "." and not has_point:
+                has_point = True
+            elif char not in string.digits:
+                break
+            numeric_length += 1
+        number = (raw_value + token[:numeric_length]).rstrip(".")
+        if number in ("", "-"):
+            return True
+        try:
+            value = float(number)
+        except ValueError:
+            return False
+        minimum, maximum = self.state_machine.minimum, self.state_machine.maximum
+        return (minimum is None or value >= minimum) and (
+            maximum is None or value <= maximum
+        )
+
+
+class LatLngStateMachine(StateMachine):
+    """
+    Accepts a `lat,lng` coordinate pair in decimal degrees, e.g. `37.77,-122.41`,
+    with latitude in [-90, 90] and longitude in [-180, 180].
+    Spaces are allowed after the comma.
+    """
+
+    def __init__(self) -> None:
+        super().__init__(
+            {
+                0: [
+                    (
+                        ChainStateMachine(
+                            [
+                                bounded_number(-90, 90),
+                                PhraseStateMachine(","),
+                                CharacterStateMachine(" ", is_optional=True),
+                                bounded_number(-180, 180),
+                            ]
+                        ),
+                        "$",
+                    )
+                ]
+            }
+        )
+
+    def get_new_stepper(self, state: StateId | None = None) -> LatLngStepper:
+        return LatLngStepper(self, state)
+
+    def __str__(self) -> str:
+        return "LatLng"
+
+
+class LatLngStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: LatLngStateMachine,
+        current_state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, current_state)
+        self.state_machine: LatLngStateMachine = state_machine
+
+    def get_current_value(self) -> Any:
+        return self.get_raw_value() or None
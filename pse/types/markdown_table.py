@@ -0,0 +1,169 @@
+from __future__ import annotations
+
+from typing import Any
+
+from pse_core import StateId
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
+
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.loop import LoopStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+
+
+def padded(state_machine: StateMachine) -> StateMachine:
+    """
+    A cell's content, with optional spaces on either side.
+    """
+    return ChainStateMachine(
+        [
+            CharacterStateMachine(" ", is_optional=True),
+            state_machine,
+            CharacterStateMachine(" ", is_optional=True),
+        ]
+    )
+
+
+def free_cell() -> StateMachine:
+    """
+    Any text without pipes or newlines, starting with a non-space character,
+    so a cell of only spaces is rejected as empty.
+    """
+    return ChainStateMachine(
+        [
+            CharacterStateMachine(blacklist_charset=" |\n", char_min=1, char_limit=1),
+            CharacterStateMachine(blacklist_charset="|\n", is_optional=True),
+        ]
+    )
+
+
+def separator_cell() -> StateMachine:
+    """
+    A header separator cell: at least three dashes, colon-aligned on either side.
+    """
+    return padded(
+        ChainStateMachine(
+            [
+                CharacterStateMachine(":", char_limit=1, is_optional=True),
+                CharacterStateMachine("-", char_min=3),
+                CharacterStateMachine(":", char_limit=1, is_optional=True),
+            ]
+        )
+    )
+
+
+class MarkdownTableRowStateMachine(StateMachine):
+    """
+    Accepts one Markdown table row with exactly one cell per state machine,
+    e.g. `| a | b | c |`.
+    """
+
+    def __init__(self, cell_state_machines: list[StateMachine]) -> None:
+        """
+        Args:
+            cell_state_machines: The state machine for each cell's content,
+                which may be surrounded by spaces.
+        """
+        if not cell_state_machines:
+            raise ValueError("At least one cell must be provided.")
+
+        self.column_count = len(cell_state_machines)
+        state_machines: list[StateMachine] = [PhraseStateMachine("|")]
+        for cell_state_machine in cell_state_machines:
+            state_machines.append(cell_state_machine)
+            state_machines.append(PhraseStateMachine("|"))
+        super().__init__({0: [(ChainStateMachine(state_machines), "$")]})
+
+    @classmethod
+    def free_form(cls, column_count: int) -> MarkdownTableRowStateMachine:
+        """
+        A row of `column_count` cells of any text without pipes or newlines.
+        """
+        return cls([padded(free_cell()) for _ in range(column_count)])
+
+    def get_new_stepper(self, state: StateId | None = None) -> MarkdownTableStepper:
+        return MarkdownTableStepper(self, state)
+
+    def __str__(self) -> str:
+        return "MarkdownTableRow"
+
+
+class MarkdownTableStateMachine(StateMachine):
+    """
+    Accepts a Markdown table: a header row naming the columns,
+    the header separator row, then newline-separated body rows.
+    """
+
+    def __init__(
+        self,
+        columns: list[str],
+        cell_state_machines: dict[str, StateMachine] | None = None,
+        min_rows: int = 1,
+        max_rows: int = -1,
+    ) -> None:
+        """
+        Args:
+            columns: The column headers, in order.
+            cell_state_machines: The state machine for the cells of each column,
+                by header. Columns without one take any text without pipes or newlines.
+            min_rows: The minimum number of body rows.
+            max_rows: The maximum number of body rows, or -1 for no limit.
+        """
+        if not columns:
+            raise ValueError("At least one column must be provided.")
+
+        cell_state_machines = cell_state_machines or {}
+        if unknown := set(cell_state_machines) - set(columns):
+            raise ValueError(f"Cells given for unknown columns: {sorted(unknown)}")
+
+        self.columns = columns
+        header = MarkdownTableRowStateMachine(
+            [padded(PhraseStateMachine(column)) for column in columns]
+        )
+        separator = MarkdownTableRowStateMachine(
+            [separator_cell() for _ in columns]
+        )
+        row = MarkdownTableRowStateMachine(
+            [padded(cell_state_machines.get(column) or free_cell()) for column in columns]
+        )
+        state_machines: list[StateMachine] = [
+            header,
+            PhraseStateMachine("\n"),
+            separator,
+        ]
+        if max_rows != 0:
+            body = LoopStateMachine(
+                row,
+                min_loop_count=max(min_rows, 1),
+                max_loop_count=max_rows,
+                separator_state_machine=PhraseStateMachine("\n"),
+            )
+            state_machines.append(
+                ChainStateMachine(
+                    [PhraseStateMachine("\n"), body],
+                    is_optional=min_rows < 1,
+                )
+            )
+        super().__init__({0: [(ChainStateMachine(state_machines), "$")]})
+
+    def get_new_stepper(self, state: StateId | None = None) -> MarkdownTableStepper:
+        return MarkdownTableStepper(self, state)
+
+    def __str__(self) -> str:
+        return "MarkdownTable"
+
+
+class MarkdownTableStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: MarkdownTableRowStateMachine | MarkdownTableStateMachine,
+        current_state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, current_state)
+        self.state_machine: MarkdownTableRowStateMachine | MarkdownTableStateMachine = (
+            state_machine
+        )
+
+    def get_current_value(self) -> Any:
+        return self.get_raw_value() or None
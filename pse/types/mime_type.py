@@ -0,0 +1,89 @@
+from __future__ import annotations
+
+import string
+from typing import Any
+
+from pse_core import StateGraph, StateId
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
+
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+
+ALPHANUMERIC = string.ascii_letters + string.digits
+# RFC 6838 restricted-name characters
+RESTRICTED_NAME_CHARS = ALPHANUMERIC + "!#$&-^_.+"
+# RFC 7230 token characters, used for parameter names and values
+TOKEN_CHARS = ALPHANUMERIC + "!#$%&'*+-.^_`|~"
+QUOTED_TEXT_CHARS = "".join(
+    chr(c) for c in range(0x20, 0x7F) if chr(c) not in '"\\'
+) + "\t"
+OPTIONAL_WHITESPACE = " \t"
+
+
+def restricted_name() -> StateMachine:
+    """
+    A type or subtype name: an alphanumeric followed by up to 126 restricted-name characters.
+    """
+    return ChainStateMachine(
+        [
+            CharacterStateMachine(ALPHANUMERIC, exact_length=1),
+            CharacterStateMachine(RESTRICTED_NAME_CHARS, char_limit=126, is_optional=True),
+        ]
+    )
+
+
+class MimeTypeStateMachine(StateMachine):
+    """
+    Accepts a MIME type, `type/subtype`, with optional parameters, e.g.
+    `application/json; charset=utf-8`.
+
+    Names follow RFC 6838; parameter values are tokens or quoted strings (RFC 7231).
+    """
+
+    def __init__(self) -> None:
+        parameter_separator = ChainStateMachine(
+            [
+                CharacterStateMachine(OPTIONAL_WHITESPACE, is_optional=True),
+                PhraseStateMachine(";"),
+                CharacterStateMachine(OPTIONAL_WHITESPACE, is_optional=True),
+            ]
+        )
+        token = CharacterStateMachine(TOKEN_CHARS, char_min=1)
+        quoted_string = ChainStateMachine(
+            [
+                PhraseStateMachine('"'),
+                CharacterStateMachine(QUOTED_TEXT_CHARS, is_optional=True),
+                PhraseStateMachine('"'),
+            ]
+        )
+        state_graph: StateGraph = {
+            0: [(restricted_name(), 1)],
+            1: [(PhraseStateMachine("/"), 2)],
+            2: [(restricted_name(), 3)],
+            3: [(parameter_separator, 4)],
+            4: [(token, 5)],
+            5: [(PhraseStateMachine("="), 6)],
+            6: [(token, 3), (quoted_string, 3)],
+        }
+        super().__init__(state_graph, end_states=[3])
+
+    def get_new_stepper(self, state: StateId | None = None) -> MimeTypeStepper:
+        return MimeTypeStepper(self, state)
+
+    def __str__(self) -> str:
+        return "MimeType"
+
+
+class MimeTypeStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: MimeTypeStateMachine,
+        current_state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, current_state)
+        self.state_machine: MimeTypeStateMachine = state_machine
+
+    def get_current_value(self) -> Any:
+        return self.get_raw_value() or None
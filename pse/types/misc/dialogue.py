@@ -0,0 +1,37 @@
+from __future__ import annotations
+
+from pse_core.state_machine import StateMachine
+
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.loop import LoopStateMachine
+from pse.types.whitespace import WhitespaceStateMachine
+
+
+def alternating(
+    first: StateMachine,
+    second: StateMachine,
+    max_rounds: int = -1,
+    separator: StateMachine | None = None,
+) -> StateMachine:
+    """
+    Accept turns alternating between two structures, e.g. a user turn then
+    an assistant turn, repeated.
+
+    Every round is a `first` turn followed by a `second` turn, so the
+    dialogue always ends on a `second` turn.
+
+    Args:
+        first: The structure of the turns that open each round.
+        second: The structure of the turns that answer them.
+        max_rounds: The maximum number of rounds, or -1 for no limit.
+        separator: What goes between turns; defaults to optional whitespace.
+    """
+    if max_rounds == 0:
+        raise ValueError("max_rounds must be at least 1, or -1 for no limit")
+
+    separator = separator or WhitespaceStateMachine()
+    return LoopStateMachine(
+        ChainStateMachine([first, separator, second]),
+        max_loop_count=max_rounds,
+        separator_state_machine=separator,
+    )
@@ -0,0 +1,78 @@
+from __future__ import annotations
+
+import json
+from typing import Any
+
+from pse_core import StateGraph
+from pse_core.state_machine import StateMachine
+
+from pse.types.base.phrase import PhraseStateMachine
+
+
+class DocumentChoiceStateMachine(StateMachine):
+    """
+    Accepts exactly one of several complete documents.
+
+    Documents are factored into a prefix tree, so shared prefixes are a single
+    path and the choice narrows only where the documents first differ.
+    """
+
+    def __init__(self, documents: list[Any]) -> None:
+        """
+        Args:
+            documents: The documents to choose between. Strings are used verbatim,
+                anything else is serialized as JSON.
+        """
+        if not documents:
+            raise ValueError("Documents must be provided.")
+
+        self.documents = [
+            document if isinstance(document, str) else json.dumps(document)
+            for document in documents
+        ]
+        if any(not document for document in self.documents):
+            raise ValueError("Documents cannot be empty.")
+
+        state_graph, end_states = self._build_state_graph(self.documents)
+        super().__init__(state_graph, end_states=end_states)
+
+    @staticmethod
+    def _build_state_graph(documents: list[str]) -> tuple[StateGraph, list[int]]:
+        """
+        Build a radix tree over the documents: each edge is the longest run of
+        characters without a branch point or document end.
+        """
+        # character trie; "" marks the end of a document
+        trie: dict[str, Any] = {}
+        for document in documents:
+            node = trie
+            for char in document:
+                node = node.setdefault(char, {})
+            node[""] = {}
+
+        state_graph: StateGraph = {}
+        end_states: list[int] = []
+        next_state = 1
+        queue: list[tuple[int, dict[str, Any]]] = [(0, trie)]
+        while queue:
+            state, node = queue.pop()
+            for char, child in node.items():
+                if not char:
+                    end_states.append(state)
+                    continue
+
+                label = char
+                while len(child) == 1 and "" not in child:
+                    next_char, child = next(iter(child.items()))
+                    label += next_char
+
+                state_graph.setdefault(state, []).append(
+                    (PhraseStateMachine(label), next_state)
+                )
+                queue.append((next_state, child))
+                next_state += 1
+
+        return state_graph, end_states
+
+    def __str__(self) -> str:
+        return "DocumentChoice"
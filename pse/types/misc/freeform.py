@@ -4,11 +4,36 @@ from collections.abc import Callable
 from typing import Any
 
 from pse_core import StateId
+from pse_core.state_machine import StateMachine
 
+from pse.types.base.character import CharacterStateMachine
 from pse.types.base.wait_for import WaitFor, WaitForStepper
 from pse.types.enum import EnumStateMachine
 
 
+def followed_by_optional(
+    state_machine: StateMachine,
+    free: StateMachine | None = None,
+) -> StateMachine:
+    """
+    Allow an optional free region after a complete value.
+
+    The value's accept state stays valid on its own; the free region
+    (any text, by default) is accepted after it but never required.
+
+    Args:
+        state_machine: The primary, structured value.
+        free: The trailing region; defaults to unconstrained text.
+    """
+    return StateMachine(
+        {
+            0: [(state_machine, 1)],
+            1: [(free or CharacterStateMachine(), 2)],
+        },
+        end_states=[1, 2],
+    )
+
+
 class FreeformStateMachine(WaitFor):
     """
     A state machine that can be used to parse freeform text that has an ending delimiter.
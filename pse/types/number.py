@@ -1,17 +1,39 @@
 from __future__ import annotations
 
 import logging
+import re
+from decimal import Decimal, InvalidOperation
+from enum import Enum
+from typing import Any
 
 from pse_core import Edge, StateId
 from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
 
 from pse.types.base.chain import ChainStateMachine
 from pse.types.base.character import CharacterStateMachine
 from pse.types.base.phrase import PhraseStateMachine
 from pse.types.integer import IntegerStateMachine
+from pse.types.misc.document_choice import DocumentChoiceStateMachine
 
 logger = logging.getLogger(__name__)
 
+NUMBER_CHARS = frozenset("0123456789+-.eE")
+
+
+class CanonicalForm(Enum):
+    """
+    Which of several equivalent spellings of a number (`1`, `1.0`, `1e0`) is accepted.
+
+    ANY: every well-formed spelling.
+    INTEGER_IF_WHOLE: whole numbers must be written as integers (`1`, not `1.0` or `1e0`).
+    ALWAYS_DECIMAL: numbers must have a fractional part and no exponent (`1.0`, not `1`).
+    """
+
+    ANY = "any"
+    INTEGER_IF_WHOLE = "integer_if_whole"
+    ALWAYS_DECIMAL = "always_decimal"
+
 
 class NumberStateMachine(StateMachine):
     """
@@ -21,7 +43,25 @@ class NumberStateMachine(StateMachine):
     decimal, and exponential formats as specified by the JSON standard.
     """
 
-    def __init__(self):
+    def __init__(
+        self,
+        exact_decimal: bool = False,
+        canonical_form: CanonicalForm = CanonicalForm.ANY,
+        max_significant_figures: int | None = None,
+    ):
+        """
+        Args:
+            exact_decimal: If True, every value, integers included, is parsed as
+                `Decimal` instead of `int` or `float`, preserving every digit of the raw input.
+            canonical_form: Restricts which spelling of a number is accepted.
+            max_significant_figures: If set, the mantissa may have at most this many
+                digits after any leading zeros; the exponent is not counted.
+        """
+        if max_significant_figures is not None and max_significant_figures < 1:
+            raise ValueError("max_significant_figures must be at least 1")
+        self.exact_decimal = exact_decimal
+        self.canonical_form = canonical_form
+        self.max_significant_figures = max_significant_figures
         super().__init__(
             {
                 0: [
@@ -54,10 +94,16 @@ class NumberStateMachine(StateMachine):
             end_states=[2, 3, "$"],
         )
 
+    def get_new_stepper(self, state: StateId | None = None) -> NumberStepper:
+        return NumberStepper(self, state)
+
     def get_edges(self, state: StateId) -> list[Edge]:
         """
         Get the edges for a given state.
         """
+        if self.canonical_form == CanonicalForm.ALWAYS_DECIMAL and state in (2, 3):
+            # no exponent
+            return [*super().get_edges(2)] if state == 2 else []
         if state == 2:
             return [*super().get_edges(state), *super().get_edges(3)]
         elif state == 4:
@@ -67,3 +113,160 @@ class NumberStateMachine(StateMachine):
 
     def __str__(self) -> str:
         return "Number"
+
+
+class NumberStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: NumberStateMachine,
+        current_state: StateId | None = None,
+    ):
+        super().__init__(state_machine, current_state)
+        self.state_machine: NumberStateMachine = state_machine
+
+    def has_reached_accept_state(self) -> bool:
+        return super().has_reached_accept_state() and self.is_canonical()
+
+    def should_start_step(self, token: str) -> bool:
+        return self.fits_significant_figures(token) and super().should_start_step(token)
+
+    def consume(self, token: str) -> list[Stepper]:
+        if not self.fits_significant_figures(token):
+            return []
+        return super().consume(token)
+
+    def fits_significant_figures(self, token: str = "") -> bool:
+        """
+        Whether the raw value, extended by the numeric start of `token`,
+        stays within the significant-figure limit.
+        """
+        limit = self.state_machine.max_significant_figures
+        if limit is None:
+            return True
+
+        numeric_length = 0
+        while numeric_length < len(token) and token[numeric_length] in NUMBER_CHARS:
+            numeric_length += 1
+        return significant_figures(self.get_raw_value() + token[:numeric_length]) <= limit
+
+    def should_complete_step(self) -> bool:
+        return super().should_complete_step() and self.is_canonical()
+
+    def is_canonical(self) -> bool:
+        """
+        Whether the raw value is spelled in the state machine's canonical form.
+        """
+        canonical_form = self.state_machine.canonical_form
+        if canonical_form == CanonicalForm.ANY:
+            return True
+
+        raw_value = self.get_raw_value()
+        is_integer_literal = not any(char in raw_value for char in ".eE")
+        if canonical_form == CanonicalForm.ALWAYS_DECIMAL:
+            return not is_integer_literal
+        if is_integer_literal:
+            return True
+        try:
+            value = Decimal(raw_value)
+        except InvalidOperation:
+            return True
+        # whole numbers must be written as integers
+        return not value.is_finite() or value != value.to_integral_value()
+
+    def get_current_value(self) -> Any:
+        """
+        Parse the raw value, handling exponent forms such as `1e3` and `1.5E-2`.
+
+        Integers stay `int` and everything else becomes `float`, unless the
+        state machine is in exact-decimal mode, where every value is a `Decimal`.
+        Incomplete numbers such as `1e` fall back to the default value.
+        """
+        raw_value = self.get_raw_value()
+        try:
+            if self.state_machine.exact_decimal:
+                return Decimal(raw_value)
+            if any(char in raw_value for char in ".eE"):
+                return float(raw_value)
+            return int(raw_value)
+        except (ValueError, InvalidOperation):
+            return super().get_current_value()
+
+
+def significant_figures(raw_value: str) -> int:
+    """
+    Count the mantissa digits after any leading zeros, ignoring the sign,
+    the decimal point and the exponent.
+    """
+    mantissa = re.split("[eE]", raw_value, maxsplit=1)[0]
+    digits = mantissa.lstrip("+-").replace(".", "")
+    return len(digits.lstrip("0"))
+
+
+class QuantizedNumberStateMachine(DocumentChoiceStateMachine):
+    """
+    Accepts one number from the grid `minimum, minimum + step, ..., maximum`.
+
+    Each value is written in its shortest form (`1`, not `1.0`), and values
+    sharing a prefix (`1` and `1.5`) share a path through the state graph.
+    """
+
+    MAX_VALUES = 10_000
+
+    def __init__(
+        self,
+        minimum: float | int | str,
+        maximum: float | int | str,
+        step: float | int | str,
+    ) -> None:
+        """
+        Args:
+            minimum: The smallest value on the grid.
+            maximum: The largest value on the grid; included if it lies on the grid.
+            step: The spacing between values; must be positive.
+        """
+        self.minimum = Decimal(str(minimum))
+        self.maximum = Decimal(str(maximum))
+        self.step = Decimal(str(step))
+        if self.step <= 0:
+            raise ValueError("Step must be positive.")
+        if self.maximum < self.minimum:
+            raise ValueError("Maximum must not be less than minimum.")
+        if (self.maximum - self.minimum) / self.step >= self.MAX_VALUES:
+            raise ValueError(f"Grid has more than {self.MAX_VALUES} values.")
+
+        values: list[str] = []
+        value = self.minimum
+        while value <= self.maximum:
+            values.append(self.format_value(value))
+            value += self.step
+
+        super().__init__(values)
+
+    @staticmethod
+    def format_value(value: Decimal) -> str:
+        if value == value.to_integral_value():
+            return str(int(value))
+        return format(value.normalize(), "f")
+
+    def get_new_stepper(self, state: StateId | None = None) -> QuantizedNumberStepper:
+        return QuantizedNumberStepper(self, state)
+
+    def __str__(self) -> str:
+        return "QuantizedNumber"
+
+
+class QuantizedNumberStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: QuantizedNumberStateMachine,
+        current_state: StateId | None = None,
+    ):
+        super().__init__(state_machine, current_state)
+        self.state_machine: QuantizedNumberStateMachine = state_machine
+
+    def get_current_value(self) -> Any:
+        raw_value = self.get_raw_value()
+        try:
+            return float(raw_value) if "." in raw_value else int(raw_value)
+        except ValueError:
+            return super().get_current_value()
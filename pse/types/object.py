@@ -10,7 +10,7 @@ from pse_core.stepper import Stepper
 from pse.types.base.chain import ChainStateMachine
 from pse.types.base.phrase import PhraseStateMachine
 from pse.types.key_value import KeyValueStateMachine
-from pse.types.whitespace import WhitespaceStateMachine
+from pse.types.whitespace import DEFAULT_MAX_WHITESPACE, WhitespaceStateMachine
 
 logger = logging.getLogger()
 
@@ -23,29 +23,41 @@ class ObjectStateMachine(StateMachine):
     and maintaining the current object properties being parsed.
     """
 
-    def __init__(self, is_optional: bool = False) -> None:
+    def __init__(
+        self,
+        is_optional: bool = False,
+        max_whitespace: int = DEFAULT_MAX_WHITESPACE,
+    ) -> None:
         """
 
         Sets up the state transition graph for parsing JSON objects.
+
+        Args:
+            is_optional: Whether the object may be omitted entirely.
+            max_whitespace: Maximum whitespace allowed between structural tokens.
         """
+        self.max_whitespace = max_whitespace
         super().__init__(
             {
                 0: [
                     (PhraseStateMachine("{"), 1),
                 ],
                 1: [
-                    (WhitespaceStateMachine(), 2),
+                    (WhitespaceStateMachine(max_whitespace=max_whitespace), 2),
                 ],
                 2: [
-                    (KeyValueStateMachine(), 3),
+                    (KeyValueStateMachine(max_whitespace=max_whitespace), 3),
                 ],
                 3: [
-                    (WhitespaceStateMachine(), 4),
+                    (WhitespaceStateMachine(max_whitespace=max_whitespace), 4),
                 ],
                 4: [
                     (
                         ChainStateMachine(
-                            [PhraseStateMachine(","), WhitespaceStateMachine()]
+                            [
+                                PhraseStateMachine(","),
+                                WhitespaceStateMachine(max_whitespace=max_whitespace),
+                            ]
                         ),
                         2,
                     ),
@@ -0,0 +1,110 @@
+from __future__ import annotations
+
+import string
+from typing import Any
+
+from pse_core import StateGraph, StateId
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
+
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+from pse.types.integer import IntegerStateMachine
+
+MAX_PERCENTAGE = 100
+
+
+class PercentageStateMachine(StateMachine):
+    """
+    Accepts a decimal number followed by `%`, e.g. `50%` or `99.9%`.
+    """
+
+    def __init__(self, bounded: bool = True, decimal_places: int | None = None) -> None:
+        """
+        Args:
+            bounded: Whether the number must lie within [0, 100]; otherwise
+                any number is accepted, including negative ones.
+            decimal_places: The most digits allowed after the decimal point,
+                0 for whole percentages only, or None for no limit.
+        """
+        if decimal_places is not None and decimal_places < 0:
+            raise ValueError("decimal_places must be at least 0")
+
+        self.bounded = bounded
+        self.decimal_places = decimal_places
+        whole_number: StateMachine = IntegerStateMachine()
+        if not bounded:
+            whole_number = ChainStateMachine(
+                [PhraseStateMachine("-", is_optional=True), whole_number]
+            )
+        percent_sign = PhraseStateMachine("%")
+        state_graph: StateGraph = {
+            0: [(whole_number, 1)],
+            1: [(percent_sign, "$")],
+        }
+        if decimal_places != 0:
+            fraction = ChainStateMachine(
+                [
+                    PhraseStateMachine("."),
+                    CharacterStateMachine(
+                        string.digits, char_min=1, char_limit=decimal_places
+                    ),
+                ]
+            )
+            state_graph[1].append((fraction, 2))
+            state_graph[2] = [(percent_sign, "$")]
+        super().__init__(state_graph)
+
+    def get_new_stepper(self, state: StateId | None = None) -> PercentageStepper:
+        return PercentageStepper(self, state)
+
+    def __str__(self) -> str:
+        return "Percentage"
+
+
+class PercentageStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: PercentageStateMachine,
+        current_state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, current_state)
+        self.state_machine: PercentageStateMachine = state_machine
+
+    def should_start_step(self, token: str) -> bool:
+        return self.is_within_bounds(token) and super().should_start_step(token)
+
+    def consume(self, token: str) -> list[Stepper]:
+        if not self.is_within_bounds(token):
+            return []
+        return super().consume(token)
+
+    def is_within_bounds(self, token: str = "") -> bool:
+        """
+        Whether the number so far, extended by the numeric start of `token`,
+        stays within [0, 100] when bounded.
+        """
+        if not self.state_machine.bounded:
+            return True
+
+        raw_value = self.get_raw_value()
+        has_point = "." in raw_value
+        numeric_length = 0
+        while numeric_length < len(token):
+            char = token[numeric_length]
+            if char == "." and not has_point:
+                has_point = True
+            elif char not in string.digits:
+                break
+            numeric_length += 1
+        number = (raw_value + token[:numeric_length]).rstrip(".")
+        if not number:
+            return True
+        try:
+            return 0 <= float(number) <= MAX_PERCENTAGE
+        except ValueError:
+            return False
+
+    def get_current_value(self) -> Any:
+        return self.get_raw_value() or None
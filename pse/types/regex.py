@@ -0,0 +1,164 @@
+from __future__ import annotations
+
+import string
+
+import regex
+from pse_core import StateId
+from pse_core.stepper import Stepper
+
+from pse.types.base.character import CharacterStateMachine, CharacterStepper
+
+# the characters offered as continuations; text outside them is still accepted if consumed
+CONTINUATION_CHARS = string.ascii_letters + string.digits + string.punctuation + " \t\n"
+
+
+class RegexStateMachine(CharacterStateMachine):
+    """
+    Accepts text that fully matches a regular expression.
+
+    Input is consumed for as long as it can still extend to a full match,
+    using partial matching; `\\d`, `\\w`, character classes and quantifiers are supported.
+    """
+
+    def __init__(self, pattern: str, flags: int = 0) -> None:
+        """
+        Args:
+            pattern: The regular expression the whole output must match.
+            flags: `regex` module flags used to compile the pattern.
+
+        Raises:
+            ValueError: If the pattern is not a valid regular expression.
+        """
+        try:
+            self.pattern = regex.compile(pattern, flags)
+        except regex.error as e:
+            raise ValueError(f"Invalid regex pattern: {pattern!r}: {e}") from e
+        # a pattern matching the empty string may be skipped entirely
+        super().__init__(is_optional=self.pattern.fullmatch("") is not None)
+
+    def get_new_stepper(self, state: StateId | None = None) -> RegexStepper:
+        return RegexStepper(self)
+
+    def matches(self, text: str, partial: bool = False) -> bool:
+        """
+        Whether the text fully matches the pattern, or with `partial`, can still extend to.
+        """
+        return self.pattern.fullmatch(text, partial=partial) is not None
+
+    def __str__(self) -> str:
+        return "Regex"
+
+
+class RegexStepper(CharacterStepper):
+    def __init__(self, state_machine: RegexStateMachine, value: str | None = None) -> None:
+        super().__init__(state_machine, value)
+        self.state_machine: RegexStateMachine = state_machine
+
+    def accepts_any_token(self) -> bool:
+        # without a charset the base stepper would take any text
+        return False
+
+    def get_valid_continuations(self, depth: int = 0) -> list[str]:
+        """
+        The characters that keep a full match possible.
+        """
+        raw_value = self.get_raw_value()
+        return [
+            char
+            for char in CONTINUATION_CHARS
+            if self.state_machine.matches(raw_value + char, partial=True)
+        ]
+
+    def should_start_step(self, token: str) -> bool:
+        return bool(token) and self.get_valid_prefix_length(token) > 0
+
+    def consume(self, token: str) -> list[Stepper]:
+        valid_length = self.get_valid_prefix_length(token)
+        if valid_length <= 0:
+            return []
+
+        new_value = self.get_raw_value() + token[:valid_length]
+        remaining_input = token[valid_length:] or None
+        return [self.step(new_value, remaining_input)]
+
+    def has_reached_accept_state(self) -> bool:
+        return self.state_machine.matches(self.get_raw_value())
+
+    def should_complete_step(self) -> bool:
+        return self.has_reached_accept_state()
+
+    def get_valid_prefix_length(self, token: str) -> int:
+        """
+        Length of the longest prefix of the token that keeps a full match possible.
+
+        Partial matches are prefix-closed, so the first failing length ends the search.
+        """
+        raw_value = self.get_raw_value()
+        valid_length = 0
+        for i in range(1, len(token) + 1):
+            if not self.state_machine.matches(raw_value + token[:i], partial=True):
+                break
+            valid_length = i
+        return valid_length
+
+
+class RegexSetStateMachine(RegexStateMachine):
+    """
+    Accepts text that fully matches any one of several regular expressions.
+
+    Each pattern is matched on its own, so group numbers and backreferences
+    keep their meaning, and the stepper reports which pattern the text
+    matched once it is accepted.
+    """
+
+    def __init__(self, patterns: list[str], flags: int = 0) -> None:
+        """
+        Args:
+            patterns: The regular expressions, any one of which the whole output must match.
+            flags: `regex` module flags used to compile every pattern.
+
+        Raises:
+            ValueError: If no patterns are given or any is not a valid regular expression.
+        """
+        if not patterns:
+            raise ValueError("At least one pattern is required.")
+
+        self.patterns: list[regex.Pattern] = []
+        for pattern in patterns:
+            try:
+                self.patterns.append(regex.compile(pattern, flags))
+            except regex.error as e:
+                raise ValueError(f"Invalid regex pattern: {pattern!r}: {e}") from e
+        CharacterStateMachine.__init__(
+            self,
+            is_optional=any(pattern.fullmatch("") is not None for pattern in self.patterns),
+        )
+
+    def matches(self, text: str, partial: bool = False) -> bool:
+        return any(
+            pattern.fullmatch(text, partial=partial) is not None for pattern in self.patterns
+        )
+
+    def get_new_stepper(self, state: StateId | None = None) -> RegexSetStepper:
+        return RegexSetStepper(self)
+
+    def __str__(self) -> str:
+        return "RegexSet"
+
+
+class RegexSetStepper(RegexStepper):
+    def __init__(self, state_machine: RegexSetStateMachine, value: str | None = None) -> None:
+        super().__init__(state_machine, value)
+        self.state_machine: RegexSetStateMachine = state_machine
+
+    def get_matched_pattern_index(self) -> int | None:
+        """
+        The index of the first pattern the text fully matches, or None if none does yet.
+        """
+        raw_value = self.get_raw_value()
+        if not raw_value:
+            return None
+        for index, pattern in enumerate(self.state_machine.patterns):
+            if pattern.fullmatch(raw_value) is not None:
+                return index
+        return None
@@ -0,0 +1,126 @@
+from __future__ import annotations
+
+from typing import Any
+
+from pse_core import StateGraph, StateId
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
+
+from pse.types.base.any import AnyStateMachine
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.loop import LoopStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+from pse.types.enum import EnumStateMachine
+from pse.types.number import NumberStateMachine
+
+COMPARISON_OPERATORS = ["=", "!=", "<>", "<", "<=", ">", ">="]
+
+
+def keyword(word: str) -> StateMachine:
+    return PhraseStateMachine(word, is_case_sensitive=False)
+
+
+def space(optional: bool = False) -> StateMachine:
+    return CharacterStateMachine(" ", char_min=1, is_optional=optional)
+
+
+def string_literal() -> StateMachine:
+    return ChainStateMachine(
+        [
+            PhraseStateMachine("'"),
+            CharacterStateMachine(blacklist_charset="'\n", is_optional=True),
+            PhraseStateMachine("'"),
+        ]
+    )
+
+
+class SqlSelectStateMachine(StateMachine):
+    """
+    Accepts a single `SELECT columns FROM table [WHERE condition]` query
+    over the allowed table and column names.
+
+    Columns are either `*` or a comma-separated list. Conditions compare a column
+    with a number, a single-quoted string or another column, joined by `AND`/`OR`.
+    Keywords match in any casing; names match exactly.
+    """
+
+    def __init__(self, tables: list[str], columns: list[str]) -> None:
+        """
+        Args:
+            tables: The table names that may be selected from.
+            columns: The column names that may be selected and compared.
+        """
+        if not tables or not columns:
+            raise ValueError("Tables and columns must be provided.")
+
+        self.tables = tables
+        self.columns = columns
+        column = EnumStateMachine(columns, require_quotes=False)
+        comma = ChainStateMachine([space(True), PhraseStateMachine(","), space(True)])
+        column_list = AnyStateMachine(
+            [
+                PhraseStateMachine("*"),
+                LoopStateMachine(column, separator_state_machine=comma),
+            ]
+        )
+        comparison = ChainStateMachine(
+            [
+                column,
+                space(True),
+                EnumStateMachine(COMPARISON_OPERATORS, require_quotes=False),
+                space(True),
+                AnyStateMachine(
+                    [
+                        NumberStateMachine(),
+                        string_literal(),
+                        column,
+                    ]
+                ),
+            ]
+        )
+        conjunction = ChainStateMachine(
+            [space(), AnyStateMachine([keyword("AND"), keyword("OR")]), space()]
+        )
+        state_graph: StateGraph = {
+            0: [(keyword("SELECT"), 1)],
+            1: [(space(), 2)],
+            2: [(column_list, 3)],
+            3: [(space(), 4)],
+            4: [(keyword("FROM"), 5)],
+            5: [(space(), 6)],
+            6: [(EnumStateMachine(tables, require_quotes=False), 7)],
+            7: [
+                (
+                    ChainStateMachine(
+                        [
+                            space(),
+                            keyword("WHERE"),
+                            space(),
+                            LoopStateMachine(comparison, separator_state_machine=conjunction),
+                        ]
+                    ),
+                    "$",
+                )
+            ],
+        }
+        super().__init__(state_graph, end_states=[7, "$"])
+
+    def get_new_stepper(self, state: StateId | None = None) -> SqlSelectStepper:
+        return SqlSelectStepper(self, state)
+
+    def __str__(self) -> str:
+        return "SqlSelect"
+
+
+class SqlSelectStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: SqlSelectStateMachine,
+        current_state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, current_state)
+        self.state_machine: SqlSelectStateMachine = state_machine
+
+    def get_current_value(self) -> Any:
+        return self.get_raw_value() or None
@@ -1,13 +1,49 @@
 from __future__ import annotations
 
+import json
+
 from pse_core import StateId
 from pse_core.state_machine import StateMachine
 from pse_core.stepper import Stepper
 
+from pse.types.base.chain import ChainStateMachine
 from pse.types.base.character import CharacterStateMachine
 from pse.types.base.phrase import PhraseStateMachine
 
 INVALID_CHARS: set[str] = {chr(c) for c in range(0, 0x20)} | {'"', "\\"}
+SLUG_CHARSET = "abcdefghijklmnopqrstuvwxyz0123456789_-"
+
+
+def slug_string(
+    charset: str = SLUG_CHARSET,
+    min_length: int = 1,
+    max_length: int | None = None,
+) -> StateMachine:
+    """
+    Accepts a quoted string whose contents are restricted to a fixed charset.
+
+    Lighter than a full regex for slugs and identifiers; no escape sequences are allowed.
+
+    Args:
+        charset: The characters allowed between the quotes.
+        min_length: Minimum content length, excluding the quotes.
+        max_length: Maximum content length, excluding the quotes (unlimited if None).
+    """
+    if INVALID_CHARS.intersection(charset):
+        raise ValueError("Slug charset cannot contain quotes, backslashes or control characters")
+
+    return ChainStateMachine(
+        [
+            PhraseStateMachine('"'),
+            CharacterStateMachine(
+                charset,
+                char_min=min_length,
+                char_limit=max_length,
+                is_optional=min_length == 0,
+            ),
+            PhraseStateMachine('"'),
+        ]
+    )
 
 
 class StringStateMachine(StateMachine):
@@ -15,6 +51,8 @@ class StringStateMachine(StateMachine):
     Accepts a well-formed JSON string.
 
     The length of the string is measured excluding the surrounding quotation marks.
+    Lengths count characters, never bytes or tokens, so a multi-byte CJK character
+    counts once.
     """
 
     # StateId constants
@@ -22,11 +60,24 @@ class StringStateMachine(StateMachine):
     ESCAPED_SEQUENCE = 2
     HEX_CODE = 3
 
-    def __init__(self, min_length: int | None = None, max_length: int | None = None):
+    def __init__(
+        self,
+        min_length: int | None = None,
+        max_length: int | None = None,
+        exact_length: int | None = None,
+    ):
         """
         The state machine is configured to parse JSON strings, handling escape sequences
         and Unicode characters appropriately.
+
+        With `exact_length`, the decoded string (an escape sequence counts as one
+        character) must be exactly that long; min_length and max_length are ignored.
         """
+        if exact_length is not None:
+            if exact_length < 0:
+                raise ValueError("exact_length cannot be negative")
+            min_length = max_length = None
+        self.exact_length = exact_length
         super().__init__(
             {
                 0: [
@@ -70,10 +121,47 @@ class StringStateMachine(StateMachine):
     def get_new_stepper(self, state: int | str | None = None) -> Stepper:
         return StringStepper(self, state)
 
+    def get_transitions(self, stepper: Stepper) -> list[tuple[Stepper, StateId]]:
+        transitions = super().get_transitions(stepper)
+        if self.exact_length is None or stepper.current_state != self.STRING_CONTENTS:
+            return transitions
+
+        remaining = self.exact_length - decoded_length(stepper.get_raw_value())
+        exact_transitions: list[tuple[Stepper, StateId]] = []
+        for transition, target_state in transitions:
+            if target_state == "$":
+                # the closing quote only once the string is exactly long enough
+                if remaining == 0:
+                    exact_transitions.append((transition, target_state))
+            elif remaining <= 0:
+                continue
+            elif isinstance(transition.state_machine, CharacterStateMachine):
+                # a run of regular characters may only fill what is left
+                contents = CharacterStateMachine(
+                    blacklist_charset=INVALID_CHARS, char_limit=remaining
+                )
+                for content_transition in contents.get_steppers():
+                    exact_transitions.append((content_transition, target_state))
+            else:
+                exact_transitions.append((transition, target_state))
+
+        return exact_transitions
+
     def __str__(self) -> str:
         return "String"
 
 
+def decoded_length(raw_value: str) -> int:
+    """
+    The decoded length of a string's contents so far, excluding the opening quote.
+    """
+    contents = raw_value[1:] if raw_value.startswith('"') else raw_value
+    try:
+        return len(json.loads(f'"{contents}"'))
+    except ValueError:
+        return len(contents)
+
+
 class StringStepper(Stepper):
     def __init__(
         self, state_machine: StringStateMachine, current_state: StateId | None = None
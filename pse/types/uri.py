@@ -0,0 +1,122 @@
+from __future__ import annotations
+
+import string
+from typing import Any
+
+from pse_core import StateGraph, StateId
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
+
+from pse.types.base.any import AnyStateMachine
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+
+UNRESERVED_CHARS = string.ascii_letters + string.digits + "-._~"
+SUB_DELIM_CHARS = "!$&'()*+,;="
+PATH_CHARS = UNRESERVED_CHARS + SUB_DELIM_CHARS + ":@"
+QUERY_CHARS = PATH_CHARS + "/?"
+SCHEME_CHARS = string.ascii_letters + string.digits + "+.-"
+
+
+def encoded(chars: str) -> StateMachine:
+    """
+    A non-empty run of `chars` and `%XX` percent-encoded octets.
+    """
+    octet = ChainStateMachine(
+        [PhraseStateMachine("%"), CharacterStateMachine(string.hexdigits, exact_length=2)]
+    )
+    run = CharacterStateMachine(chars, char_min=1)
+    state_graph: StateGraph = {
+        0: [(run, 1), (octet, 2)],
+        1: [(octet, 2)],
+        2: [(run, 1), (octet, 2)],
+    }
+    return StateMachine(state_graph, end_states=[1, 2])
+
+
+def any_scheme() -> StateMachine:
+    return ChainStateMachine(
+        [
+            CharacterStateMachine(string.ascii_letters, exact_length=1),
+            CharacterStateMachine(SCHEME_CHARS, is_optional=True),
+        ]
+    )
+
+
+class UriStateMachine(StateMachine):
+    """
+    Accepts an absolute URI with an authority, e.g. `https://example.com/path?x=1#frag`:
+    a scheme, `://`, an optional `user@`, a host, an optional `:port`, then an
+    optional path, query and fragment. Characters outside RFC 3986's allowed
+    sets must be percent-encoded.
+    """
+
+    def __init__(self, schemes: list[str] | None = None) -> None:
+        """
+        Args:
+            schemes: The allowed schemes, matched in any casing, or None to allow any.
+        """
+        if schemes is not None and not schemes:
+            raise ValueError("Allowed schemes cannot be empty.")
+
+        self.schemes = schemes
+        scheme = (
+            AnyStateMachine(
+                [PhraseStateMachine(scheme, is_case_sensitive=False) for scheme in schemes]
+            )
+            if schemes
+            else any_scheme()
+        )
+        slash = PhraseStateMachine("/")
+        question_mark = PhraseStateMachine("?")
+        hash_sign = PhraseStateMachine("#")
+        state_graph: StateGraph = {
+            0: [(scheme, 1)],
+            1: [(PhraseStateMachine("://"), 2)],
+            2: [
+                (encoded(UNRESERVED_CHARS + SUB_DELIM_CHARS + ":"), 3),
+                (encoded(UNRESERVED_CHARS + SUB_DELIM_CHARS), 5),
+            ],
+            3: [(PhraseStateMachine("@"), 4)],
+            4: [(encoded(UNRESERVED_CHARS + SUB_DELIM_CHARS), 5)],
+            # 5: after the host
+            5: [
+                (
+                    ChainStateMachine(
+                        [PhraseStateMachine(":"), CharacterStateMachine(string.digits, char_min=1)]
+                    ),
+                    6,
+                ),
+                (slash, 7),
+                (question_mark, 9),
+                (hash_sign, 11),
+            ],
+            6: [(slash, 7), (question_mark, 9), (hash_sign, 11)],
+            # 7: right after a slash in the path
+            7: [(encoded(PATH_CHARS), 8), (slash, 7), (question_mark, 9), (hash_sign, 11)],
+            8: [(slash, 7), (question_mark, 9), (hash_sign, 11)],
+            9: [(encoded(QUERY_CHARS), 10), (hash_sign, 11)],
+            10: [(hash_sign, 11)],
+            11: [(encoded(QUERY_CHARS), 12)],
+        }
+        super().__init__(state_graph, end_states=[5, 6, 7, 8, 9, 10, 11, 12])
+
+    def get_new_stepper(self, state: StateId | None = None) -> UriStepper:
+        return UriStepper(self, state)
+
+    def __str__(self) -> str:
+        return "Uri"
+
+
+class UriStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: UriStateMachine,
+        current_state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, current_state)
+        self.state_machine: UriStateMachine = state_machine
+
+    def get_current_value(self) -> Any:
+        return self.get_raw_value() or None
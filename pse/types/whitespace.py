@@ -6,23 +6,28 @@ characters in structured data formats like JSON.
 
 from __future__ import annotations
 
-from pse.types.base.character import CharacterStateMachine
+from pse.types.base.character import CharacterStateMachine, CharacterStepper
 
 # Whitespace characters as defined by the JSON standard
 WHITESPACE_CHARS = " \t\n\r"
+DEFAULT_MAX_WHITESPACE = 20
 
 
 class WhitespaceStateMachine(CharacterStateMachine):
     """Optional whitespace state machine using TokenTrie for efficient matching."""
 
-    def __init__(self, min_whitespace: int = 0, max_whitespace: int = 20):
+    def __init__(
+        self,
+        min_whitespace: int = 0,
+        max_whitespace: int = DEFAULT_MAX_WHITESPACE,
+    ):
         """Initialize the whitespace state machine with configurable limits.
 
         Args:
             min_whitespace: Minimum allowable whitespace characters.
                 Defaults to 0.
             max_whitespace: Maximum allowable whitespace characters.
-                Defaults to 20.
+                Defaults to 20. A value of 0 disallows whitespace entirely.
         """
         super().__init__(
             WHITESPACE_CHARS,
@@ -30,7 +35,34 @@ class WhitespaceStateMachine(CharacterStateMachine):
             char_limit=max_whitespace,
             is_optional=(min_whitespace == 0),
         )
+        self.max_whitespace = max_whitespace
+
+    def get_new_stepper(self, state: int | str) -> WhitespaceStepper:
+        return WhitespaceStepper(self)
 
     def __str__(self) -> str:
         """Return a string representation of this state machine."""
         return "Whitespace"
+
+
+class WhitespaceStepper(CharacterStepper):
+    """Stepper for WhitespaceStateMachine that honors a zero whitespace limit."""
+
+    def __init__(
+        self,
+        state_machine: WhitespaceStateMachine,
+        value: str | None = None,
+    ) -> None:
+        super().__init__(state_machine, value)
+        self.state_machine: WhitespaceStateMachine = state_machine
+
+    def get_valid_continuations(self, depth: int = 0) -> list[str]:
+        if self.state_machine.max_whitespace == 0:
+            return []
+        return super().get_valid_continuations(depth)
+
+    def should_start_step(self, token: str) -> bool:
+        # a limit of 0 would otherwise read as "unlimited" to the character stepper
+        if self.state_machine.max_whitespace == 0:
+            return False
+        return super().should_start_step(token)
@@ -0,0 +1,114 @@
+from __future__ import annotations
+
+import string
+from typing import Any, Self
+
+from pse_core import StateId
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
+
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.loop import LoopStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+from pse.types.whitespace import WhitespaceStateMachine
+from pse.types.xml.xml_tag import XMLTagStateMachine
+
+XML_NAME_CHARS = string.ascii_letters + string.digits + "_-.:"
+
+
+class XMLElementStateMachine(StateMachine):
+    """
+    Accepts an XML element: an opening tag, the inner state machine, and the matching closing tag.
+
+    Unlike `XMLEncapsulatedStateMachine`, no text is allowed before the opening tag.
+    """
+
+    def __init__(
+        self,
+        tag_name: str,
+        state_machine: StateMachine,
+        allow_attributes: bool = False,
+        max_attributes: int = 8,
+        is_optional: bool = False,
+    ) -> None:
+        """
+        Args:
+            tag_name: The name of the element's tag.
+            state_machine: The state machine for the element's content.
+            allow_attributes: Whether the opening tag may carry `name="value"` attributes.
+            max_attributes: Maximum number of attributes when they are allowed.
+        """
+        self.tag_name = tag_name
+        self.inner_state_machine = state_machine
+        if allow_attributes:
+            attribute = ChainStateMachine(
+                [
+                    WhitespaceStateMachine(min_whitespace=1),
+                    CharacterStateMachine(XML_NAME_CHARS),
+                    PhraseStateMachine('="'),
+                    CharacterStateMachine(blacklist_charset='"<&', is_optional=True),
+                    PhraseStateMachine('"'),
+                ]
+            )
+            opening_tag: StateMachine = ChainStateMachine(
+                [
+                    PhraseStateMachine(f"<{tag_name}"),
+                    LoopStateMachine(
+                        attribute,
+                        min_loop_count=0,
+                        max_loop_count=max_attributes,
+                    ),
+                    PhraseStateMachine(">"),
+                ]
+            )
+        else:
+            opening_tag = XMLTagStateMachine(tag_name)
+
+        super().__init__(
+            {
+                0: [(opening_tag, 1)],
+                1: [(state_machine, 2)],
+                2: [(XMLTagStateMachine(tag_name, closing_tag=True), "$")],
+            },
+            is_optional=is_optional,
+        )
+
+    def get_new_stepper(self, state: StateId | None = None) -> XMLElementStepper:
+        return XMLElementStepper(self, state)
+
+    def __str__(self) -> str:
+        return f"XMLElement({self.tag_name})"
+
+
+class XMLElementStepper(Stepper):
+    def __init__(
+        self,
+        state_machine: XMLElementStateMachine,
+        state: StateId | None = None,
+    ) -> None:
+        super().__init__(state_machine, state)
+        self.state_machine: XMLElementStateMachine = state_machine
+        self.inner_stepper: Stepper | None = None
+
+    def clone(self) -> Self:
+        clone = super().clone()
+        if self.inner_stepper:
+            clone.inner_stepper = self.inner_stepper.clone()
+        return clone
+
+    def add_to_history(self, stepper: Stepper) -> None:
+        if self.current_state == 2:
+            self.inner_stepper = stepper
+
+        return super().add_to_history(stepper)
+
+    def get_current_value(self) -> Any:
+        """
+        The value of the element's content, without the tags.
+        """
+        if self.inner_stepper is not None:
+            return self.inner_stepper.get_current_value()
+        if self.current_state == 1 and self.sub_stepper is not None:
+            return self.sub_stepper.get_current_value()
+        return None
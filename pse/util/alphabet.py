@@ -0,0 +1,70 @@
+"""Enumerate the literal characters a state machine can ever emit.
+
+Useful for checking that a tokenizer can express every character a structure needs.
+"""
+
+from pse_core.state_machine import StateMachine
+
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+from pse.types.base.wait_for import WaitFor
+from pse.types.json.json_object import ObjectSchemaStateMachine
+
+# stands in for character classes without a whitelist, which are unbounded
+UNBOUNDED_CHARSET: frozenset[str] = frozenset(chr(c) for c in range(0x20, 0x7F))
+
+
+def alphabet(state_machine: StateMachine) -> set[str]:
+    """
+    Get every literal character that can appear anywhere in the state machine's output.
+
+    Composed state machines are resolved recursively. Character classes without
+    a whitelist (free-form strings, grammars, regexes) contribute printable ASCII
+    minus their blacklist, rather than all of Unicode.
+
+    Args:
+        state_machine: The state machine to inspect.
+
+    Returns:
+        The set of single characters.
+    """
+    chars: set[str] = set()
+    visited: set[int] = set()
+    queue: list[StateMachine] = [state_machine]
+    while queue:
+        current = queue.pop()
+        if id(current) in visited:
+            continue
+        visited.add(id(current))
+
+        if isinstance(current, PhraseStateMachine):
+            chars.update(_with_cases(current.phrase, current.is_case_sensitive))
+        elif isinstance(current, CharacterStateMachine):
+            charset = current.charset or UNBOUNDED_CHARSET - current.blacklist_charset
+            chars.update(_with_cases(charset, current.is_case_sensitive))
+            chars.update(current.graylist_charset)
+        elif isinstance(current, WaitFor):
+            chars.update(UNBOUNDED_CHARSET)
+            queue.append(current.wait_for_sm)
+
+        states = {0, *current.state_graph}
+        if isinstance(current, ObjectSchemaStateMachine):
+            # properties are compiled lazily, in place of the free-form key-value edge
+            states.discard(2)
+            queue.extend(
+                current.get_property_state_machine(prop_name)
+                for prop_name in current.properties
+            )
+            required = dict.fromkeys(current.required_property_names)
+            queue.extend(current.get_property_state_machines(required))
+
+        for state in states:
+            queue.extend(edge for edge, _ in current.get_edges(state))
+
+    return chars
+
+
+def _with_cases(chars: str | set[str] | frozenset[str], case_sensitive: bool) -> set[str]:
+    if case_sensitive:
+        return set(chars)
+    return {variant for char in chars for variant in (char.lower(), char.upper())}
@@ -0,0 +1,108 @@
+"""Per-edge costs, so completion search can prefer cheap paths over merely short ones."""
+
+from __future__ import annotations
+
+from typing import TypeVar
+
+from pse_core import StateId
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
+
+StateMachineT = TypeVar("StateMachineT", bound=StateMachine)
+
+
+def set_cost(
+    state_machine: StateMachineT, state: StateId, edge_index: int, cost: float
+) -> StateMachineT:
+    """
+    Charge `cost` for taking one edge of this state machine's graph.
+
+    The edge is the `edge_index`-th edge out of `state` in `state_graph`, so
+    costs are keyed by `(state, edge index)` in the parent's edge table: a
+    sub-state machine shared by several edges is only charged where a cost was set.
+    Returns the state machine, so it can wrap a constructor call.
+
+    Raises:
+        ValueError: If the cost is negative or there is no such edge.
+        TypeError: If the state machine cannot hold attributes (a bare pse_core one).
+    """
+    if cost < 0:
+        raise ValueError("Edge costs cannot be negative.")
+    if not 0 <= edge_index < len(state_machine.state_graph.get(state, [])):
+        raise ValueError(f"State {state!r} has no edge {edge_index}.")
+    edge_costs = dict(get_edge_costs(state_machine))
+    edge_costs[(state, edge_index)] = cost
+    try:
+        state_machine.edge_costs = edge_costs  # type: ignore[attr-defined]
+    except AttributeError as error:
+        raise TypeError(
+            f"{state_machine} cannot hold edge costs; use a Python subclass."
+        ) from error
+    return state_machine
+
+
+def get_cost(state_machine: StateMachine, state: StateId, edge_index: int) -> float:
+    return get_edge_costs(state_machine).get((state, edge_index), 0.0)
+
+
+def get_edge_costs(state_machine: StateMachine) -> dict[tuple[StateId, int], float]:
+    return getattr(state_machine, "edge_costs", {})
+
+
+def path_cost(
+    stepper: Stepper, memo: dict[int, tuple[Stepper, float]] | None = None
+) -> float:
+    """
+    The total cost of the edges a stepper has taken, including nested ones.
+
+    The edges are recovered by walking the completed sub-steppers through the
+    state graph from state 0, then adding the edge of the current sub-stepper.
+    Completed sub-steppers never change, so their own costs are memoized by id.
+    The memo holds on to each sub-stepper, so an id is never reused by
+    another stepper while the memo is alive.
+    """
+    memo = {} if memo is None else memo
+    state_machine = stepper.state_machine
+    edge_costs = get_edge_costs(state_machine)
+    state: StateId | None = 0
+    cost = 0.0
+    for completed in stepper.history:
+        if id(completed) not in memo:
+            memo[id(completed)] = (completed, path_cost(completed, memo))
+        cost += memo[id(completed)][1]
+        if edge_costs and state is not None:
+            edge = _find_edge(state_machine, state, completed.state_machine)
+            if edge is None:
+                # the walk lost track of the state; charge no further edges
+                state = None
+            else:
+                cost += edge_costs.get((state, edge[0]), 0.0)
+                state = edge[1]
+
+    if stepper.sub_stepper is not None:
+        if edge_costs:
+            edge = _find_edge(
+                state_machine,
+                stepper.current_state,
+                stepper.sub_stepper.state_machine,
+                stepper.target_state,
+            )
+            if edge is not None:
+                cost += edge_costs.get((stepper.current_state, edge[0]), 0.0)
+        cost += path_cost(stepper.sub_stepper, memo)
+    return cost
+
+
+def _find_edge(
+    state_machine: StateMachine,
+    state: StateId,
+    sub_state_machine: StateMachine,
+    target_state: StateId | None = None,
+) -> tuple[int, StateId] | None:
+    """
+    The index and target of the first edge out of `state` through `sub_state_machine`.
+    """
+    for index, (edge, target) in enumerate(state_machine.state_graph.get(state, [])):
+        if edge is sub_state_machine and target_state in (None, target):
+            return index, target
+    return None
@@ -0,0 +1,54 @@
+"""Bounded debug summaries of large stepper sets, so debug logs stay readable."""
+
+from __future__ import annotations
+
+import os
+from collections.abc import Iterable
+from typing import Any
+
+MAX_LOGGED_STEPPERS_ENV = "PSE_DEBUG_MAX_STEPPERS"
+DEFAULT_MAX_LOGGED_STEPPERS = 10
+
+
+def get_max_logged_steppers() -> int:
+    """
+    The most distinct steppers to log per step, from `PSE_DEBUG_MAX_STEPPERS`.
+
+    Invalid or negative values fall back to the default.
+    """
+    try:
+        limit = int(os.environ.get(MAX_LOGGED_STEPPERS_ENV, DEFAULT_MAX_LOGGED_STEPPERS))
+    except ValueError:
+        return DEFAULT_MAX_LOGGED_STEPPERS
+    return limit if limit >= 0 else DEFAULT_MAX_LOGGED_STEPPERS
+
+
+def summarize_steppers(steppers: Iterable[Any], limit: int | None = None) -> str:
+    """
+    Describe a set of steppers in at most `limit` + 2 lines.
+
+    Identical steppers are listed once with a count, in order of first
+    appearance; distinct steppers past the limit are only counted.
+
+    Args:
+        steppers: The steppers to describe.
+        limit: The most distinct steppers to list, or None for `PSE_DEBUG_MAX_STEPPERS`.
+
+    Returns:
+        The summary.
+    """
+    if limit is None:
+        limit = get_max_logged_steppers()
+
+    counts: dict[str, int] = {}
+    for stepper in steppers:
+        description = repr(stepper)
+        counts[description] = counts.get(description, 0) + 1
+
+    total = sum(counts.values())
+    lines = [f"{total} steppers ({len(counts)} distinct)"]
+    for description, count in list(counts.items())[:limit]:
+        lines.append(f"  {description}" + (f" (x{count})" if count > 1 else ""))
+    if (hidden := len(counts) - limit) > 0:
+        lines.append(f"  ... and {hidden} more distinct")
+    return "\n".join(lines)
@@ -0,0 +1,16 @@
+class PseGrammarError(ValueError):
+    """
+    Raised when a structure cannot be compiled into a state machine,
+    e.g. because it references a definition that was never provided.
+    """
+
+    def __init__(self, message: str, reference: str | None = None) -> None:
+        super().__init__(message)
+        self.reference = reference
+
+
+class PseAmbiguityError(RuntimeError):
+    """
+    Raised when a structure that was declared unambiguous needs more than
+    one stepper, i.e. the engine would have to branch or backtrack.
+    """
@@ -0,0 +1,32 @@
+"""Fork a stepper towards a chosen target state, for lookahead in combinators."""
+
+from __future__ import annotations
+
+from pse_core import StateId
+from pse_core.stepper import Stepper
+
+
+def fork_to(stepper: Stepper, target_state: StateId) -> Stepper:
+    """
+    Clone the stepper, aimed at `target_state` through the edge that leads there.
+
+    Unlike branching, no token is needed: the fork simply starts the edge's
+    state machine from scratch. Any transition the stepper has in progress is
+    not carried over, and the original stepper is left untouched.
+
+    Raises:
+        ValueError: If no edge leads from the current state to `target_state`.
+    """
+    edges = stepper.state_machine.get_edges(stepper.current_state)
+    for state_machine, edge_target_state in edges:
+        if edge_target_state == target_state:
+            break
+    else:
+        raise ValueError(
+            f"No edge from state {stepper.current_state!r} to {target_state!r}"
+        )
+
+    fork = stepper.clone()
+    fork.target_state = target_state
+    fork.sub_stepper = state_machine.get_new_stepper(None)
+    return fork
@@ -0,0 +1,142 @@
+"""Measure the size and shape of a compiled grammar, to estimate its cost before use."""
+
+from __future__ import annotations
+
+from dataclasses import dataclass
+from typing import Any
+
+from pse_core import StateId
+from pse_core.state_machine import StateMachine
+
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.wait_for import WaitFor
+from pse.types.json import JSONSchemaSource, json_schema_state_machine
+from pse.types.json.json_key_value import KeyValueSchemaStateMachine
+from pse.types.json.json_object import (
+    ObjectSchemaStateMachine,
+    has_data_references,
+    resolve_data_references,
+)
+from pse.types.json.json_value import JsonStateMachine
+from pse.types.object import ObjectStateMachine
+
+
+@dataclass(frozen=True)
+class GrammarStats:
+    """
+    The size and shape of a grammar, counted over every distinct state machine in it.
+
+    Attributes:
+        state_count: The number of states across all state graphs.
+        edge_count: The number of edges across all state graphs.
+        max_depth: The deepest nesting of JSON objects and arrays.
+        has_unbounded_text: Whether any region accepts free text of unlimited length,
+            e.g. a string without `maxLength` or a free-form JSON value.
+    """
+
+    state_count: int
+    edge_count: int
+    max_depth: int
+    has_unbounded_text: bool
+
+
+def compile_stats(schema: JSONSchemaSource, **kwargs: Any) -> GrammarStats:
+    """
+    Compile a schema only to measure it; the compiled state machine is not kept.
+
+    Args:
+        schema: The schema to compile.
+        **kwargs: Passed on to `json_schema_state_machine`.
+
+    Returns:
+        The statistics of the compiled grammar.
+    """
+    _, state_machine = json_schema_state_machine(schema, **kwargs)
+    return grammar_stats(state_machine)
+
+
+def grammar_stats(state_machine: StateMachine) -> GrammarStats:
+    """
+    Measure a state machine and everything nested within it.
+
+    State machines shared between several edges are counted once. Free-form
+    JSON values are counted but not descended into, as they nest without limit.
+    """
+    counter = _Counter()
+    max_depth = counter.visit(state_machine)
+    return GrammarStats(
+        state_count=counter.state_count,
+        edge_count=counter.edge_count,
+        max_depth=max_depth,
+        has_unbounded_text=counter.has_unbounded_text,
+    )
+
+
+class _Counter:
+    def __init__(self) -> None:
+        self.state_count = 0
+        self.edge_count = 0
+        self.has_unbounded_text = False
+        # the nesting depth below each visited state machine, by id
+        self.depths: dict[int, int] = {}
+
+    def visit(self, state_machine: StateMachine) -> int:
+        from pse.types.array import ArrayStateMachine
+
+        if id(state_machine) in self.depths:
+            # already counted, or a recursive reference still being counted
+            return self.depths[id(state_machine)]
+        self.depths[id(state_machine)] = 0
+
+        states: set[StateId] = {state_machine.start_state, *state_machine.end_states}
+        children: list[StateMachine] = []
+        for state in {state_machine.start_state, *state_machine.state_graph}:
+            states.add(state)
+            if isinstance(state_machine, ObjectSchemaStateMachine) and state == 2:
+                # properties are compiled lazily, in place of the free-form key-value edge
+                properties = self.get_properties(state_machine)
+                self.edge_count += len(properties)
+                children.extend(properties)
+                continue
+            for edge, target in state_machine.get_edges(state):
+                states.add(target)
+                self.edge_count += 1
+                children.append(edge)
+        self.state_count += len(states)
+
+        if isinstance(state_machine, JsonStateMachine | WaitFor):
+            self.has_unbounded_text = True
+            children = [state_machine.wait_for_sm] if isinstance(state_machine, WaitFor) else []
+        elif (
+            isinstance(state_machine, CharacterStateMachine)
+            and not state_machine.charset
+            and not state_machine.char_limit
+        ):
+            self.has_unbounded_text = True
+
+        child_depth = max((self.visit(child) for child in children), default=0)
+        is_container = isinstance(state_machine, ObjectStateMachine | ArrayStateMachine)
+        depth = child_depth + 1 if is_container else child_depth
+        self.depths[id(state_machine)] = depth
+        return depth
+
+    @staticmethod
+    def get_properties(state_machine: ObjectSchemaStateMachine) -> list[StateMachine]:
+        properties: list[StateMachine] = []
+        for prop_name, prop_schema in state_machine.properties.items():
+            if has_data_references(prop_schema):
+                # measured without the keywords that depend on other properties
+                properties.append(
+                    KeyValueSchemaStateMachine(
+                        prop_name,
+                        resolve_data_references(prop_schema, {}),
+                        state_machine.context,
+                    )
+                )
+            else:
+                properties.append(state_machine.get_property_state_machine(prop_name))
+
+        # pattern and additional properties are only offered once the required ones are in
+        value = dict.fromkeys(state_machine.properties)
+        properties.extend(state_machine.get_property_state_machines(value))
+        return properties
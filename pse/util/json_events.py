@@ -0,0 +1,131 @@
+"""Incremental SAX-style events over JSON text, so large outputs can be processed without buffering."""
+
+import json
+from collections.abc import Callable
+from dataclasses import dataclass
+from enum import Enum
+from typing import Any
+
+LITERAL_CHARS = frozenset("0123456789+-.eEtruefalsn")
+
+
+class JsonEventType(Enum):
+    START_OBJECT = "start_object"
+    END_OBJECT = "end_object"
+    START_ARRAY = "start_array"
+    END_ARRAY = "end_array"
+    KEY = "key"
+    VALUE = "value"
+
+
+@dataclass
+class JsonEvent:
+    """
+    A single parsing event.
+
+    Attributes:
+        type: What happened.
+        value: The key name for KEY, the scalar for VALUE, otherwise None.
+    """
+
+    type: JsonEventType
+    value: Any = None
+
+
+class JsonEventParser:
+    """
+    Turns JSON text, fed in arbitrary chunks, into events as soon as they are complete.
+
+    Only the current string or scalar and the container nesting are buffered.
+    The text is assumed to be well-formed, as it is when fed from a structuring engine.
+    Characters outside of any JSON value are ignored, as are brackets that
+    close no open container.
+    """
+
+    def __init__(self, callback: Callable[[JsonEvent], None]) -> None:
+        self.callback = callback
+        self.reset()
+
+    def reset(self) -> None:
+        # each entry is True for an object, False for an array
+        self._containers: list[bool] = []
+        self._expects_key = False
+        self._in_string = False
+        self._is_escaped = False
+        self._buffer = ""
+        self._literal = ""
+
+    def feed(self, text: str) -> None:
+        for char in text:
+            self._feed_char(char)
+
+    def finish(self) -> None:
+        """
+        Emit a trailing scalar, which has no delimiter to end it.
+        """
+        self._flush_literal()
+
+    def _feed_char(self, char: str) -> None:
+        if self._in_string:
+            self._feed_string_char(char)
+            return
+
+        if self._literal:
+            if char in LITERAL_CHARS:
+                self._literal += char
+                return
+            self._flush_literal()
+
+        if char == "{":
+            self.callback(JsonEvent(JsonEventType.START_OBJECT))
+            self._containers.append(True)
+            self._expects_key = True
+        elif char == "}":
+            if not self._containers or not self._containers[-1]:
+                # not closing an open object, e.g. in free text around the JSON
+                return
+            self._containers.pop()
+            self._expects_key = False
+            self.callback(JsonEvent(JsonEventType.END_OBJECT))
+        elif char == "[":
+            self.callback(JsonEvent(JsonEventType.START_ARRAY))
+            self._containers.append(False)
+            self._expects_key = False
+        elif char == "]":
+            if not self._containers or self._containers[-1]:
+                return
+            self._containers.pop()
+            self.callback(JsonEvent(JsonEventType.END_ARRAY))
+        elif char == ",":
+            self._expects_key = bool(self._containers and self._containers[-1])
+        elif char == ":":
+            self._expects_key = False
+        elif char == '"':
+            self._in_string = True
+            self._buffer = ""
+        elif char in LITERAL_CHARS:
+            self._literal = char
+
+    def _feed_string_char(self, char: str) -> None:
+        if self._is_escaped:
+            self._is_escaped = False
+        elif char == "\\":
+            self._is_escaped = True
+        elif char == '"':
+            self._in_string = False
+            value = json.loads(f'"{self._buffer}"')
+            event_type = JsonEventType.KEY if self._expects_key else JsonEventType.VALUE
+            self.callback(JsonEvent(event_type, value))
+            self._expects_key = False
+            return
+        self._buffer += char
+
+    def _flush_literal(self) -> None:
+        if not self._literal:
+            return
+        literal, self._literal = self._literal, ""
+        try:
+            value = json.loads(literal)
+        except ValueError:
+            return
+        self.callback(JsonEvent(JsonEventType.VALUE, value))
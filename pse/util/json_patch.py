@@ -0,0 +1,76 @@
+"""Utility functions for describing changes between JSON values as JSON Patch (RFC 6902) operations."""
+
+from dataclasses import dataclass
+from typing import Any
+
+_MISSING = object()
+
+
+@dataclass
+class JsonPatchOp:
+    """
+    A single JSON Patch operation.
+
+    Attributes:
+        op: One of "add", "remove" or "replace".
+        path: A JSON Pointer (RFC 6901) to the changed location.
+        value: The new value; unused for "remove".
+    """
+
+    op: str
+    path: str
+    value: Any = None
+
+    def to_dict(self) -> dict[str, Any]:
+        if self.op == "remove":
+            return {"op": self.op, "path": self.path}
+        return {"op": self.op, "path": self.path, "value": self.value}
+
+
+def escape_pointer_token(token: str | int) -> str:
+    return str(token).replace("~", "~0").replace("/", "~1")
+
+
+def diff_json(old: Any, new: Any, path: str = "") -> list[JsonPatchOp]:
+    """
+    Compute the operations that turn `old` into `new`.
+
+    Objects are diffed key by key and arrays index by index, so a value that
+    fills in incrementally yields small operations rather than whole-value replacements.
+
+    Args:
+        old: The previous value, or `_MISSING` if there was none.
+        new: The current value.
+        path: The JSON Pointer of the values being compared.
+
+    Returns:
+        The patch operations, in application order.
+    """
+    if old is _MISSING:
+        return [JsonPatchOp("add", path, new)]
+
+    if isinstance(old, dict) and isinstance(new, dict):
+        ops: list[JsonPatchOp] = []
+        for key in old:
+            if key not in new:
+                ops.append(JsonPatchOp("remove", f"{path}/{escape_pointer_token(key)}"))
+        for key, value in new.items():
+            ops.extend(
+                diff_json(old.get(key, _MISSING), value, f"{path}/{escape_pointer_token(key)}")
+            )
+        return ops
+
+    if isinstance(old, list) and isinstance(new, list):
+        ops = []
+        for index in range(min(len(old), len(new))):
+            ops.extend(diff_json(old[index], new[index], f"{path}/{index}"))
+        for index in range(len(new), len(old))[::-1]:
+            ops.append(JsonPatchOp("remove", f"{path}/{index}"))
+        for index in range(len(old), len(new)):
+            ops.append(JsonPatchOp("add", f"{path}/{index}", new[index]))
+        return ops
+
+    if old == new and type(old) is type(new):
+        return []
+
+    return [JsonPatchOp("replace", path, new)]
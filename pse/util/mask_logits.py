@@ -0,0 +1,154 @@
+"""Utility functions for masking and biasing logits by token id.
+
+Used when a constraint or preference is expressed over token ids rather than text,
+so the vocabulary trie can be bypassed entirely.
+"""
+
+from collections.abc import Iterable
+from typing import Any
+
+import numpy as np
+
+# Optional dependencies
+try:
+    import mlx.core as mx
+
+    _HAS_MLX = True
+except ImportError:
+    _HAS_MLX = False
+
+try:
+    import jax.numpy as jnp
+
+    _HAS_JAX = True
+except ImportError:
+    _HAS_JAX = False
+
+try:
+    import torch
+
+    _HAS_TORCH = True
+except ImportError:
+    _HAS_TORCH = False
+
+
+def mask_logits_to_ids(logits: Any, allowed_ids: Iterable[int]) -> Any:
+    """
+    Set every logit outside `allowed_ids` to -inf.
+
+    Args:
+        logits: Array of shape (vocab_size,) or (batch, vocab_size)
+        allowed_ids: The token ids to keep
+
+    Returns:
+        A masked array of the same type and shape as `logits`
+
+    Raises:
+        TypeError: If logits type is not supported
+    """
+    vocab_size = logits.shape[-1]
+    keep = np.zeros(vocab_size, dtype=bool)
+    keep[[token_id for token_id in allowed_ids if 0 <= token_id < vocab_size]] = True
+    return _mask_logits(logits, keep)
+
+
+def _mask_logits(logits: Any, keep: np.ndarray) -> Any:
+    """
+    Set every logit where the boolean `keep` mask is False to -inf.
+    """
+    if _HAS_MLX and isinstance(logits, mx.array):
+        return mx.where(mx.array(keep), logits, float("-inf"))
+    elif isinstance(logits, np.ndarray):
+        return np.where(keep, logits, -np.inf).astype(logits.dtype)
+    elif _HAS_JAX and isinstance(logits, jnp.ndarray):
+        return jnp.where(jnp.asarray(keep), logits, -jnp.inf)
+    elif _HAS_TORCH and isinstance(logits, torch.Tensor):
+        mask = torch.from_numpy(keep).to(logits.device)
+        return logits.masked_fill(~mask, float("-inf"))
+    else:
+        raise TypeError(f"Unsupported array type: {type(logits)}")
+
+
+def bias_logits(logits: Any, token_ids: Iterable[int], bias: float) -> Any:
+    """
+    Add `bias` to the logits of `token_ids`, leaving every other logit untouched.
+
+    Masked (-inf) logits stay masked.
+
+    Args:
+        logits: Array of shape (vocab_size,) or (batch, vocab_size)
+        token_ids: The token ids to bias
+        bias: The additive bias; negative values penalize the tokens
+
+    Returns:
+        A biased array of the same type and shape as `logits`
+
+    Raises:
+        TypeError: If logits type is not supported
+    """
+    vocab_size = logits.shape[-1]
+    offsets = np.zeros(vocab_size, dtype=np.float32)
+    offsets[[token_id for token_id in token_ids if 0 <= token_id < vocab_size]] = bias
+
+    if _HAS_MLX and isinstance(logits, mx.array):
+        return logits + mx.array(offsets).astype(logits.dtype)
+    elif isinstance(logits, np.ndarray):
+        return (logits + offsets).astype(logits.dtype)
+    elif _HAS_JAX and isinstance(logits, jnp.ndarray):
+        return logits + jnp.asarray(offsets, dtype=logits.dtype)
+    elif _HAS_TORCH and isinstance(logits, torch.Tensor):
+        return logits + torch.from_numpy(offsets).to(logits.device, logits.dtype)
+    else:
+        raise TypeError(f"Unsupported array type: {type(logits)}")
+
+
+def mask_logits_from_ids(logits: Any, forbidden_ids: Iterable[int]) -> Any:
+    """
+    Set the logits of `forbidden_ids` to -inf, leaving every other logit untouched.
+
+    Args:
+        logits: Array of shape (vocab_size,) or (batch, vocab_size)
+        forbidden_ids: The token ids to mask out
+
+    Returns:
+        A masked array of the same type and shape as `logits`
+
+    Raises:
+        TypeError: If logits type is not supported
+    """
+    vocab_size = logits.shape[-1]
+    keep = np.ones(vocab_size, dtype=bool)
+    keep[[token_id for token_id in forbidden_ids if 0 <= token_id < vocab_size]] = False
+    return _mask_logits(logits, keep)
+
+
+def unmask_logits(logits: Any, raw_logits: Any, token_ids: Iterable[int]) -> Any:
+    """
+    Restore the raw logits of `token_ids`, undoing any masking applied to them.
+
+    Args:
+        logits: The masked array, of shape (vocab_size,) or (batch, vocab_size)
+        raw_logits: The array before masking, of the same type and shape
+        token_ids: The token ids to restore
+
+    Returns:
+        An array of the same type and shape as `logits`
+
+    Raises:
+        TypeError: If logits type is not supported
+    """
+    vocab_size = logits.shape[-1]
+    restore = np.zeros(vocab_size, dtype=bool)
+    restore[[token_id for token_id in token_ids if 0 <= token_id < vocab_size]] = True
+
+    if _HAS_MLX and isinstance(logits, mx.array):
+        return mx.where(mx.array(restore), raw_logits, logits)
+    elif isinstance(logits, np.ndarray):
+        return np.where(restore, raw_logits, logits).astype(logits.dtype)
+    elif _HAS_JAX and isinstance(logits, jnp.ndarray):
+        return jnp.where(jnp.asarray(restore), raw_logits, logits)
+    elif _HAS_TORCH and isinstance(logits, torch.Tensor):
+        mask = torch.from_numpy(restore).to(logits.device)
+        return torch.where(mask, raw_logits.to(logits.dtype), logits)
+    else:
+        raise TypeError(f"Unsupported array type: {type(logits)}")
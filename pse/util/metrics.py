@@ -0,0 +1,50 @@
+"""Cumulative engine counters, cheap to update and safe to read from another thread."""
+
+import threading
+
+
+class EngineMetrics:
+    """
+    Cumulative counters for a structuring engine.
+
+    Updates take a lock, so a serving wrapper can export a snapshot
+    from another thread while generation is running.
+    """
+
+    def __init__(self) -> None:
+        self._lock = threading.Lock()
+        self.tokens_processed = 0
+        self.tokens_healed = 0
+        self.generations_completed = 0
+        self.generations_stuck = 0
+        self.steps = 0
+        self.steppers_total = 0
+
+    def record_step(self, token_count: int, stepper_count: int, healed: bool = False) -> None:
+        with self._lock:
+            self.tokens_processed += token_count
+            self.tokens_healed += int(healed)
+            self.steps += 1
+            self.steppers_total += stepper_count
+
+    def record_generation(self, completed: bool) -> None:
+        with self._lock:
+            if completed:
+                self.generations_completed += 1
+            else:
+                self.generations_stuck += 1
+
+    def snapshot(self) -> dict[str, float]:
+        """
+        The current value of every counter, named in Prometheus style.
+        """
+        with self._lock:
+            return {
+                "pse_tokens_processed_total": self.tokens_processed,
+                "pse_tokens_healed_total": self.tokens_healed,
+                "pse_generations_completed_total": self.generations_completed,
+                "pse_generations_stuck_total": self.generations_stuck,
+                "pse_steppers_per_step_avg": (
+                    self.steppers_total / self.steps if self.steps else 0.0
+                ),
+            }
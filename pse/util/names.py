@@ -0,0 +1,36 @@
+"""Human-readable names for state machines, shown in diagnostics alongside their type."""
+
+from __future__ import annotations
+
+from typing import TypeVar
+
+from pse_core.state_machine import StateMachine
+
+StateMachineT = TypeVar("StateMachineT", bound=StateMachine)
+
+
+def set_name(state_machine: StateMachineT, name: str | None) -> StateMachineT:
+    """
+    Attach a name to a state machine, e.g. the schema title or property it was built from.
+
+    Returns the state machine, so it can wrap a constructor call.
+    A bare pse_core state machine cannot hold attributes and stays unnamed.
+    """
+    if name:
+        try:
+            state_machine.name = name  # type: ignore[attr-defined]
+        except AttributeError:
+            pass
+    return state_machine
+
+
+def get_name(state_machine: StateMachine) -> str | None:
+    return getattr(state_machine, "name", None)
+
+
+def describe(state_machine: StateMachine, name: str | None = None) -> str:
+    """
+    The state machine's type, qualified by its name (or `name`, if given), e.g. `Integer[age]`.
+    """
+    name = name or get_name(state_machine)
+    return f"{state_machine}[{name}]" if name else str(state_machine)
@@ -0,0 +1,134 @@
+"""Enumerate or sample strings accepted by a state machine, for tests and diagnostics."""
+
+import random
+from collections import deque
+
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
+
+from pse.util.alphabet import alphabet
+
+# bounds the search when few of the explored prefixes are accepted
+MAX_EXPANSIONS_PER_SAMPLE = 1_000
+
+
+def sample_accepting(
+    state_machine: StateMachine,
+    max_len: int,
+    limit: int,
+    seed: int | None = None,
+) -> list[str]:
+    """
+    Get up to `limit` distinct strings of at most `max_len` characters that
+    the state machine accepts.
+
+    Without a seed the search is breadth-first, so the shortest strings come first.
+    With a seed, samples come from random walks instead: diverse, and the same
+    for the same seed.
+
+    Args:
+        state_machine: The state machine to sample from.
+        max_len: Maximum length of each sample.
+        limit: Maximum number of samples.
+        seed: Seed for random walks, or None for breadth-first order.
+
+    Returns:
+        The accepted strings, in the order they were found.
+    """
+    free_form_moves = sorted(alphabet(state_machine))
+    if seed is None:
+        return _breadth_first(state_machine, max_len, limit, free_form_moves)
+    return _random_walks(state_machine, max_len, limit, free_form_moves, seed)
+
+
+def _breadth_first(
+    state_machine: StateMachine,
+    max_len: int,
+    limit: int,
+    free_form_moves: list[str],
+) -> list[str]:
+    samples: list[str] = []
+    seen: set[str] = {""}
+    queue: deque[tuple[str, list[Stepper]]] = deque()
+    queue.append(("", state_machine.get_steppers()))
+    max_expansions = limit * MAX_EXPANSIONS_PER_SAMPLE
+    expansions = 0
+    while queue and len(samples) < limit and expansions < max_expansions:
+        text, steppers = queue.popleft()
+        expansions += 1
+        if text and _is_accepted(steppers):
+            samples.append(text)
+
+        for move in _get_moves(steppers, free_form_moves):
+            next_text = text + move
+            if len(next_text) > max_len or next_text in seen:
+                continue
+            if next_steppers := _advance(state_machine, steppers, move):
+                seen.add(next_text)
+                queue.append((next_text, next_steppers))
+
+    return samples
+
+
+def _random_walks(
+    state_machine: StateMachine,
+    max_len: int,
+    limit: int,
+    free_form_moves: list[str],
+    seed: int,
+) -> list[str]:
+    rng = random.Random(seed)
+    samples: list[str] = []
+    for _ in range(limit * MAX_EXPANSIONS_PER_SAMPLE // 10):
+        if len(samples) >= limit:
+            break
+
+        text = ""
+        steppers = state_machine.get_steppers()
+        while True:
+            if text and _is_accepted(steppers) and rng.random() < 0.5:
+                break
+            moves = [
+                move
+                for move in _get_moves(steppers, free_form_moves)
+                if len(text) + len(move) <= max_len
+            ]
+            rng.shuffle(moves)
+            for move in moves:
+                if next_steppers := _advance(state_machine, steppers, move):
+                    text, steppers = text + move, next_steppers
+                    break
+            else:
+                break
+
+        if text and _is_accepted(steppers) and text not in samples:
+            samples.append(text)
+
+    return samples
+
+
+def _get_moves(steppers: list[Stepper], free_form_moves: list[str]) -> list[str]:
+    moves = {
+        continuation
+        for stepper in steppers
+        for continuation in stepper.get_valid_continuations()
+        if continuation
+    }
+    if any(stepper.accepts_any_token() for stepper in steppers):
+        moves.update(free_form_moves)
+    # sorted, so the same seed shuffles the same list
+    return sorted(moves)
+
+
+def _advance(
+    state_machine: StateMachine, steppers: list[Stepper], move: str
+) -> list[Stepper]:
+    return [
+        stepper
+        for stepper in state_machine.advance_all_basic(steppers, move)
+        if not stepper.remaining_input
+    ]
+
+
+def _is_accepted(steppers: list[Stepper]) -> bool:
+    return any(stepper.has_reached_accept_state() for stepper in steppers)
@@ -0,0 +1,162 @@
+"""Convert the regular subset of state machines to an equivalent regular expression."""
+
+from __future__ import annotations
+
+import re
+
+from pse_core import StateId
+from pse_core.state_machine import StateMachine
+
+from pse.types.base.any import AnyStateMachine
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.loop import LoopStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+from pse.types.enum import EnumStateMachine
+from pse.types.regex import RegexSetStateMachine, RegexStateMachine
+from pse.types.whitespace import WhitespaceStateMachine
+
+# state machines whose language is exactly the one of their state graph
+GRAPH_STATE_MACHINES = (StateMachine, ChainStateMachine, AnyStateMachine, EnumStateMachine)
+
+
+def to_regex(state_machine: StateMachine) -> str | None:
+    """
+    Get a regular expression accepting exactly what the state machine accepts.
+
+    Phrases, character sets, regexes, loops and plain state graphs are converted,
+    the graphs by state elimination. Anything validated beyond its state graph
+    (e.g. numbers with bounds, or JSON objects) cannot be expressed, and neither
+    can anything built from it.
+
+    Args:
+        state_machine: The state machine to convert.
+
+    Returns:
+        The pattern, to match in full with the `regex` module, or None if it cannot be expressed.
+    """
+    pattern = _to_pattern(state_machine)
+    if pattern is None or not state_machine.is_optional:
+        return pattern
+    return _optional(pattern)
+
+
+def _to_pattern(state_machine: StateMachine) -> str | None:
+    match state_machine:
+        case PhraseStateMachine():
+            pattern = re.escape(state_machine.phrase)
+            return pattern if state_machine.is_case_sensitive else f"(?i:{pattern})"
+        case RegexSetStateMachine():
+            return _union([f"(?:{pattern.pattern})" for pattern in state_machine.patterns])
+        case RegexStateMachine():
+            return f"(?:{state_machine.pattern.pattern})"
+        case WhitespaceStateMachine() if state_machine.max_whitespace == 0:
+            return ""
+        case CharacterStateMachine() if type(state_machine) in (
+            CharacterStateMachine,
+            WhitespaceStateMachine,
+        ):
+            return _character_pattern(state_machine)
+        case LoopStateMachine():
+            return _loop_pattern(state_machine)
+        case _ if type(state_machine) in GRAPH_STATE_MACHINES:
+            return _graph_pattern(state_machine)
+    return None
+
+
+def _character_pattern(state_machine: CharacterStateMachine) -> str | None:
+    if state_machine.graylist_charset or not state_machine.is_case_sensitive:
+        return None
+
+    if state_machine.charset:
+        chars = state_machine.charset - state_machine.blacklist_charset
+        if not chars:
+            return None
+        char_class = f"[{''.join(re.escape(char) for char in sorted(chars))}]"
+    else:
+        blacklist = "".join(re.escape(char) for char in sorted(state_machine.blacklist_charset))
+        char_class = f"[^{blacklist}]" if blacklist else "."
+
+    minimum = max(state_machine.char_min, 1)
+    maximum = state_machine.char_limit or ""
+    return f"{char_class}{{{minimum},{maximum}}}"
+
+
+def _loop_pattern(state_machine: LoopStateMachine) -> str | None:
+    if state_machine.stop_before is not None:
+        return None
+
+    item = to_regex(state_machine.state_graph[0][0][0])
+    if item is None:
+        return None
+
+    minimum = state_machine.min_loop_count
+    maximum = state_machine.max_loop_count
+    if state_machine.separator_state_machine is None:
+        upper = "" if maximum < 0 else maximum
+        return f"(?:{item}){{{minimum},{upper}}}"
+
+    separator = to_regex(state_machine.separator_state_machine)
+    if separator is None:
+        return None
+    upper = "" if maximum < 0 else maximum - 1
+    return f"(?:{item})(?:{separator}(?:{item})){{{minimum - 1},{upper}}}"
+
+
+def _graph_pattern(state_machine: StateMachine) -> str | None:
+    """
+    Convert a state graph by state elimination (Kleene's algorithm).
+    """
+    start: StateId = "__start__"
+    end: StateId = "__end__"
+    # edges[source][target] is the pattern of all edges between them; "" is the empty string
+    edges: dict[StateId, dict[StateId, str]] = {start: {state_machine.start_state: ""}}
+    states: list[StateId] = [state_machine.start_state]
+
+    def add_edge(source: StateId, target: StateId, pattern: str) -> None:
+        targets = edges.setdefault(source, {})
+        targets[target] = _union([targets[target], pattern]) if target in targets else pattern
+
+    for source, transitions in state_machine.state_graph.items():
+        if source not in states:
+            states.append(source)
+        for edge, target in transitions:
+            if target not in states:
+                states.append(target)
+            pattern = to_regex(edge)
+            if pattern is None:
+                return None
+            add_edge(source, target, pattern)
+
+    for end_state in state_machine.end_states:
+        if end_state not in states:
+            states.append(end_state)
+        add_edge(end_state, end, "")
+
+    for state in states:
+        outgoing = edges.pop(state, {})
+        loop = outgoing.pop(state, None)
+        prefix = f"(?:{loop})*" if loop else ""
+        for source, targets in edges.items():
+            if (incoming := targets.pop(state, None)) is None:
+                continue
+            for target, pattern in outgoing.items():
+                add_edge(source, target, incoming + prefix + pattern)
+
+    pattern = edges[start].get(end)
+    # no path from start to end accepts nothing at all
+    return pattern if pattern is not None else "(?!)"
+
+
+def _union(patterns: list[str]) -> str:
+    unique = list(dict.fromkeys(patterns))
+    if len(unique) == 1:
+        return unique[0]
+    if "" in unique:
+        unique.remove("")
+        return _optional(_union(unique))
+    return f"(?:{'|'.join(unique)})"
+
+
+def _optional(pattern: str) -> str:
+    return f"(?:{pattern})?" if pattern else ""
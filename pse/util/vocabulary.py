@@ -0,0 +1,38 @@
+"""Build the decoded vocabulary, including for tokenizers that cannot decode."""
+
+from __future__ import annotations
+
+from collections.abc import Callable, Mapping
+from typing import Any
+
+Decode = Callable[[list[int]], str]
+
+
+def build_vocabulary(vocab: Mapping[str, int], decode: Decode | None = None) -> dict[int, str]:
+    """
+    Map each token id to its decoded text.
+
+    Args:
+        vocab: The forward `token -> id` map.
+        decode: Decodes a list of token ids to text. If None, the tokens
+            themselves are used as their decoded text.
+
+    Returns:
+        The decoded text of every token id.
+    """
+    if decode is None:
+        return {token_id: token for token, token_id in vocab.items()}
+    return {token_id: decode([token_id]) for token_id in vocab.values()}
+
+
+def get_decode(tokenizer: Any) -> Decode:
+    """
+    Get a decode function for the tokenizer.
+
+    Tokenizers with only a forward vocabulary decode through its reversed map.
+    """
+    if callable(decode := getattr(tokenizer, "decode", None)):
+        return decode
+
+    vocabulary = build_vocabulary(tokenizer.get_vocab())
+    return lambda token_ids: "".join(vocabulary.get(token_id, "") for token_id in token_ids)
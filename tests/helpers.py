@@ -0,0 +1,22 @@
+"""Helpers shared by the unit tests."""
+
+from __future__ import annotations
+
+from pse_core.state_machine import StateMachine
+from pse_core.stepper import Stepper
+
+
+def accepts(
+    state_machine: StateMachine, value: str, steppers: list[Stepper] | None = None
+) -> bool:
+    """
+    Whether the state machine accepts the whole value, starting from `steppers`
+    if given, or from the state machine's initial steppers.
+    """
+    if steppers is None:
+        steppers = state_machine.get_steppers()
+    steppers = state_machine.advance_all_basic(steppers, value)
+    return any(
+        stepper.has_reached_accept_state() and not stepper.remaining_input
+        for stepper in steppers
+    )
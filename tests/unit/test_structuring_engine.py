@@ -16,7 +16,7 @@ try:
 except ImportError:
     _has_mlx = False
 
-from pse.structuring_engine import StructuringEngine
+from pse.structuring_engine import EngineMode, StructuringEngine, ValidationStatus
 
 logging.basicConfig(level=logging.DEBUG, stream=sys.stdout)
 
@@ -233,6 +233,25 @@ def test_logits_processing(engine: StructuringEngine) -> None:
     engine.reset(hard_reset=True)
 
 
+@pytest.mark.skipif(not _has_mlx, reason="mlx not installed")
+def test_select_best_valid_token(engine: StructuringEngine) -> None:
+    """Test that select skips higher-scoring invalid tokens."""
+    engine.configure(structure={"type": "string"})
+    scores = generate_mock_logits(
+        engine,
+        {
+            "Hello": 10.0,
+            '"': 2.0,
+            "Hi": 8.0,
+        },
+        mx.float32,
+    )
+    quote_token_id = engine.tokenizer.encode('"', add_special_tokens=False)[0]
+    assert engine.select(scores[None]) == quote_token_id
+    assert engine.steppers[0].get_raw_value() == '"'
+    engine.reset(hard_reset=True)
+
+
 def test_python_interpreter(engine: StructuringEngine) -> None:
     """Test that the python interpreter is working correctly."""
     python_state_machine = EncapsulatedStateMachine(
@@ -249,6 +268,28 @@ def test_python_interpreter(engine: StructuringEngine) -> None:
     assert output == "print('Hello, world!')"
 
 
+@pytest.mark.parametrize(
+    "text, expected",
+    [("1", 1), ("2.5", 2.5), ("true", True), ("null", None), ('"x"', "x")],
+)
+def test_mixed_enum_output_is_typed(
+    engine: StructuringEngine, text: str, expected: Any
+) -> None:
+    """Test that mixed-type enum values are output with their JSON types."""
+    engine.configure(
+        {
+            "type": "object",
+            "properties": {"value": {"enum": [1, 2.5, True, None, "x"]}},
+            "required": ["value"],
+        }
+    )
+    engine.consume_text(f'{{"value": {text}}}')
+    assert engine.has_reached_accept_state
+    output = engine.get_structured_output()
+    assert output == {"value": expected}
+    assert type(output["value"]) is type(expected)
+
+
 def test_get_structured_output_with_type(engine: StructuringEngine) -> None:
     """Test get_structured_output with a type parameter."""
 
@@ -340,3 +381,1565 @@ def test_configure_with_direct_state_machine(engine: StructuringEngine) -> None:
 
     # Reset for next test
     engine.reset(hard_reset=True)
+
+
+def test_strict_mode_forces_structure(engine: StructuringEngine) -> None:
+    """Test that strict mode only consults the model at value positions."""
+    strict_engine = StructuringEngine(engine.tokenizer, mode=EngineMode.STRICT)
+    strict_engine.configure(
+        {
+            "type": "object",
+            "properties": {"flag": {"type": "boolean"}},
+            "required": ["flag"],
+        }
+    )
+    # the key and punctuation are fixed; only the boolean is a choice
+    assert strict_engine.get_forced_continuation() == '{"flag":'
+    strict_engine.consume_text('{"flag":')
+    assert strict_engine.get_forced_continuation() == ""
+
+    strict_engine.consume_text("t")
+    assert strict_engine.get_forced_continuation() == "rue}"
+    strict_engine.consume_text("rue}")
+    assert strict_engine.has_reached_accept_state
+
+
+def test_strict_mode_rejects_whitespace(engine: StructuringEngine) -> None:
+    """Test that strict mode compiles schemas without optional whitespace."""
+    strict_engine = StructuringEngine(engine.tokenizer, mode=EngineMode.STRICT)
+    strict_engine.configure(
+        {
+            "type": "object",
+            "properties": {"value": {"type": "integer"}},
+            "required": ["value"],
+        }
+    )
+    steppers = strict_engine.state_machine.advance_all_basic(
+        strict_engine.steppers, "{ "
+    )
+    assert all(stepper.remaining_input for stepper in steppers)
+
+
+def test_replay_pinpoints_divergence(engine: StructuringEngine) -> None:
+    """Test that replay reports the index of the first token that could not be consumed."""
+    engine.configure(
+        {
+            "type": "object",
+            "properties": {"value": {"type": "integer"}},
+            "required": ["value"],
+        }
+    )
+    valid_token_ids = engine.tokenizer.encode('{"value": 4', add_special_tokens=False)
+    invalid_token_id = engine.tokenizer.convert_tokens_to_ids("]")
+    report = engine.replay([*valid_token_ids, invalid_token_id])
+
+    assert report.is_stuck
+    assert report.stuck_index == len(valid_token_ids)
+    assert report.consumed_token_ids == valid_token_ids
+    assert report.expected
+    assert not report.has_reached_accept_state
+
+    report = engine.replay(engine.tokenizer.encode('{"value": 4}', add_special_tokens=False))
+    assert not report.is_stuck
+    assert report.has_reached_accept_state
+    engine.reset(hard_reset=True)
+
+
+def test_replay_restores_primary_and_skips_metrics(engine: StructuringEngine) -> None:
+    """Test that replay starts on the primary again and leaves the metrics alone."""
+    replay_engine = StructuringEngine(engine.tokenizer)
+    primary = {
+        "type": "object",
+        "properties": {"count": {"type": "integer"}},
+        "required": ["count"],
+        "additionalProperties": False,
+    }
+    replay_engine.with_fallback(primary, {"type": "object"})
+    primary_state_machine = replay_engine.state_machine
+    replay_engine.consume_text('{"count": 1, "extra": true}')
+    assert replay_engine.state_machine is not primary_state_machine
+    snapshot = replay_engine.metrics_snapshot()
+
+    token_ids = engine.tokenizer.encode('{"count": 1', add_special_tokens=False)
+    report = replay_engine.replay(token_ids)
+    assert not report.is_stuck
+    assert replay_engine.state_machine is primary_state_machine
+    assert replay_engine.fallback_state_machine is not None
+    assert replay_engine.metrics_snapshot() == snapshot
+
+
+def test_capped_choice_mask_allows_every_alternative(engine: StructuringEngine) -> None:
+    """Test that capping a choice's branches never removes an alternative from the mask."""
+    import numpy as np
+
+    from pse.types.base.any import AnyStateMachine
+    from pse.types.base.phrase import PhraseStateMachine
+
+    choice_engine = StructuringEngine(engine.tokenizer)
+    choice_engine.configure(
+        AnyStateMachine(
+            [PhraseStateMachine(word) for word in ("x", "y", "z")],
+            max_transitions_per_stepper=1,
+        )
+    )
+    logits = np.zeros(len(engine.tokenizer.get_vocab()), dtype=np.float32)
+    adjusted_logits = choice_engine.process_logits(None, logits)
+    for word in ("x", "y", "z"):
+        assert np.isfinite(adjusted_logits[engine.tokenizer.convert_tokens_to_ids(word)])
+
+
+def test_is_complete_and_can_continue(engine: StructuringEngine) -> None:
+    """Test the aggregate completion checks across the engine's steppers."""
+    engine.configure({"type": "array", "items": {"type": "integer"}})
+    engine.consume_text("[1, 2")
+    assert engine.can_continue()
+    assert not engine.is_complete()
+
+    engine.consume_text("]")
+    assert engine.is_complete()
+    assert not engine.can_continue()
+    engine.reset(hard_reset=True)
+
+
+def test_is_complete_and_can_continue_open_ended(engine: StructuringEngine) -> None:
+    """Test that an open-ended structure is both complete and able to continue mid-array."""
+    from pse.types.base.loop import LoopStateMachine
+    from pse.types.base.phrase import PhraseStateMachine
+    from pse.types.integer import IntegerStateMachine
+
+    engine.configure(
+        LoopStateMachine(
+            IntegerStateMachine(),
+            min_loop_count=1,
+            max_loop_count=10,
+            separator_state_machine=PhraseStateMachine(","),
+        )
+    )
+    engine.consume_text("1,2")
+    assert engine.is_complete()
+    assert engine.can_continue()
+    engine.reset(hard_reset=True)
+
+
+def test_completed_items_before_array_closes(engine: StructuringEngine) -> None:
+    """Test that completed array items are available while the array is still open."""
+    engine.configure(
+        {
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {"a": {"type": "integer"}},
+                "required": ["a"],
+            },
+        }
+    )
+    engine.consume_text('[{"a":1}')
+    assert engine.completed_items() == [{"a": 1}]
+
+    engine.consume_text(',{"a":2}')
+    assert engine.completed_items() == [{"a": 1}, {"a": 2}]
+    assert not engine.has_reached_accept_state
+    engine.reset(hard_reset=True)
+
+
+def test_completed_items_of_root_array_only(engine: StructuringEngine) -> None:
+    """Test that only the root array's items are reported, never a nested array's."""
+    engine.configure(
+        {"type": "array", "items": {"type": "array", "items": {"type": "integer"}}}
+    )
+    engine.consume_text("[[1, 2], [3, 4")
+    assert engine.completed_items() == [[1, 2]]
+
+    engine.configure(
+        {
+            "type": "object",
+            "properties": {"tags": {"type": "array", "items": {"type": "integer"}}},
+        }
+    )
+    engine.consume_text('{"tags": [1, 2, 3')
+    assert engine.completed_items() == []
+    engine.reset(hard_reset=True)
+
+
+def test_token_id_constraint_bypasses_text_masking(engine: StructuringEngine) -> None:
+    """Test that a token id constraint masks logits to exactly the allowed ids."""
+    import numpy as np
+
+    from pse.types.base.chain import ChainStateMachine
+    from pse.types.base.phrase import PhraseStateMachine
+    from pse.types.base.token_id import TokenIdStateMachine
+
+    allowed_ids = {
+        engine.tokenizer.encode("yes", add_special_tokens=False)[0],
+        engine.tokenizer.encode("no", add_special_tokens=False)[0],
+    }
+    engine.configure(
+        ChainStateMachine(
+            [
+                PhraseStateMachine("answer: "),
+                TokenIdStateMachine(allowed_ids, engine.tokenizer.decode),
+            ]
+        )
+    )
+    assert engine.get_allowed_token_ids() is None
+
+    engine.consume_text("answer: ")
+    assert engine.get_allowed_token_ids() == allowed_ids
+
+    logits = np.zeros(len(engine.tokenizer.get_vocab()), dtype=np.float32)
+    adjusted_logits = engine.process_logits(None, logits)
+    assert set(np.flatnonzero(np.isfinite(adjusted_logits)).tolist()) == allowed_ids
+
+    engine.consume_text("yes")
+    assert engine.has_reached_accept_state
+    engine.reset(hard_reset=True)
+
+
+def test_leading_bom_is_ignored(engine: StructuringEngine) -> None:
+    """Test that a configured leading marker is accepted and left out of the output."""
+    schema = {
+        "type": "object",
+        "properties": {"a": {"type": "integer"}},
+        "required": ["a"],
+    }
+    bom_engine = StructuringEngine(engine.tokenizer, leading_markers=["\ufeff"])
+    bom_engine.configure(schema)
+    bom_engine.consume_text('\ufeff{"a": 1}')
+    assert bom_engine.has_reached_accept_state
+    assert bom_engine.get_structured_output() == {"a": 1}
+
+    # the marker stays optional
+    bom_engine.configure(schema)
+    bom_engine.consume_text('{"a": 2}')
+    assert bom_engine.has_reached_accept_state
+    assert bom_engine.get_structured_output() == {"a": 2}
+
+
+def test_accept_candidates_lists_each_reading(engine: StructuringEngine) -> None:
+    """Test that every distinct completed value is returned for an ambiguous grammar."""
+    from pse.types.base.any import AnyStateMachine
+    from pse.types.base.phrase import PhraseStateMachine
+    from pse.types.integer import IntegerStateMachine
+
+    engine.configure(AnyStateMachine([IntegerStateMachine(), PhraseStateMachine("12")]))
+    assert engine.accept_candidates() == []
+
+    engine.consume_text("12")
+    candidates = engine.accept_candidates()
+    assert len(candidates) == 2
+    assert {value for value, _ in candidates} == {12, "12"}
+    assert len({path for _, path in candidates}) == 2
+    engine.reset(hard_reset=True)
+
+
+def test_prefer_shorter_favors_closing_token(engine: StructuringEngine) -> None:
+    """Test that prefer-shorter biases the mask towards the accept-terminating token."""
+    import numpy as np
+
+    from pse.structuring_engine import CompletionPreference
+
+    schema = {
+        "type": "object",
+        "properties": {"a": {"type": "number"}},
+        "required": ["a"],
+    }
+    logits = np.zeros(len(engine.tokenizer.get_vocab()), dtype=np.float32)
+    close_id = engine.tokenizer.convert_tokens_to_ids("}")
+    digit_id = engine.tokenizer.convert_tokens_to_ids("0")
+
+    for preference in CompletionPreference:
+        biased_engine = StructuringEngine(
+            engine.tokenizer,
+            completion_preference=preference,
+            completion_bias=2.0,
+        )
+        biased_engine.configure(schema)
+        biased_engine.consume_text('{"a": 1')
+        adjusted_logits = biased_engine.process_logits(None, logits)
+        assert np.isfinite(adjusted_logits[close_id])
+        assert np.isfinite(adjusted_logits[digit_id])
+        if preference == CompletionPreference.SHORTER:
+            assert adjusted_logits[close_id] > adjusted_logits[digit_id]
+        elif preference == CompletionPreference.LONGER:
+            assert adjusted_logits[close_id] < adjusted_logits[digit_id]
+        else:
+            assert adjusted_logits[close_id] == adjusted_logits[digit_id]
+
+
+def test_terminal_token_ids_cached_per_stepper_state(
+    engine: StructuringEngine, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    """Test that terminal token ids are reused until the steppers change."""
+    engine.configure({"type": "object", "properties": {"a": {"type": "number"}}})
+    engine.consume_text('{"a": 1')
+    terminal_token_ids = engine.get_terminal_token_ids()
+
+    calls = []
+    advance_all_basic = engine.state_machine.advance_all_basic
+
+    def counting_advance_all_basic(*args):
+        calls.append(args)
+        return advance_all_basic(*args)
+
+    monkeypatch.setattr(
+        engine.state_machine, "advance_all_basic", counting_advance_all_basic
+    )
+    assert engine.get_terminal_token_ids() == terminal_token_ids
+    assert not calls
+
+    engine.consume_text("2")
+    engine.get_terminal_token_ids()
+    assert calls
+
+
+def test_zero_completion_bias_skips_terminal_tokens(
+    engine: StructuringEngine,
+) -> None:
+    """Test that a zero completion bias does not compute terminal token ids."""
+    import numpy as np
+
+    from pse.structuring_engine import CompletionPreference
+
+    biased_engine = StructuringEngine(
+        engine.tokenizer,
+        completion_preference=CompletionPreference.SHORTER,
+        completion_bias=0.0,
+    )
+    biased_engine.configure({"type": "object"})
+    biased_engine.consume_text("{")
+    biased_engine.get_terminal_token_ids = lambda: pytest.fail("computed")
+    logits = np.zeros(len(engine.tokenizer.get_vocab()), dtype=np.float32)
+    biased_engine.process_logits(None, logits)
+
+
+def test_set_constrained_passthrough_region(engine: StructuringEngine) -> None:
+    """Test that an unconstrained region is buffered and the grammar resumes where it paused."""
+    schema = {
+        "type": "object",
+        "properties": {"a": {"type": "integer"}},
+        "required": ["a"],
+    }
+    engine.configure(schema)
+    engine.consume_text('{"a": ')
+
+    engine.set_constrained(False)
+    engine.consume_text("let me think about this...")
+    assert engine.passthrough_buffer == "let me think about this..."
+
+    engine.set_constrained(True)
+    assert engine.passthrough_buffer == ""
+    engine.consume_text("1}")
+    assert engine.has_reached_accept_state
+    assert engine.get_structured_output() == {"a": 1}
+    engine.reset(hard_reset=True)
+
+
+def test_set_constrained_resyncs_valid_text(engine: StructuringEngine) -> None:
+    """Test that buffered text the grammar can consume is replayed into the steppers."""
+    engine.configure(
+        {
+            "type": "object",
+            "properties": {"a": {"type": "integer"}},
+            "required": ["a"],
+        }
+    )
+    engine.consume_text('{"a": ')
+
+    engine.set_constrained(False)
+    engine.consume_text("12")
+    engine.set_constrained(True)
+
+    engine.consume_text("}")
+    assert engine.has_reached_accept_state
+    assert engine.get_structured_output() == {"a": 12}
+    engine.reset(hard_reset=True)
+
+
+def test_reset_during_passthrough_resumes_constrained(engine: StructuringEngine) -> None:
+    """Test that a reset in an unconstrained region starts the next generation constrained."""
+    engine.configure(
+        {
+            "type": "object",
+            "properties": {"a": {"type": "integer"}},
+            "required": ["a"],
+        }
+    )
+    engine.set_constrained(False)
+    engine.consume_text("let me think about this...")
+
+    engine.reset()
+    assert engine.constrained
+    assert engine.passthrough_buffer == ""
+    engine.consume_text('{"a": 1}')
+    assert engine.has_reached_accept_state
+    assert engine.get_structured_output() == {"a": 1}
+    engine.reset(hard_reset=True)
+
+
+def test_single_quote_brace_token_closes_object(engine: StructuringEngine) -> None:
+    """Test that a tokenizer's single `"}` token closes a pattern-constrained string and the object."""
+    token_id = engine.tokenizer.convert_tokens_to_ids('"}')
+    if token_id == engine.tokenizer.unk_token_id:
+        pytest.skip('tokenizer has no single `"}` token')
+
+    engine.configure(
+        {
+            "type": "object",
+            "properties": {"code": {"type": "string", "pattern": "^[a-z]+$"}},
+            "required": ["code"],
+        }
+    )
+    engine.consume_text('{"code": "abc')
+    assert engine.consume(token_id) is not None
+    assert engine.has_reached_accept_state
+    assert engine.get_structured_output() == {"code": "abc"}
+    engine.reset(hard_reset=True)
+
+
+def test_expected_at_object_boundary(engine: StructuringEngine) -> None:
+    """Test that the expectations at an object boundary include `,` and `}`."""
+    from pse.structuring_engine import Expectation
+
+    engine.configure(
+        {
+            "type": "object",
+            "properties": {"a": {"type": "integer"}, "b": {"type": "integer"}},
+            "required": ["a"],
+        }
+    )
+    engine.consume_text('{"a": 1')
+    expectations = engine.expected()
+
+    assert expectations
+    assert all(isinstance(expectation, Expectation) for expectation in expectations)
+    assert all(expectation.label and expectation.examples for expectation in expectations)
+    examples = {example for expectation in expectations for example in expectation.examples}
+    assert "," in examples
+    assert "}" in examples
+    engine.reset(hard_reset=True)
+
+
+def test_expected_keeps_shortest_examples(engine: StructuringEngine) -> None:
+    """Test that truncated examples keep the shortest ones, not the first alphabetically."""
+    from pse.types.enum import EnumStateMachine
+
+    engine.configure(EnumStateMachine(["aaaa", "b"], require_quotes=False))
+    expectations = engine.expected(max_examples=1)
+    assert [expectation.examples for expectation in expectations] == [["b"]]
+    engine.reset(hard_reset=True)
+
+
+def test_take_patches_yields_add_for_new_key(engine: StructuringEngine) -> None:
+    """Test that a newly completed key is reported as a JSON Patch `add` operation."""
+    patch_engine = StructuringEngine(engine.tokenizer)
+    patch_engine.configure(
+        {
+            "type": "object",
+            "properties": {
+                "a": {"type": "integer"},
+                "b": {"type": "string"},
+                "c": {"type": "boolean"},
+            },
+            "required": ["a"],
+        }
+    )
+    patch_engine.consume_text('{"a": 1, ')
+    patch_engine.take_patches()
+
+    patch_engine.consume_text('"b": "x", ')
+    patches = patch_engine.take_patches()
+    assert [patch.to_dict() for patch in patches] == [
+        {"op": "add", "path": "/b", "value": "x"}
+    ]
+    assert patch_engine.take_patches() == []
+
+
+def test_take_patches_start_from_null_after_reset(engine: StructuringEngine) -> None:
+    """Test that patches after a reset rebuild the new output, not the previous one."""
+    patch_engine = StructuringEngine(engine.tokenizer)
+    patch_engine.configure({"type": "object", "properties": {"a": {"type": "integer"}}})
+    patch_engine.consume_text('{"a": 1}')
+    assert patch_engine.take_patches()
+
+    patch_engine.reset()
+    patch_engine.consume_text('{"a": 1}')
+    patches = patch_engine.take_patches()
+    assert [patch.to_dict() for patch in patches] == [
+        {"op": "replace", "path": "", "value": {"a": 1}}
+    ]
+
+
+def test_serialize_state_round_trip(engine: StructuringEngine) -> None:
+    """Test that a generation checkpointed mid-way resumes in a fresh engine."""
+    schema = {
+        "type": "object",
+        "properties": {"a": {"type": "integer"}, "b": {"type": "string"}},
+        "required": ["a", "b"],
+    }
+    engine.configure(schema)
+    for token_id in engine.tokenizer.encode('{"a": 1, "b": "hel', add_special_tokens=False):
+        assert engine.consume(token_id) is not None
+    serialized_state = engine.serialize_state()
+    engine.reset(hard_reset=True)
+
+    restored_engine = StructuringEngine(engine.tokenizer)
+    restored_engine.configure(schema)
+    restored_engine.deserialize_state(serialized_state)
+    assert not restored_engine.has_reached_accept_state
+
+    restored_engine.consume_text('lo"}')
+    assert restored_engine.has_reached_accept_state
+    assert restored_engine.get_structured_output() == {"a": 1, "b": "hello"}
+
+
+def test_serialize_state_keeps_text_and_tokens(engine: StructuringEngine) -> None:
+    """Test that text consumed between tokens is part of the checkpoint."""
+    schema = {
+        "type": "object",
+        "properties": {"a": {"type": "integer"}, "b": {"type": "string"}},
+        "required": ["a", "b"],
+    }
+    engine.configure(schema)
+    engine.consume_text('{"a": 1')
+    for token_id in engine.tokenizer.encode(', "b": "hel', add_special_tokens=False):
+        assert engine.consume(token_id) is not None
+    serialized_state = engine.serialize_state()
+    engine.reset(hard_reset=True)
+
+    restored_engine = StructuringEngine(engine.tokenizer)
+    restored_engine.configure(schema)
+    restored_engine.deserialize_state(serialized_state)
+    restored_engine.consume_text('lo"}')
+    assert restored_engine.has_reached_accept_state
+    assert restored_engine.get_structured_output() == {"a": 1, "b": "hello"}
+
+
+def test_deserialize_state_discards_in_progress_generation(
+    engine: StructuringEngine,
+) -> None:
+    """Test that restoring over a generation drops its pending bytes and fallback switch."""
+    restored_engine = StructuringEngine(engine.tokenizer)
+    restored_engine.with_fallback(
+        {
+            "type": "object",
+            "properties": {"count": {"type": "integer"}},
+            "required": ["count"],
+            "additionalProperties": False,
+        },
+        {"type": "object"},
+    )
+    primary_state_machine = restored_engine.state_machine
+    restored_engine.consume_text('{"count": 1')
+    serialized_state = restored_engine.serialize_state()
+
+    restored_engine.consume_text(', "extra": true}')
+    assert restored_engine.state_machine is not primary_state_machine
+    restored_engine.pending_token_ids = [0]
+
+    restored_engine.deserialize_state(serialized_state)
+    assert restored_engine.state_machine is primary_state_machine
+    assert restored_engine.fallback_state_machine is not None
+    assert restored_engine.pending_token_ids == []
+    restored_engine.consume_text("}")
+    assert restored_engine.get_structured_output() == {"count": 1}
+
+
+def test_deserialize_state_rejects_mismatched_structure(engine: StructuringEngine) -> None:
+    """Test that restoring a checkpoint against a different structure fails cleanly."""
+    engine.configure({"type": "object", "properties": {"a": {"type": "integer"}}})
+    for token_id in engine.tokenizer.encode('{"a": 1', add_special_tokens=False):
+        engine.consume(token_id)
+    serialized_state = engine.serialize_state()
+
+    engine.configure({"type": "array", "items": {"type": "integer"}})
+    with pytest.raises(ValueError):
+        engine.deserialize_state(serialized_state)
+    with pytest.raises(ValueError):
+        engine.deserialize_state("not json")
+    engine.reset(hard_reset=True)
+
+
+def test_metrics_snapshot_counts_advances(engine: StructuringEngine) -> None:
+    """Test that the metrics counters increment across several advances."""
+    metrics_engine = StructuringEngine(engine.tokenizer)
+    schema = {
+        "type": "object",
+        "properties": {"a": {"type": "integer"}},
+        "required": ["a"],
+    }
+    metrics_engine.configure(schema)
+    assert metrics_engine.metrics_snapshot()["pse_tokens_processed_total"] == 0
+
+    token_ids = metrics_engine.tokenizer.encode('{"a": 1}', add_special_tokens=False)
+    for token_id in token_ids:
+        metrics_engine.consume(token_id)
+    snapshot = metrics_engine.metrics_snapshot()
+    assert snapshot["pse_tokens_processed_total"] == len(token_ids)
+    assert snapshot["pse_generations_completed_total"] == 1
+    assert snapshot["pse_generations_stuck_total"] == 0
+    assert snapshot["pse_steppers_per_step_avg"] > 0
+
+    metrics_engine.configure(schema)
+    metrics_engine.consume(metrics_engine.tokenizer.convert_tokens_to_ids("]"))
+    metrics_engine.consume(metrics_engine.tokenizer.convert_tokens_to_ids("]"))
+    snapshot = metrics_engine.metrics_snapshot()
+    assert snapshot["pse_tokens_processed_total"] == len(token_ids) + 2
+    assert snapshot["pse_generations_stuck_total"] == 1
+
+
+def test_from_regex_masks_date_pattern(engine: StructuringEngine) -> None:
+    """Test that a regex-configured engine masks tokens to the pattern end to end."""
+    import numpy as np
+
+    regex_engine = StructuringEngine.from_regex(r"\d{4}-\d{2}-\d{2}", engine.tokenizer)
+    regex_engine.consume_text("2024-01-")
+    assert not regex_engine.has_reached_accept_state
+
+    logits = np.zeros(len(engine.tokenizer.get_vocab()), dtype=np.float32)
+    adjusted_logits = regex_engine.process_logits(None, logits)
+    digit_id = engine.tokenizer.convert_tokens_to_ids("1")
+    letter_id = engine.tokenizer.convert_tokens_to_ids("a")
+    dash_id = engine.tokenizer.convert_tokens_to_ids("-")
+    assert np.isfinite(adjusted_logits[digit_id])
+    assert not np.isfinite(adjusted_logits[letter_id])
+    assert not np.isfinite(adjusted_logits[dash_id])
+
+    regex_engine.consume_text("15")
+    assert regex_engine.has_reached_accept_state
+    assert regex_engine.is_complete()
+
+
+def test_regex_field_masks_to_pattern(engine: StructuringEngine) -> None:
+    """Test that a regex nested in a larger structure masks tokens to the pattern."""
+    import numpy as np
+
+    from pse.types.base.chain import ChainStateMachine
+    from pse.types.base.phrase import PhraseStateMachine
+    from pse.types.regex import RegexStateMachine
+
+    regex_engine = StructuringEngine(engine.tokenizer)
+    regex_engine.configure(
+        ChainStateMachine([PhraseStateMachine("id="), RegexStateMachine(r"[a-f]{2}")])
+    )
+    regex_engine.consume_text("id=")
+
+    logits = np.zeros(len(engine.tokenizer.get_vocab()), dtype=np.float32)
+    adjusted_logits = regex_engine.process_logits(None, logits)
+    assert np.isfinite(adjusted_logits[engine.tokenizer.convert_tokens_to_ids("a")])
+    assert np.isfinite(adjusted_logits[engine.tokenizer.convert_tokens_to_ids("f")])
+    assert not np.isfinite(adjusted_logits[engine.tokenizer.convert_tokens_to_ids("z")])
+    assert not np.isfinite(adjusted_logits[engine.tokenizer.convert_tokens_to_ids("1")])
+
+
+def test_from_regex_rejects_invalid_pattern(engine: StructuringEngine) -> None:
+    """Test that an invalid pattern fails at construction."""
+    with pytest.raises(ValueError):
+        StructuringEngine.from_regex(r"(\d", engine.tokenizer)
+
+
+def test_forbid_chars_masks_newline_tokens(engine: StructuringEngine) -> None:
+    """Test that tokens containing a forbidden newline are masked, spaces are kept."""
+    import numpy as np
+
+    single_line_engine = StructuringEngine(engine.tokenizer)
+    single_line_engine.configure(
+        {"type": "object", "properties": {"a": {"type": "integer"}}}
+    )
+    single_line_engine.consume_text("{")
+
+    logits = np.zeros(len(engine.tokenizer.get_vocab()), dtype=np.float32)
+    newline_id = engine.tokenizer.convert_tokens_to_ids("<0x0A>")
+    space_id = engine.tokenizer.convert_tokens_to_ids("▁")
+    adjusted_logits = single_line_engine.process_logits(None, logits)
+    assert np.isfinite(adjusted_logits[newline_id])
+    assert np.isfinite(adjusted_logits[space_id])
+
+    single_line_engine.forbid_chars({"\n"})
+    adjusted_logits = single_line_engine.process_logits(None, logits)
+    assert not np.isfinite(adjusted_logits[newline_id])
+    assert np.isfinite(adjusted_logits[space_id])
+    for token_id in np.flatnonzero(np.isfinite(adjusted_logits)).tolist():
+        assert "\n" not in single_line_engine.reverse_vocabulary.get(token_id, "")
+
+    single_line_engine.forbid_chars(())
+    adjusted_logits = single_line_engine.process_logits(None, logits)
+    assert np.isfinite(adjusted_logits[newline_id])
+
+
+def test_next_forced_tokens_closes_string(engine: StructuringEngine) -> None:
+    """Test that the closing quote is forced, as a token id, once a string is complete."""
+    forced_engine = StructuringEngine(engine.tokenizer)
+    forced_engine.configure(
+        {
+            "type": "object",
+            "properties": {"name": {"const": "Ada"}},
+            "required": ["name"],
+        }
+    )
+    forced_engine.consume_text('{"name": "Ada')
+    forced_token_ids = forced_engine.next_forced_tokens()
+    assert forced_token_ids
+    forced_text = "".join(
+        forced_engine.reverse_vocabulary[token_id] for token_id in forced_token_ids
+    )
+    assert forced_text.startswith('"')
+    # querying does not advance the engine
+    assert forced_engine.next_forced_tokens() == forced_token_ids
+
+    for token_id in forced_token_ids:
+        forced_engine.consume(token_id)
+    assert forced_engine.next_forced_tokens() == []
+
+
+def test_emit_forced_tokens_match_next_forced_tokens(engine: StructuringEngine) -> None:
+    """Test that emitting forced tokens returns the ids the query reports."""
+    forced_engine = StructuringEngine(engine.tokenizer)
+    forced_engine.configure(
+        {
+            "type": "object",
+            "properties": {"name": {"const": "Ada"}},
+            "required": ["name"],
+        }
+    )
+    forced_engine.consume_text('{"name": ')
+    forced_token_ids = forced_engine.next_forced_tokens()
+    assert forced_token_ids
+    assert forced_engine.emit_forced_tokens() == forced_token_ids
+
+
+def test_emit_forced_tokens_decode_to_consumed_text(engine: StructuringEngine) -> None:
+    """Test that emitted forced ids decode to exactly the text consumed mid-sequence."""
+    forced_engine = StructuringEngine(engine.tokenizer)
+    forced_engine.configure(
+        {
+            "type": "object",
+            "properties": {"name": {"const": "Ada"}},
+            "required": ["name"],
+        }
+    )
+    forced_engine.consume_text('{"name": ')
+    forced = forced_engine.get_forced_continuation()
+    assert forced
+
+    forced_token_ids = forced_engine.emit_forced_tokens()
+    forced_text = "".join(
+        forced_engine.reverse_vocabulary[token_id] for token_id in forced_token_ids
+    )
+    assert forced_text == forced
+    assert forced_engine.steppers[0].get_raw_value() == '{"name": ' + forced
+
+
+def test_emit_forced_tokens_consumes_only_tokenized_prefix(
+    engine: StructuringEngine,
+) -> None:
+    """Test that a forced continuation that tokenizes only in part is only consumed in part."""
+    forced_engine = StructuringEngine(engine.tokenizer)
+    forced_engine.configure(
+        {
+            "type": "object",
+            "properties": {"name": {"const": "Ada"}},
+            "required": ["name"],
+        }
+    )
+    forced_engine.consume_text('{"name": ')
+    assert forced_engine.get_forced_continuation().startswith('"Ada')
+
+    # a vocabulary without "d" stops the tokenization after '"A'
+    forced_engine._text_to_token_ids = {
+        text: [token_id]
+        for token_id, text in forced_engine.reverse_vocabulary.items()
+        if text in ('"', "A")
+    }
+    forced_token_ids = forced_engine.emit_forced_tokens()
+    forced_text = "".join(
+        forced_engine.reverse_vocabulary[token_id] for token_id in forced_token_ids
+    )
+    assert forced_text == '"A'
+    assert forced_engine.steppers[0].get_raw_value() == '{"name": "A'
+
+
+def test_emit_defaults_fills_required_field_on_early_close(
+    engine: StructuringEngine,
+) -> None:
+    """Test that closing an object early fills in a defaulted required field."""
+    import numpy as np
+
+    defaults_engine = StructuringEngine(engine.tokenizer, emit_defaults=True)
+    defaults_engine.configure(
+        {
+            "type": "object",
+            "properties": {
+                "city": {"type": "string"},
+                "units": {"type": "string", "default": "metric"},
+            },
+            "required": ["city", "units"],
+        }
+    )
+    defaults_engine.consume_text('{"city": "Oslo"')
+    close_id = engine.tokenizer.convert_tokens_to_ids("}")
+    assert defaults_engine.get_default_fill("}") == ', "units": "metric"'
+
+    logits = np.zeros(len(engine.tokenizer.get_vocab()), dtype=np.float32)
+    adjusted_logits = defaults_engine.process_logits(None, logits)
+    assert np.isfinite(adjusted_logits[close_id])
+
+    defaults_engine.consume(close_id)
+    assert defaults_engine.has_reached_accept_state
+    injected_text = "".join(
+        defaults_engine.reverse_vocabulary[token_id]
+        for token_id in defaults_engine.take_injected_token_ids()
+    )
+    assert injected_text == ', "units": "metric"'
+    assert defaults_engine.take_injected_token_ids() == []
+    output = defaults_engine.get_structured_output()
+    assert output == {"city": "Oslo", "units": "metric"}
+
+
+def test_sampled_early_close_commits_fill_with_token(engine: StructuringEngine) -> None:
+    """Test that a sampled early close fills in defaults, and nothing is kept if it fails."""
+    import numpy as np
+
+    defaults_engine = StructuringEngine(engine.tokenizer, emit_defaults=True)
+    defaults_engine.configure(
+        {
+            "type": "object",
+            "properties": {
+                "city": {"type": "string"},
+                "units": {"type": "string", "default": "metric"},
+            },
+            "required": ["city", "units"],
+        }
+    )
+    defaults_engine.consume_text('{"city": "Oslo"')
+    raw_value = defaults_engine.steppers[0].get_raw_value()
+    close_id = engine.tokenizer.convert_tokens_to_ids("}")
+    bracket_id = engine.tokenizer.convert_tokens_to_ids("]")
+    logits = np.zeros(len(engine.tokenizer.get_vocab()), dtype=np.float32)
+    logprobs = defaults_engine.process_logits(None, logits)[None]
+
+    sampled = defaults_engine._sample_with_default_fill(
+        logprobs, lambda _: np.array([bracket_id])
+    )
+    assert sampled is None
+    assert defaults_engine.steppers[0].get_raw_value() == raw_value
+
+    sampled = defaults_engine._sample_with_default_fill(
+        logprobs, lambda _: np.array([close_id])
+    )
+    assert sampled is not None and sampled[0][-1] == close_id
+    assert defaults_engine.has_reached_accept_state
+    output = defaults_engine.get_structured_output()
+    assert output == {"city": "Oslo", "units": "metric"}
+
+
+def test_default_fill_without_exact_tokenization_is_dropped(
+    engine: StructuringEngine,
+) -> None:
+    """Test that a fill the vocabulary can't spell in full is neither consumed nor injected."""
+    defaults_engine = StructuringEngine(engine.tokenizer, emit_defaults=True)
+    defaults_engine.configure(
+        {
+            "type": "object",
+            "properties": {
+                "city": {"type": "string"},
+                "units": {"type": "string", "default": "metric"},
+            },
+            "required": ["city", "units"],
+        }
+    )
+    defaults_engine.consume_text('{"city": "Oslo"')
+    raw_value = defaults_engine.steppers[0].get_raw_value()
+    # a vocabulary without "m" stops the tokenization of the fill part way
+    defaults_engine._text_to_token_ids = {
+        text: [token_id]
+        for token_id, text in defaults_engine.reverse_vocabulary.items()
+        if "m" not in text
+    }
+
+    defaults_engine._fill_defaults("}")
+    assert defaults_engine.take_injected_token_ids() == []
+    assert defaults_engine.steppers[0].get_raw_value() == raw_value
+
+
+def test_defaults_stay_optional_without_emit_defaults(engine: StructuringEngine) -> None:
+    """Test that a defaulted field can still be omitted when the option is off."""
+    plain_engine = StructuringEngine(engine.tokenizer)
+    plain_engine.configure(
+        {
+            "type": "object",
+            "properties": {
+                "city": {"type": "string"},
+                "units": {"type": "string", "default": "metric"},
+            },
+            "required": ["city", "units"],
+        }
+    )
+    plain_engine.consume_text('{"city": "Oslo"}')
+    assert plain_engine.has_reached_accept_state
+    assert plain_engine.get_default_fill("}") == ""
+
+
+def test_budget_aware_closure_allows_only_closing_tokens(
+    engine: StructuringEngine,
+) -> None:
+    """Test that near the token budget only structure-closing tokens are allowed."""
+    import numpy as np
+
+    schema = {"type": "array", "items": {"type": "integer"}}
+    logits = np.zeros(len(engine.tokenizer.get_vocab()), dtype=np.float32)
+    close_id = engine.tokenizer.convert_tokens_to_ids("]")
+    digit_id = engine.tokenizer.convert_tokens_to_ids("3")
+    comma_id = engine.tokenizer.convert_tokens_to_ids(",")
+
+    budget_engine = StructuringEngine(
+        engine.tokenizer, max_output_tokens=1, budget_aware_closure=True
+    )
+    budget_engine.configure(schema)
+    budget_engine.consume_text("[1, 2")
+    assert budget_engine.get_shortest_completion() == "]"
+    adjusted_logits = budget_engine.process_logits(None, logits)
+    assert np.isfinite(adjusted_logits[close_id])
+    assert not np.isfinite(adjusted_logits[digit_id])
+    assert not np.isfinite(adjusted_logits[comma_id])
+
+    budget_engine.max_output_tokens = 100
+    adjusted_logits = budget_engine.process_logits(None, logits)
+    assert np.isfinite(adjusted_logits[digit_id])
+    assert np.isfinite(adjusted_logits[comma_id])
+
+
+def test_budget_aware_closure_skips_search_far_from_budget(
+    engine: StructuringEngine, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    """Test that the shortest completion is not searched while the budget is far off."""
+    from pse.structuring_engine import MAX_CLOSURE_TOKENS
+
+    budget_engine = StructuringEngine(
+        engine.tokenizer,
+        max_output_tokens=MAX_CLOSURE_TOKENS + 1,
+        budget_aware_closure=True,
+    )
+    budget_engine.configure({"type": "array", "items": {"type": "integer"}})
+    budget_engine.consume_text("[1, 2")
+    monkeypatch.setattr(
+        budget_engine, "get_shortest_completion", lambda: pytest.fail("searched")
+    )
+    assert budget_engine.get_closure_token_ids() is None
+
+
+def test_no_backtrack_raises_on_ambiguity(engine: StructuringEngine) -> None:
+    """Test that branching raises in no-backtrack mode, while a deterministic grammar passes."""
+    from pse_core.state_machine import StateMachine
+
+    from pse.types.base.phrase import PhraseStateMachine
+    from pse.util.errors import PseAmbiguityError
+
+    strict_engine = StructuringEngine(engine.tokenizer, no_backtrack=True)
+    strict_engine.configure(
+        StateMachine(
+            {
+                0: [(PhraseStateMachine("a"), 1)],
+                1: [(PhraseStateMachine("b"), "$"), (PhraseStateMachine("bc"), "$")],
+            }
+        )
+    )
+    with pytest.raises(PseAmbiguityError):
+        strict_engine.consume_text("ab")
+
+    strict_engine.configure(
+        StateMachine(
+            {
+                0: [(PhraseStateMachine("a"), 1)],
+                1: [(PhraseStateMachine("b"), "$")],
+            }
+        )
+    )
+    strict_engine.consume_text("a")
+    assert len(strict_engine.steppers) == 1
+    strict_engine.consume_text("b")
+    assert strict_engine.has_reached_accept_state
+
+
+def test_event_callback_streams_events(engine: StructuringEngine) -> None:
+    """Test that the engine streams SAX-style events as the output is consumed."""
+    from pse.util.json_events import JsonEvent, JsonEventType
+
+    events: list[JsonEvent] = []
+    engine.set_event_callback(events.append)
+    engine.configure(
+        {
+            "type": "object",
+            "properties": {"tags": {"type": "array", "items": {"type": "string"}}},
+            "required": ["tags"],
+        }
+    )
+    engine.consume_text('{"tags": ["a"')
+    assert [event.type for event in events] == [
+        JsonEventType.START_OBJECT,
+        JsonEventType.KEY,
+        JsonEventType.START_ARRAY,
+        JsonEventType.VALUE,
+    ]
+    engine.consume_text("]}")
+    assert events[-2:] == [
+        JsonEvent(JsonEventType.END_ARRAY),
+        JsonEvent(JsonEventType.END_OBJECT),
+    ]
+    engine.set_event_callback(None)
+
+
+def test_event_callback_skips_rejected_text(engine: StructuringEngine) -> None:
+    """Test that text the steppers reject produces no events."""
+    from pse.util.json_events import JsonEvent, JsonEventType
+
+    events: list[JsonEvent] = []
+    event_engine = StructuringEngine(engine.tokenizer)
+    event_engine.set_event_callback(events.append)
+    event_engine.configure(
+        {
+            "type": "object",
+            "properties": {"a": {"type": "integer"}},
+            "required": ["a"],
+        }
+    )
+    event_engine.consume_text('{"a": 1')
+    sent = list(events)
+
+    event_engine.consume_text(']"b"')
+    assert not event_engine.steppers
+    assert events == sent
+    assert JsonEvent(JsonEventType.END_ARRAY) not in events
+
+
+def test_optional_root_permits_immediate_eos(engine: StructuringEngine) -> None:
+    """Test that an optional root grammar allows EOS before any token."""
+    import numpy as np
+
+    from pse.types.base.phrase import PhraseStateMachine
+
+    assert engine.tokenizer.eos_token_id is not None
+    logits = np.zeros(len(engine.tokenizer.get_vocab()), dtype=np.float32)
+
+    engine.configure(PhraseStateMachine("yes", is_optional=True))
+    assert engine.has_reached_accept_state
+    adjusted_logits = engine.process_logits(None, logits)
+    assert np.isfinite(adjusted_logits[engine.tokenizer.eos_token_id])
+
+    engine.consume_text("y")
+    assert not engine.has_reached_accept_state
+    adjusted_logits = engine.process_logits(None, logits)
+    assert not np.isfinite(adjusted_logits[engine.tokenizer.eos_token_id])
+
+    engine.configure({"type": "object"}, accept_empty=True)
+    assert engine.is_complete()
+    engine.configure({"type": "object"})
+    assert not engine.is_complete()
+
+
+def test_cjk_max_length_masks_extra_character(engine: StructuringEngine) -> None:
+    """Test that a 5-character limit masks out a 6th CJK token but allows the closing quote."""
+    import numpy as np
+
+    vocab = engine.tokenizer.get_vocab()
+    extra_id = engine.tokenizer.convert_tokens_to_ids("人")
+    quote_id = engine.tokenizer.convert_tokens_to_ids('"')
+    assert extra_id != engine.tokenizer.unk_token_id
+
+    engine.configure({"type": "string", "maxLength": 5})
+    engine.consume_text('"中文字日本')
+    logits = np.zeros(len(vocab), dtype=np.float32)
+    adjusted_logits = engine.process_logits(None, logits)
+    assert not np.isfinite(adjusted_logits[extra_id])
+    assert np.isfinite(adjusted_logits[quote_id])
+
+
+def test_expectation_shows_property_name(engine: StructuringEngine) -> None:
+    """Test that a stuck-state expectation names the property being generated."""
+    engine.configure(
+        {
+            "type": "object",
+            "properties": {"age": {"type": "integer"}},
+            "required": ["age"],
+        }
+    )
+    engine.consume_text('{"age": ')
+    labels = [expectation.label for expectation in engine.expected()]
+    assert any(label.endswith("[age]") for label in labels)
+
+
+def test_composite_engine_combines_grammar_and_forbidden_words(
+    engine: StructuringEngine,
+) -> None:
+    """Test that only tokens allowed by both the JSON grammar and the word list survive."""
+    import numpy as np
+
+    from pse.composite_engine import CompositeEngine, ForbiddenWordsConstraint
+
+    d_id = engine.tokenizer.convert_tokens_to_ids("d")
+    t_id = engine.tokenizer.convert_tokens_to_ids("t")
+    newline_id = engine.tokenizer.convert_tokens_to_ids("<0x0A>")
+    logits = np.zeros(len(engine.tokenizer.get_vocab()), dtype=np.float32)
+
+    engine.configure({"type": "string"})
+    composite = CompositeEngine(
+        [engine, ForbiddenWordsConstraint(engine.tokenizer, ["bad"])]
+    )
+    composite.consume_text('"ba')
+    adjusted_logits = composite.process_logits(None, logits)
+    assert not np.isfinite(adjusted_logits[d_id]), "forbidden by the word list"
+    assert not np.isfinite(adjusted_logits[newline_id]), "forbidden by the grammar"
+    assert np.isfinite(adjusted_logits[t_id])
+
+    composite.consume(t_id)
+    adjusted_logits = composite.process_logits(None, logits)
+    assert np.isfinite(adjusted_logits[d_id])
+
+
+def test_composite_engine_masks_forbidden_words_once(
+    engine: StructuringEngine, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    """Test that the forbidden sets of several word lists are unioned into one mask."""
+    import numpy as np
+
+    from pse import composite_engine
+    from pse.composite_engine import CompositeEngine, ForbiddenWordsConstraint
+
+    masked: list[set[int]] = []
+    original = composite_engine.mask_logits_from_ids
+
+    def mask_logits_from_ids(logits, token_ids):
+        masked.append(set(token_ids))
+        return original(logits, token_ids)
+
+    bad = ForbiddenWordsConstraint(engine.tokenizer, ["bad"])
+    worse = ForbiddenWordsConstraint(engine.tokenizer, ["worse"])
+    composite = CompositeEngine([bad, worse])
+    composite.consume_text("ba")
+    expected = bad.get_forbidden_token_ids() | worse.get_forbidden_token_ids()
+
+    monkeypatch.setattr(composite_engine, "mask_logits_from_ids", mask_logits_from_ids)
+    logits = np.zeros(len(engine.tokenizer.get_vocab()), dtype=np.float32)
+    composite.process_logits(None, logits)
+    assert masked == [expected]
+
+
+def test_shortest_completion_minimizes_edge_cost(engine: StructuringEngine) -> None:
+    """Test that a shorter but costly path loses to a longer, cheaper one."""
+    from pse_core.state_machine import StateMachine
+
+    from pse.types.base.phrase import PhraseStateMachine
+    from pse.util.costs import set_cost
+
+    class Grammar(StateMachine):
+        """A plain state graph that can hold edge costs."""
+
+    def build(shortcut_cost: float) -> StateMachine:
+        grammar = Grammar(
+            {
+                0: [
+                    (PhraseStateMachine("ab"), "$"),
+                    (PhraseStateMachine("x"), 1),
+                ],
+                1: [(PhraseStateMachine("y"), 2)],
+                2: [(PhraseStateMachine("z"), "$")],
+            }
+        )
+        return set_cost(grammar, 0, 0, shortcut_cost)
+
+    engine.configure(build(0))
+    assert engine.get_shortest_completion() == "ab"
+    engine.configure(build(5))
+    assert engine.get_shortest_completion() == "xyz"
+
+
+def test_single_valid_token(engine: StructuringEngine) -> None:
+    """Test that the only valid token is returned, and None when there is a choice."""
+    from pse.types.base.token_id import TokenIdStateMachine
+
+    token_id = engine.tokenizer.convert_tokens_to_ids("{")
+    engine.configure(TokenIdStateMachine([token_id], engine.tokenizer.decode))
+    assert engine.single_valid_token() == token_id
+    assert engine.single_valid_token(advance=True) == token_id
+    assert engine.has_reached_accept_state
+
+    engine.configure({"type": "string"})
+    engine.consume_text('"')
+    assert engine.single_valid_token() is None
+
+
+def test_single_valid_token_skips_masking(
+    engine: StructuringEngine, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    """Test that the only valid token is found from continuations, without masking."""
+    from pse.types.base.phrase import PhraseStateMachine
+
+    single_engine = StructuringEngine(engine.tokenizer)
+    single_engine.configure(PhraseStateMachine("{"))
+
+    def fail(*_: object) -> None:
+        raise AssertionError("process_logits should not be called")
+
+    monkeypatch.setattr(single_engine, "process_logits", fail)
+    # tokens running past the phrase, e.g. `{"`, are candidates but not valid
+    assert single_engine.single_valid_token() == engine.tokenizer.convert_tokens_to_ids("{")
+
+    single_engine.configure({"type": "string"})
+    single_engine.consume_text('"')
+    assert single_engine.single_valid_token() is None
+
+
+def test_next_structural_event(engine: StructuringEngine) -> None:
+    """Test that the engine reports the structure it expects next."""
+    from pse.structuring_engine import StructuralHintKind
+
+    engine.configure(
+        {
+            "type": "object",
+            "properties": {"count": {"type": "number"}},
+            "required": ["count"],
+        }
+    )
+    hint = engine.next_structural_event()
+    assert hint is not None and hint.kind == StructuralHintKind.VALUE
+
+    engine.consume_text("{")
+    hint = engine.next_structural_event()
+    assert hint is not None and hint.kind == StructuralHintKind.KEY
+
+    engine.consume_text('"count": ')
+    hint = engine.next_structural_event()
+    assert hint is not None and hint.kind == StructuralHintKind.VALUE
+    assert hint.value_type is not None and "Number" in hint.value_type
+
+    engine.consume_text("1}")
+    assert engine.next_structural_event() is None
+
+
+def test_with_fallback_continues_after_primary_dies(engine: StructuringEngine) -> None:
+    """Test that generation continues on the looser fallback once the primary dies."""
+    engine.with_fallback(
+        {
+            "type": "object",
+            "properties": {"count": {"type": "integer"}},
+            "required": ["count"],
+            "additionalProperties": False,
+        },
+        {"type": "object"},
+    )
+    engine.consume_text('{"count": 1')
+    assert engine.fallback_state_machine is not None
+
+    # a second key is invalid for the primary but fine for the fallback
+    engine.consume_text(', "extra": true}')
+    assert engine.fallback_state_machine is None
+    assert engine.has_reached_accept_state
+    assert engine.get_structured_output() == {"count": 1, "extra": True}
+
+
+def test_process_logits_masks_by_fallback_once_primary_died(
+    engine: StructuringEngine,
+) -> None:
+    """Test that logits are masked by the fallback once the primary has no steppers left."""
+    import numpy as np
+
+    engine.with_fallback(
+        {
+            "type": "object",
+            "properties": {"count": {"type": "integer"}},
+            "required": ["count"],
+            "additionalProperties": False,
+        },
+        {"type": "object"},
+    )
+    engine.consume_text("{")
+    fallback = engine.fallback_state_machine
+    engine.steppers = []
+
+    logits = np.zeros(len(engine.tokenizer.get_vocab()), dtype=np.float32)
+    adjusted = engine.process_logits(None, logits)
+    assert engine.state_machine is fallback
+    assert engine.steppers
+    assert np.isfinite(adjusted).any()
+
+
+def test_reset_returns_to_start(engine: StructuringEngine) -> None:
+    """Test that reset puts the engine back at the start of the structure."""
+    engine.configure(
+        {
+            "type": "object",
+            "properties": {"count": {"type": "integer"}},
+            "required": ["count"],
+        }
+    )
+    start_continuations = {
+        continuation
+        for stepper in engine.steppers
+        for continuation in stepper.get_valid_continuations()
+    }
+    engine.consume_text('{"count": 4')
+    assert engine.reset_count == 0
+
+    engine.reset()
+    assert engine.reset_count == 1
+    assert engine.output_token_count == 0
+    assert not engine.has_reached_accept_state
+    assert {
+        continuation
+        for stepper in engine.steppers
+        for continuation in stepper.get_valid_continuations()
+    } == start_continuations
+
+    engine.consume_text('{"count": 2}')
+    assert engine.get_structured_output() == {"count": 2}
+
+
+def test_from_regex_set_reports_matched_pattern(engine: StructuringEngine) -> None:
+    """Test that output matching either pattern is accepted, with the pattern index reported."""
+    regex_engine = StructuringEngine.from_regex_set(
+        [r"\d{4}-\d{2}-\d{2}", r"[a-z]+@[a-z]+\.com"], engine.tokenizer
+    )
+    regex_engine.consume_text("me@example.com")
+    assert regex_engine.has_reached_accept_state
+    assert regex_engine.matched_pattern_index() == 1
+
+    regex_engine = StructuringEngine.from_regex_set(
+        [r"\d{4}-\d{2}-\d{2}", r"[a-z]+"], engine.tokenizer
+    )
+    regex_engine.consume_text("2024-01-15")
+    assert regex_engine.matched_pattern_index() == 0
+
+
+def test_finalize_closes_open_structures(engine: StructuringEngine) -> None:
+    """Test that stopping mid-object yields a valid closed object with close_open."""
+    engine.configure(
+        {
+            "type": "object",
+            "properties": {
+                "name": {"type": "string"},
+                "tags": {"type": "array", "items": {"type": "string"}},
+            },
+            "required": ["name", "tags"],
+        }
+    )
+    engine.consume_text('{"name": "Ada", "tags": ["math", "eng')
+    assert engine.finalize() is None
+    assert engine.finalize(close_open=True) == {"name": "Ada", "tags": ["math", "eng"]}
+    assert engine.has_reached_accept_state
+
+
+def test_would_accept_leaves_engine_unchanged(engine: StructuringEngine) -> None:
+    """Test that probing a token reports its validity without consuming it."""
+    open_brace_id = engine.tokenizer.convert_tokens_to_ids("{")
+    close_brace_id = engine.tokenizer.convert_tokens_to_ids("}")
+    engine.configure(
+        {
+            "type": "object",
+            "properties": {"count": {"type": "integer"}},
+            "required": ["count"],
+        }
+    )
+    steppers = list(engine.steppers)
+    raw_values = [stepper.get_raw_value() for stepper in steppers]
+    assert engine.would_accept(open_brace_id)
+    assert not engine.would_accept(close_brace_id)
+    assert not engine.would_accept(engine.tokenizer.eos_token_id)
+    assert engine.steppers == steppers
+    assert [stepper.get_raw_value() for stepper in engine.steppers] == raw_values
+
+    engine.consume(open_brace_id)
+    assert engine.would_accept(engine.tokenizer.convert_tokens_to_ids('"'))
+
+
+def test_valid_next_chars_inside_number(engine: StructuringEngine) -> None:
+    """Test that inside a decimal number only digits and the decimal point may follow."""
+    from pse.types.number import CanonicalForm, NumberStateMachine
+
+    engine.configure(NumberStateMachine(canonical_form=CanonicalForm.ALWAYS_DECIMAL))
+    engine.consume_text("12")
+    assert engine.valid_next_chars() == set("0123456789.")
+
+    engine.consume_text(".")
+    assert engine.valid_next_chars() == set("0123456789")
+
+
+def test_ambiguity_report_ordered_below_unordered(engine: StructuringEngine) -> None:
+    """Test that ordered object keys keep fewer steppers alive than unordered ones."""
+    schema: dict[str, Any] = {
+        "type": "object",
+        "properties": {
+            "alpha": {"type": "boolean"},
+            "beta": {"type": "boolean"},
+            "gamma": {"type": "boolean"},
+        },
+        "required": ["alpha", "beta", "gamma"],
+        "additionalProperties": False,
+    }
+    engine.configure(schema)
+    ordered = engine.ambiguity_report(samples=5, max_len=60)
+    engine.configure({**schema, "orderedProperties": False})
+    unordered = engine.ambiguity_report(samples=5, max_len=60)
+
+    assert ordered.sample_count and ordered.token_count
+    assert unordered.sample_count and unordered.token_count
+    assert ordered.max_steppers <= unordered.max_steppers
+    assert ordered.avg_steppers < unordered.avg_steppers
+
+
+def test_validate_message(engine: StructuringEngine) -> None:
+    """Test that a correct message validates and an incorrect one reports where it broke."""
+    engine.configure(
+        {
+            "type": "object",
+            "properties": {"count": {"type": "integer"}},
+            "required": ["count"],
+        }
+    )
+    steppers = list(engine.steppers)
+
+    result = engine.validate_message('{"count": 3}')
+    assert result.status == ValidationStatus.VALID
+    assert result.is_valid
+    assert result.error_index is None
+
+    result = engine.validate_message('{"count": "three"}')
+    assert result.status == ValidationStatus.PARTIAL
+    assert result.valid_prefix == '{"count": '
+    assert result.error_index == len('{"count": ')
+    assert result.error_token_index is not None
+    token_ids = engine._tokenize_greedily('{"count": "three"}')
+    token_start = len(
+        "".join(engine.reverse_vocabulary[i] for i in token_ids[: result.error_token_index])
+    )
+    error_token = engine.reverse_vocabulary[token_ids[result.error_token_index]]
+    assert token_start <= result.error_index < token_start + len(error_token)
+
+    result = engine.validate_message('{"count": 3')
+    assert result.status == ValidationStatus.PARTIAL
+    assert result.error_index is None
+    assert result.error_token_index is None
+    assert result.expected
+
+    assert engine.validate_message("count: 3").status == ValidationStatus.INVALID
+    assert engine.steppers == steppers
+
+
+def test_character_split_across_byte_tokens(engine: StructuringEngine) -> None:
+    """Test that an emoji emitted as separate byte tokens is consumed once complete."""
+    engine.configure(
+        {
+            "type": "object",
+            "properties": {"value": {"type": "string"}},
+            "required": ["value"],
+        }
+    )
+    engine.consume_text('{"value": "')
+    byte_token_ids = [
+        engine.tokenizer.convert_tokens_to_ids(f"<0x{byte:02X}>")
+        for byte in "😀".encode()
+    ]
+    for token_id in byte_token_ids[:-1]:
+        assert engine.consume(token_id) == token_id
+        assert engine.pending_token_ids
+    assert engine.consume(byte_token_ids[-1]) == byte_token_ids[-1]
+    assert not engine.pending_token_ids
+
+    engine.consume_text('"}')
+    assert engine.has_reached_accept_state
+    assert engine.get_structured_output() == {"value": "😀"}
+
+
+def test_pending_bytes_mask_to_continuation_bytes(engine: StructuringEngine) -> None:
+    """Test that only bytes continuing the pending character survive the mask."""
+    import numpy as np
+
+    engine.configure(
+        {
+            "type": "object",
+            "properties": {"value": {"type": "string"}},
+            "required": ["value"],
+        }
+    )
+    engine.consume_text('{"value": "')
+    byte_token_ids = [
+        engine.tokenizer.convert_tokens_to_ids(f"<0x{byte:02X}>")
+        for byte in "😀".encode()
+    ]
+    close_id = engine.tokenizer.convert_tokens_to_ids("}")
+    quote_id = engine.tokenizer.convert_tokens_to_ids('"')
+    logits = np.zeros(len(engine.tokenizer.get_vocab()), dtype=np.float32)
+
+    engine.consume(byte_token_ids[0])
+    adjusted_logits = engine.process_logits(None, logits)
+    assert np.isfinite(adjusted_logits[byte_token_ids[1]])
+    assert not np.isfinite(adjusted_logits[close_id])
+    assert not np.isfinite(adjusted_logits[quote_id])
+    # a second lead byte cannot continue the character
+    assert not np.isfinite(adjusted_logits[byte_token_ids[0]])
+
+    for token_id in byte_token_ids[1:]:
+        engine.consume(token_id)
+    assert not engine.pending_token_ids
+    assert np.isfinite(engine.process_logits(None, logits)[quote_id])
+
+
+def test_is_within_value(engine: StructuringEngine) -> None:
+    """Test that the engine tells values apart from structural punctuation."""
+    engine.configure(
+        {
+            "type": "object",
+            "properties": {"name": {"type": "string"}, "age": {"type": "integer"}},
+            "required": ["name", "age"],
+        }
+    )
+    assert not engine.is_within_value()
+    engine.consume_text("{")
+    assert not engine.is_within_value()
+    engine.consume_text('"name"')
+    assert not engine.is_within_value()
+    engine.consume_text(': "Ad')
+    assert engine.is_within_value()
+    engine.consume_text('a", ')
+    assert not engine.is_within_value()
+    engine.consume_text('"age": 4')
+    assert engine.is_within_value()
+
+
+@pytest.mark.parametrize("greedy", [True, False])
+def test_greedy_and_lazy_repetition(engine: StructuringEngine, greedy: bool) -> None:
+    """Test that greedy loops favor repeating and lazy loops favor stopping."""
+    import numpy as np
+
+    from pse.types.base.chain import ChainStateMachine
+    from pse.types.base.loop import LoopStateMachine
+    from pse.types.base.phrase import PhraseStateMachine
+
+    open_brace_id = engine.tokenizer.convert_tokens_to_ids("{")
+    close_brace_id = engine.tokenizer.convert_tokens_to_ids("}")
+    engine.configure(
+        ChainStateMachine(
+            [
+                LoopStateMachine(PhraseStateMachine("{"), greedy=greedy),
+                PhraseStateMachine("}"),
+            ]
+        )
+    )
+    engine.consume(open_brace_id)
+    greedy_token_ids, lazy_token_ids = engine.get_repetition_token_ids()
+    assert open_brace_id in (greedy_token_ids if greedy else lazy_token_ids)
+    assert close_brace_id not in greedy_token_ids | lazy_token_ids
+    # multi-character tokens that start a repetition are biased too
+    multi_char_ids = {
+        token_id
+        for token_id, text in engine.reverse_vocabulary.items()
+        if len(text) > 1 and text.startswith("{")
+    }
+    assert multi_char_ids
+    assert multi_char_ids <= (greedy_token_ids if greedy else lazy_token_ids)
+    assert engine.get_repetition_token_ids() == (greedy_token_ids, lazy_token_ids)
+
+    logits = np.zeros(len(engine.tokenizer.get_vocab()), dtype=np.float32)
+    logits = engine.process_logits(None, logits)
+    if greedy:
+        assert logits[open_brace_id] > logits[close_brace_id]
+    else:
+        assert logits[open_brace_id] < logits[close_brace_id]
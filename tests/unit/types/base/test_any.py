@@ -0,0 +1,108 @@
+import pytest
+
+from pse.types.base.any import AnyStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+
+
+def wide_choice(**kwargs) -> AnyStateMachine:
+    return AnyStateMachine(
+        [PhraseStateMachine(f"option_{i:03d}") for i in range(100)],
+        **kwargs,
+    )
+
+
+def test_uncapped_choice_branches_into_every_option():
+    """Test that without a cap every alternative gets a stepper."""
+    sm = wide_choice()
+    assert len(sm.get_steppers()) == 100
+
+
+def test_max_transitions_bounds_branching():
+    """Test that the cap bounds the number of branches from a single stepper."""
+    sm = wide_choice(max_transitions_per_stepper=5)
+    stepper = sm.get_new_stepper(0)
+
+    branches = sm.branch_stepper(stepper, "option_04")
+    assert len(branches) <= 5
+
+
+def test_max_transitions_keeps_matching_branches():
+    """Test that pruning keeps the branches that overlap with the incoming token."""
+    sm = wide_choice(max_transitions_per_stepper=5)
+    steppers = sm.advance_all_basic(sm.get_steppers(), "option_042")
+
+    assert any(stepper.has_reached_accept_state() for stepper in steppers)
+    for stepper in steppers:
+        if stepper.has_reached_accept_state():
+            assert stepper.get_current_value() == "option_042"
+
+
+def test_max_transitions_keeps_every_branch_without_token():
+    """Test that branching with no token, as when masking, keeps every alternative."""
+    sm = wide_choice(max_transitions_per_stepper=5)
+    stepper = sm.get_new_stepper(0)
+
+    branches = sm.branch_stepper(stepper)
+    assert len(branches) == 100
+
+
+def test_invalid_max_transitions():
+    """Test that a cap below one is rejected."""
+    with pytest.raises(ValueError):
+        wide_choice(max_transitions_per_stepper=0)
+
+
+def test_merge_prefixes_shares_common_prefix_path():
+    """Test that variants sharing `{"type":` are merged into one prefix path."""
+    from pse.types.base.chain import ChainStateMachine
+
+    variants = [
+        ChainStateMachine(
+            [
+                PhraseStateMachine('{"type":'),
+                PhraseStateMachine(f'"{kind}"'),
+                PhraseStateMachine("}"),
+            ]
+        )
+        for kind in ["a", "b", "c"]
+    ]
+    sm = AnyStateMachine.merge_prefixes(variants)
+
+    assert len(sm.state_graph[0]) == 1
+    shared_link, branch_state = sm.state_graph[0][0]
+    assert shared_link == PhraseStateMachine('{"type":')
+    assert len(sm.state_graph[branch_state]) == 3
+
+    for kind in ["a", "b", "c"]:
+        steppers = sm.advance_all_basic(sm.get_steppers(), f'{{"type":"{kind}"}}')
+        assert any(
+            stepper.has_reached_accept_state() and not stepper.remaining_input
+            for stepper in steppers
+        )
+    steppers = sm.advance_all_basic(sm.get_steppers(), '{"type":"d"}')
+    assert not any(stepper.has_reached_accept_state() for stepper in steppers)
+
+
+def test_choice_merges_shared_prefix_at_construction():
+    """Test that a choice over variants sharing `{"type":` branches only where they differ."""
+    from pse.types.base.chain import ChainStateMachine
+
+    variants = [
+        ChainStateMachine(
+            [
+                PhraseStateMachine('{"type":'),
+                PhraseStateMachine(f'"{kind}"'),
+                PhraseStateMachine("}"),
+            ]
+        )
+        for kind in ["a", "b", "c"]
+    ]
+    sm = AnyStateMachine(variants)
+
+    assert len(sm.state_graph[0]) == 1
+    for kind in ["a", "b", "c"]:
+        steppers = sm.advance_all_basic(sm.get_steppers(), f'{{"type":"{kind}"}}')
+        assert any(
+            stepper.has_reached_accept_state() and not stepper.remaining_input
+            for stepper in steppers
+        )
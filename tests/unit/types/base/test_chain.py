@@ -258,3 +258,51 @@ def test_whitespace_acceptor_sequence_acceptor():
     assert len(steppers) == 1
     assert steppers[0].has_reached_accept_state()
     assert steppers[0].get_current_value() == '"test"   :    '
+
+
+def test_flatten_namespaces_states():
+    """Test that flattening state machines with overlapping state ids avoids collisions."""
+    first = StateMachine({0: [(PhraseStateMachine("a"), 1)]}, end_states=[1])
+    second = StateMachine({0: [(PhraseStateMachine("b"), 1)]}, end_states=[1])
+    sm = ChainStateMachine.flatten([first, second])
+
+    assert set(sm.state_graph) == {"0.0", "0.1", "1.0", "1.1"}
+    assert sm.start_state == "0.0"
+    assert list(sm.end_states) == ["1.1"]
+
+    steppers = sm.advance_all_basic(sm.get_steppers(), "ab")
+    assert any(stepper.has_reached_accept_state() for stepper in steppers)
+
+    steppers = sm.advance_all_basic(sm.get_steppers(), "ba")
+    assert not any(stepper.has_reached_accept_state() for stepper in steppers)
+
+
+def test_flatten_optional_tail():
+    """Test that an optional trailing state machine keeps earlier end states accepting."""
+    first = StateMachine({0: [(PhraseStateMachine("a"), 1)]}, end_states=[1])
+    second = StateMachine(
+        {0: [(PhraseStateMachine("b"), 1)]}, end_states=[1], is_optional=True
+    )
+    sm = ChainStateMachine.flatten([first, second])
+
+    for text in ["a", "ab"]:
+        steppers = sm.advance_all_basic(sm.get_steppers(), text)
+        assert any(stepper.has_reached_accept_state() for stepper in steppers)
+
+
+def test_partially_consumed_token_remainder_drives_next_transition():
+    """Test that the unconsumed end of a token is carried into the next state machine."""
+    first = PhraseStateMachine("tru")
+    steppers = first.advance_all_basic(first.get_steppers(), "true")
+    assert [stepper.remaining_input for stepper in steppers] == ["e"]
+
+    sm = ChainStateMachine([PhraseStateMachine("tru"), PhraseStateMachine("e")])
+    steppers = sm.advance_all_basic(sm.get_steppers(), "true")
+    assert any(
+        stepper.has_reached_accept_state() and not stepper.remaining_input
+        for stepper in steppers
+    )
+
+    sm = ChainStateMachine([PhraseStateMachine("tru"), PhraseStateMachine("x")])
+    steppers = sm.advance_all_basic(sm.get_steppers(), "true")
+    assert not any(stepper.has_reached_accept_state() for stepper in steppers)
@@ -1,6 +1,7 @@
 import pytest
 from pse_core.state_machine import StateMachine
 
+from pse.types.base.character import CharacterStateMachine
 from pse.types.base.loop import LoopStateMachine, LoopStepper
 from pse.types.base.phrase import PhraseStateMachine
 from pse.types.whitespace import WhitespaceStateMachine
@@ -349,3 +350,73 @@ def test_get_final_state():
     # Test with no separator
     final_states = stepper.get_final_state()
     assert final_states == stepper.history
+
+
+def test_stop_before_leaves_delimiter_unconsumed():
+    """Test that the stop_before lookahead ends the loop without consuming the match."""
+    digit_sm = CharacterStateMachine("0123456789", char_limit=1)
+    loop_sm = LoopStateMachine(
+        digit_sm,
+        min_loop_count=1,
+        max_loop_count=10,
+        stop_before=PhraseStateMachine(";"),
+    )
+
+    steppers = loop_sm.advance_all_basic(loop_sm.get_steppers(), "123;")
+    stopped = [s for s in steppers if s.remaining_input == ";"]
+    assert stopped
+    for stepper in stopped:
+        assert stepper.has_reached_accept_state()
+        assert stepper.get_raw_value() == "123"
+        assert not stepper.can_accept_more_input()
+
+
+@pytest.mark.parametrize(
+    "lookahead, token, expected",
+    [
+        (";", "12;", 2),
+        (";", ";", 0),
+        (";", "12", None),
+        (";;", "1;;2", 1),
+        (";;", "1;2;;", 3),
+        (";;", "1;", None),
+    ],
+)
+def test_find_stop_index(lookahead, token, expected):
+    """Test that the lookahead finds the earliest match in a single scan of the token."""
+    loop_sm = LoopStateMachine(
+        CharacterStateMachine("0123456789", char_limit=1),
+        stop_before=PhraseStateMachine(lookahead),
+    )
+    assert loop_sm.get_new_stepper().find_stop_index(token) == expected
+
+
+def test_repeated_phrase_token_counted_in_place():
+    """Test that a token repeating the looped phrase is taken as one run."""
+    loop_sm = LoopStateMachine(PhraseStateMachine(" "), min_loop_count=0)
+    steppers = loop_sm.advance_all_basic(loop_sm.get_steppers(), " " * 1000)
+    accepted = [
+        stepper
+        for stepper in steppers
+        if stepper.has_reached_accept_state() and not stepper.remaining_input
+    ]
+    assert len(accepted) == 1
+    assert accepted[0].loop_count == 1000
+    assert accepted[0].get_raw_value() == " " * 1000
+
+
+def test_repeated_phrase_token_can_stop_inside_the_run():
+    """Test that a run still lets the loop stop early and hand the rest on."""
+    from pse.types.base.chain import ChainStateMachine
+
+    chain_sm = ChainStateMachine(
+        [LoopStateMachine(PhraseStateMachine("a")), PhraseStateMachine("a")]
+    )
+    steppers = chain_sm.advance_all_basic(chain_sm.get_steppers(), "aaa")
+    accepted = [
+        stepper
+        for stepper in steppers
+        if stepper.has_reached_accept_state() and not stepper.remaining_input
+    ]
+    assert accepted
+    assert all(stepper.get_raw_value() == "aaa" for stepper in accepted)
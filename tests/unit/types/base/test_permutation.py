@@ -0,0 +1,63 @@
+import pytest
+
+from pse.types.base.permutation import PermutationStateMachine, PermutationStepper
+from pse.types.base.phrase import PhraseStateMachine
+
+
+def accepted_steppers(sm: PermutationStateMachine, value: str) -> list:
+    steppers = sm.advance_all_basic(sm.get_steppers(), value)
+    return [
+        stepper
+        for stepper in steppers
+        if stepper.has_reached_accept_state() and not stepper.remaining_input
+    ]
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ("AB", True),
+        ("BA", True),
+        ("AA", False),
+        ("BB", False),
+        ("A", False),
+        ("ABA", False),
+    ],
+)
+def test_permutation(value: str, should_accept: bool):
+    sm = PermutationStateMachine([PhraseStateMachine("A"), PhraseStateMachine("B")])
+    assert bool(accepted_steppers(sm, value)) == should_accept
+
+
+def test_permutation_tracks_completion_order():
+    sm = PermutationStateMachine(
+        [PhraseStateMachine("x"), PhraseStateMachine("y"), PhraseStateMachine("z")]
+    )
+    steppers = accepted_steppers(sm, "zxy")
+    assert steppers
+    assert all(isinstance(stepper, PermutationStepper) for stepper in steppers)
+    assert steppers[0].get_completed_indices() == [2, 0, 1]
+
+
+def test_permutation_of_the_same_state_machine():
+    """Test that a state machine listed twice is accepted twice, once per position."""
+    phrase = PhraseStateMachine("A")
+    sm = PermutationStateMachine([phrase, phrase, PhraseStateMachine("B")])
+    assert accepted_steppers(sm, "AAB")
+    assert accepted_steppers(sm, "ABA")
+    assert not accepted_steppers(sm, "AB")
+    assert not accepted_steppers(sm, "ABB")
+
+
+def test_large_permutation_builds_states_lazily():
+    """Test that a permutation of many state machines only builds the states it reaches."""
+    sm = PermutationStateMachine([PhraseStateMachine(chr(65 + i)) for i in range(25)])
+    assert not sm.state_graph
+    assert len(sm.get_edges(0)) == 25
+    assert accepted_steppers(sm, "".join(chr(65 + i) for i in reversed(range(25))))
+    assert not accepted_steppers(sm, "AA")
+
+
+def test_empty_permutation():
+    with pytest.raises(ValueError):
+        PermutationStateMachine([])
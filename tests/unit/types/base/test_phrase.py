@@ -220,3 +220,13 @@ def test_should_complete_step():
     # Completed
     stepper = PhraseStepper(text_acceptor, 5)
     assert stepper.should_complete_step()
+
+
+def test_case_insensitive_phrase_keeps_canonical_value():
+    """Test that a case-insensitive phrase matches any casing and returns the phrase's casing."""
+    sm = PhraseStateMachine("Hello", is_case_sensitive=False)
+    steppers = sm.advance_all_basic(sm.get_steppers(), "hELLO")
+
+    assert any(stepper.has_reached_accept_state() for stepper in steppers)
+    for stepper in steppers:
+        assert stepper.get_raw_value() == "Hello"
@@ -440,3 +440,26 @@ def test_simple_number_acceptor():
         assert stepper_delta.token == "-1"
         assert stepper_delta.stepper.has_reached_accept_state()
         assert stepper_delta.stepper.get_current_value() == -1
+
+
+def test_stepper_introspection_surface():
+    """Test that a stepper's progress can be read back after advancing."""
+    sm = StateMachine(
+        {
+            0: [(PhraseStateMachine("hello"), 1)],
+            1: [(PhraseStateMachine("world"), "$")],
+        }
+    )
+    steppers = sm.advance_all_basic(sm.get_steppers(), "hel")
+    assert len(steppers) == 1
+    stepper = steppers[0]
+    assert stepper.current_state == 0
+    assert stepper.target_state == 1
+    assert not stepper.remaining_input
+    assert stepper.sub_stepper is not None
+    assert stepper.sub_stepper.consumed_character_count == 3
+
+    steppers = sm.advance_all_basic(steppers, "lowor")
+    assert len(steppers) == 1
+    assert steppers[0].current_state == 1
+    assert steppers[0].sub_stepper.consumed_character_count == 3
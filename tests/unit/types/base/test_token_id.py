@@ -0,0 +1,49 @@
+import pytest
+
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+from pse.types.base.token_id import TokenIdStateMachine, TokenIdStepper
+
+VOCAB = {1: "yes", 2: "no", 3: "maybe", 4: "yes"}
+
+
+def decode(token_ids: list[int]) -> str:
+    return "".join(VOCAB[token_id] for token_id in token_ids)
+
+
+def test_accepts_decoded_text_of_allowed_ids():
+    """Test that the decoded text of an allowed id is accepted."""
+    sm = TokenIdStateMachine([1, 2], decode)
+    steppers = sm.advance_all_basic(sm.get_steppers(), "no")
+    assert any(stepper.has_reached_accept_state() for stepper in steppers)
+
+    steppers = sm.advance_all_basic(sm.get_steppers(), "maybe")
+    assert not any(stepper.has_reached_accept_state() for stepper in steppers)
+
+
+def test_lossy_decodes_are_grouped():
+    """Test that ids decoding to the same text share one branch."""
+    sm = TokenIdStateMachine([1, 4], decode)
+    assert sm.token_texts == {"yes": {1, 4}}
+
+
+def test_allowed_token_ids_until_consumed():
+    """Test that steppers report their allowed ids until they start consuming."""
+    sm = ChainStateMachine([PhraseStateMachine("a:"), TokenIdStateMachine([1, 2], decode)])
+    steppers = sm.advance_all_basic(sm.get_steppers(), "a:")
+    token_id_steppers = []
+    for stepper in steppers:
+        current = stepper
+        while current is not None and not isinstance(current, TokenIdStepper):
+            current = current.sub_stepper
+        if current is not None:
+            token_id_steppers.append(current)
+
+    assert token_id_steppers
+    assert all(s.get_allowed_token_ids() == {1, 2} for s in token_id_steppers)
+
+
+def test_empty_allowed_ids():
+    """Test that an empty set of ids is rejected."""
+    with pytest.raises(ValueError):
+        TokenIdStateMachine([], decode)
@@ -0,0 +1,75 @@
+"""
+Fuzz tests for the Lark-backed grammars.
+
+Malformed input must be rejected cleanly: validation returns a bool and
+advancing returns (possibly empty) steppers, never an exception.
+"""
+
+import random
+
+import pytest
+
+from pse.types.grammar import BashStateMachine, LarkGrammarStateMachine, PythonStateMachine
+
+SEEDS = {
+    "python": [
+        "x = 1\n",
+        "def foo(a, b=2):\n    return a + b\n",
+        "class A:\n    pass\n",
+        "for i in range(3):\n    print(i)\n",
+        "d = {'a': [1, 2, (3, 4)]}\n",
+    ],
+    "bash": [
+        "echo hello\n",
+        "for f in *.txt; do cat \"$f\"; done\n",
+        "if [ -f x ]; then echo yes; fi\n",
+        "ls -la | grep foo > out.txt\n",
+    ],
+}
+STATE_MACHINES = {"python": PythonStateMachine, "bash": BashStateMachine}
+SPECIAL_CHARACTERS = list("()[]{}:;'\"\\`$|&<>#=\n\t ") + ["\x00", "\ufeff", "😊"]
+FUZZ_CASES = 50
+
+
+def mangle(text: str, rng: random.Random) -> str:
+    """Apply a few random insertions, deletions and swaps to the text."""
+    chars = list(text)
+    for _ in range(rng.randint(1, 4)):
+        operation = rng.choice(["insert", "delete", "swap", "truncate"])
+        if operation == "insert" or not chars:
+            chars.insert(rng.randint(0, len(chars)), rng.choice(SPECIAL_CHARACTERS))
+        elif operation == "delete":
+            del chars[rng.randrange(len(chars))]
+        elif operation == "swap":
+            i, j = rng.randrange(len(chars)), rng.randrange(len(chars))
+            chars[i], chars[j] = chars[j], chars[i]
+        else:
+            chars = chars[: rng.randrange(len(chars) + 1)]
+    return "".join(chars)
+
+
+def fuzz_inputs(language: str) -> list[str]:
+    rng = random.Random(f"pse-{language}")
+    inputs = ["", "\n", "\x00", *SPECIAL_CHARACTERS]
+    for _ in range(FUZZ_CASES):
+        inputs.append(mangle(rng.choice(SEEDS[language]), rng))
+    return inputs
+
+
+@pytest.mark.parametrize("language", ["python", "bash"])
+def test_validate_never_raises(language: str) -> None:
+    state_machine = STATE_MACHINES[language]
+    assert isinstance(state_machine, LarkGrammarStateMachine)
+    for text in fuzz_inputs(language):
+        for strict in (False, True):
+            assert isinstance(state_machine.grammar.validate(text, strict), bool), text
+
+
+@pytest.mark.parametrize("language", ["python", "bash"])
+def test_advance_never_raises(language: str) -> None:
+    state_machine = STATE_MACHINES[language]
+    for text in fuzz_inputs(language):
+        steppers = state_machine.advance_all_basic(state_machine.get_steppers(), text)
+        assert isinstance(steppers, list), text
+        # consuming without checking should_start_step first must not raise either
+        assert isinstance(state_machine.get_new_stepper(None).consume(text), list), text
@@ -0,0 +1,41 @@
+import pytest
+from helpers import accepts
+
+from pse.types.json import example_state_machine
+from pse.types.json.schema_sources.from_example import example_to_schema
+
+
+def test_infer_schema_from_object_example() -> None:
+    schema = example_to_schema('{"name":"x","age":3}')
+    assert schema == {
+        "type": "object",
+        "properties": {"name": {"type": "string"}, "age": {"type": "number"}},
+        "required": ["name", "age"],
+        "additionalProperties": False,
+    }
+
+
+def test_example_state_machine_requires_same_structure() -> None:
+    """Test that the inferred schema needs a string name and a number age."""
+    _, state_machine = example_state_machine('{"name":"x","age":3}')
+    assert accepts(state_machine, '{"name": "Grace", "age": 85.5}')
+    assert not accepts(state_machine, '{"name": 1, "age": 3}')
+    assert not accepts(state_machine, '{"name": "x", "age": "3"}')
+    assert not accepts(state_machine, '{"name": "x"}')
+
+
+def test_infer_array_items_from_first_element() -> None:
+    schema = example_to_schema([{"ok": True}, "ignored"])
+    assert schema["items"] == {
+        "type": "object",
+        "properties": {"ok": {"type": "boolean"}},
+        "required": ["ok"],
+        "additionalProperties": False,
+    }
+    assert example_to_schema([]) == {"type": "array"}
+    assert example_to_schema(None) == {"type": "null"}
+
+
+def test_invalid_example() -> None:
+    with pytest.raises(ValueError):
+        example_to_schema("{not json")
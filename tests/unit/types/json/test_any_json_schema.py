@@ -146,3 +146,14 @@ def test_multiple_accepted_steppers(context, token, expected_result):
 
     result = parse_input(state_machine, token)
     assert result == expected_result, f"AnyOfAcceptor should accept input {token}."
+
+
+def test_alternatives_share_common_prefix(context):
+    """Test that schemas compiling to chains with the same first link share one path."""
+    state_machine = AnySchemaStateMachine(
+        schemas=[{"const": "ab"}, {"const": "cd"}], context=context
+    )
+    assert len(state_machine.state_graph[0]) == 1
+    assert parse_input(state_machine, '"cd"') == "cd"
+    with pytest.raises(ValueError):
+        parse_input(state_machine, '"ad"')
@@ -235,3 +235,89 @@ def test_array_schema_uniqueItems_constraint(base_context):
             array_value = stepper.get_current_value()
             assert len(array_value) == 2, "Array should only contain unique values"
             assert array_value == [1, 2], "Array should maintain order and uniqueness"
+
+
+@pytest.mark.parametrize(
+    "monotonic, json_array, expected",
+    [
+        ("increasing", "[1,2,5]", [1, 2, 5]),
+        ("increasing", "[1,2,2]", None),
+        ("increasing", "[1,3,2]", None),
+        ("non-decreasing", "[1,2,2]", [1, 2, 2]),
+        ("non-decreasing", "[1,3,2]", None),
+        ("increasing", "[-1.5, 0, 10]", [-1.5, 0, 10]),
+    ],
+)
+def test_monotonic_items(monotonic, json_array, expected, base_context):
+    """
+    Test that each item must be greater than (or, non-strictly, equal to) the previous one.
+    """
+    schema = {"type": "array", "items": {"type": "number"}, "monotonic": monotonic}
+    state_machine = ArraySchemaStateMachine(schema, base_context)
+    if expected is None:
+        with pytest.raises(ValueError):
+            parse_array(state_machine, json_array)
+    else:
+        assert parse_array(state_machine, json_array) == expected
+
+
+@pytest.mark.parametrize(
+    "json_array, expected",
+    [
+        ('[{"a": 1, "b": 2}, {"a": 3, "b": 4}]', [{"a": 1, "b": 2}, {"a": 3, "b": 4}]),
+        ('[{"a": 1, "b": 2}, {"b": 4, "a": 3}]', [{"a": 1, "b": 2}, {"b": 4, "a": 3}]),
+        ('[{"a": 1, "b": 2}, {"a": 3}]', None),
+        ('[{"a": 1}, {"a": 3, "b": 4}]', None),
+        ('[{"a": 1}]', [{"a": 1}]),
+    ],
+)
+def test_consistent_keys(json_array, expected, base_context):
+    """
+    Test that every object item must have the same keys as the first one.
+    """
+    schema = {
+        "type": "array",
+        "items": {
+            "type": "object",
+            "properties": {"a": {"type": "integer"}, "b": {"type": "integer"}},
+            "orderedProperties": False,
+        },
+        "consistentKeys": True,
+    }
+    state_machine = ArraySchemaStateMachine(schema, base_context)
+    if expected is None:
+        with pytest.raises(ValueError):
+            parse_array(state_machine, json_array)
+    else:
+        assert parse_array(state_machine, json_array) == expected
+
+
+@pytest.mark.parametrize(
+    "json_prefix, is_live",
+    [
+        ('[{"a": 1}, {"a"', True),
+        ('[{"a": 1}, {"b"', False),
+        ('[{"a": 1, "b": 2}, {"b"', True),
+        ('[{"a": 1, "x": 2}, {"x"', True),
+        ('[{"a": 1, "x": 2}, {"y"', False),
+    ],
+)
+def test_consistent_keys_offers_only_first_keys(json_prefix, is_live, base_context):
+    """
+    Test that later object items are only offered the keys of the first one.
+    """
+    schema = {
+        "type": "array",
+        "items": {
+            "type": "object",
+            "properties": {"a": {"type": "integer"}, "b": {"type": "integer"}},
+            "additionalProperties": {"type": "integer"},
+            "orderedProperties": False,
+        },
+        "consistentKeys": True,
+    }
+    state_machine = ArraySchemaStateMachine(schema, base_context)
+    steppers = list(state_machine.get_steppers())
+    for char in json_prefix:
+        steppers = state_machine.advance_all_basic(steppers, char)
+    assert bool(steppers) is is_live
@@ -1,6 +1,7 @@
 from typing import Any
 
 import pytest
+from helpers import accepts
 
 from pse.types.json.json_number import NumberSchemaStateMachine
 
@@ -226,3 +227,87 @@ def test_schema_stepper_creation() -> None:
     for char in "12.34":
         steppers = state_machine.advance_all_basic(steppers, char)
     assert any(stepper.has_reached_accept_state() for stepper in steppers)
+
+
+@pytest.mark.parametrize(
+    "schema, value, should_accept",
+    [
+        ({"type": "number", "exclusiveMinimum": 0}, "0", False),
+        ({"type": "number", "exclusiveMinimum": 0}, "0.0", False),
+        ({"type": "number", "exclusiveMinimum": 0}, "0.0001", True),
+        ({"type": "number", "exclusiveMinimum": 0}, "-1", False),
+        ({"type": "number", "exclusiveMaximum": 10}, "10", False),
+        ({"type": "number", "exclusiveMaximum": 10}, "9.99", True),
+        ({"type": "number", "exclusiveMaximum": 0}, "-0.5", True),
+        ({"type": "number", "exclusiveMaximum": 0}, "0", False),
+        # the older boolean form makes the inclusive bound exclusive
+        ({"type": "number", "minimum": 5, "exclusiveMinimum": True}, "5", False),
+        ({"type": "number", "minimum": 5, "exclusiveMinimum": True}, "5.5", True),
+        ({"type": "number", "maximum": 5, "exclusiveMaximum": False}, "5", True),
+    ],
+)
+def test_exclusive_bounds(schema: dict[str, Any], value: str, should_accept: bool) -> None:
+    assert accepts(NumberSchemaStateMachine(schema), value) == should_accept
+
+
+def test_exclusive_bounds_prune_sign() -> None:
+    """Test that a sign ruled out by the bounds is pruned before any digits."""
+    positive = NumberSchemaStateMachine({"type": "number", "exclusiveMinimum": 0})
+    assert not positive.advance_all_basic(positive.get_steppers(), "-")
+
+    negative = NumberSchemaStateMachine({"type": "number", "exclusiveMaximum": 0})
+    assert not negative.advance_all_basic(negative.get_steppers(), "1")
+    assert negative.advance_all_basic(negative.get_steppers(), "-")
+
+
+def test_exact_decimal_schema() -> None:
+    """Test that exact-decimal mode reaches schema numbers and checks them exactly."""
+    from decimal import Decimal
+
+    from pse.types.json import json_schema_state_machine
+
+    _, state_machine = json_schema_state_machine(
+        {"type": "number", "multipleOf": 0.1, "maximum": 1}, exact_decimal=True
+    )
+    steppers = state_machine.advance_all_basic(state_machine.get_steppers(), "0.3")
+    accepted = [stepper for stepper in steppers if stepper.has_reached_accept_state()]
+    assert accepted
+    assert all(stepper.get_current_value() == Decimal("0.3") for stepper in accepted)
+    assert not accepts(state_machine, "0.35")
+    assert not accepts(state_machine, "1.5")
+
+    _, state_machine = json_schema_state_machine({"type": "integer"}, exact_decimal=True)
+    steppers = state_machine.advance_all_basic(state_machine.get_steppers(), "42")
+    assert any(
+        stepper.get_current_value() == Decimal(42)
+        for stepper in steppers
+        if stepper.has_reached_accept_state()
+    )
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [("2", True), ("2.0", False), ("2e0", False), ("2.5", True)],
+)
+def test_canonical_form_schema(value: str, should_accept: bool) -> None:
+    """Test that the canonical form reaches schema numbers, alongside their bounds."""
+    from pse.types.json import json_schema_state_machine
+    from pse.types.number import CanonicalForm
+
+    _, state_machine = json_schema_state_machine(
+        {"type": "number", "maximum": 3},
+        canonical_form=CanonicalForm.INTEGER_IF_WHOLE,
+    )
+    assert accepts(state_machine, value) == should_accept
+
+
+def test_max_significant_figures_schema() -> None:
+    """Test that the significant-figure limit reaches schema numbers."""
+    from pse.types.json import json_schema_state_machine
+
+    _, state_machine = json_schema_state_machine(
+        {"type": "number"}, max_significant_figures=3
+    )
+    assert accepts(state_machine, "1.23")
+    assert accepts(state_machine, "1.23e45")
+    assert not accepts(state_machine, "1.234")
@@ -2,7 +2,10 @@ from typing import Any
 
 import pytest
 
-from pse.types.json.json_object import ObjectSchemaStateMachine
+from pse.types.json.json_object import (
+    ObjectSchemaStateMachine,
+    resolve_data_references,
+)
 
 
 @pytest.fixture
@@ -373,3 +376,354 @@ def test_object_equality_check(base_context: dict[str, Any]) -> None:
     assert state_machine1 != state_machine3, (
         "State machines with different schema should not be equal"
     )
+
+
+def test_add_property_without_rebuild(base_context: dict[str, Any]) -> None:
+    """
+    Test that adding a property to a compiled object makes the new key valid.
+    """
+    schema = {
+        "type": "object",
+        "properties": {"name": {"type": "string"}},
+        "required": ["name"],
+        "additionalProperties": False,
+    }
+    state_machine = ObjectSchemaStateMachine(schema, base_context)
+    steppers = state_machine.advance_all_basic(
+        state_machine.get_steppers(), '{"name": "test", "age'
+    )
+    assert all(stepper.remaining_input for stepper in steppers)
+
+    name_property = state_machine.get_property_state_machine("name")
+    state_machine.add_property("age", {"type": "integer"}, required=True)
+    # the existing property keeps its compiled state machine
+    assert state_machine.get_property_state_machine("name") is name_property
+    assert state_machine.required_property_names == ["name", "age"]
+    # the schema the object was compiled from is left untouched
+    assert schema["properties"] == {"name": {"type": "string"}}
+    assert schema["required"] == ["name"]
+
+    steppers = state_machine.advance_all_basic(
+        state_machine.get_steppers(), '{"name": "test", "age": 42}'
+    )
+    assert any(stepper.has_reached_accept_state() for stepper in steppers)
+    for stepper in steppers:
+        if stepper.has_reached_accept_state():
+            assert stepper.get_current_value() == {"name": "test", "age": 42}
+
+
+def test_remove_property(base_context: dict[str, Any]) -> None:
+    """
+    Test that removing a property from a compiled object makes the key invalid.
+    """
+    schema = {
+        "type": "object",
+        "properties": {"name": {"type": "string"}, "age": {"type": "integer"}},
+        "required": ["name", "age"],
+        "additionalProperties": False,
+    }
+    state_machine = ObjectSchemaStateMachine(schema, base_context)
+    state_machine.remove_property("age")
+    assert state_machine.required_property_names == ["name"]
+
+    steppers = state_machine.advance_all_basic(
+        state_machine.get_steppers(), '{"name": "test"}'
+    )
+    assert any(stepper.has_reached_accept_state() for stepper in steppers)
+
+    with pytest.raises(KeyError):
+        state_machine.remove_property("age")
+
+
+def test_pattern_properties(base_context: dict[str, Any]) -> None:
+    """
+    Test that keys matching a patternProperties regex must satisfy its value schema.
+    """
+    schema = {
+        "type": "object",
+        "properties": {"name": {"type": "string"}},
+        "patternProperties": {
+            "^x_": {"type": "number"},
+            "_flag$": {"type": "boolean"},
+        },
+        "required": ["name"],
+        "additionalProperties": False,
+    }
+    state_machine = ObjectSchemaStateMachine(schema, base_context)
+
+    steppers = state_machine.advance_all_basic(
+        state_machine.get_steppers(),
+        '{"name": "test", "x_count": 3, "x_ratio": 0.5, "is_flag": true}',
+    )
+    assert any(stepper.has_reached_accept_state() for stepper in steppers)
+    for stepper in steppers:
+        if stepper.has_reached_accept_state():
+            assert stepper.get_current_value() == {
+                "name": "test",
+                "x_count": 3,
+                "x_ratio": 0.5,
+                "is_flag": True,
+            }
+
+    steppers = state_machine.advance_all_basic(
+        state_machine.get_steppers(), '{"name": "test", "x_count": "three"}'
+    )
+    assert not any(stepper.has_reached_accept_state() for stepper in steppers)
+
+    steppers = state_machine.advance_all_basic(
+        state_machine.get_steppers(), '{"name": "test", "y_count": 3}'
+    )
+    assert not any(stepper.has_reached_accept_state() for stepper in steppers)
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ('{"x_count": 5}', True),
+        ('{"x_count": 11}', False),
+        ('{"x_count": -1}', False),
+        ('{"x_total": -1}', False),
+        ('{"y_count": -1}', True),
+        ('{"x_total": 11}', True),
+    ],
+)
+def test_key_matching_several_patterns(
+    base_context: dict[str, Any], value: str, should_accept: bool
+) -> None:
+    """
+    Test that a key matching several patterns must satisfy all of their schemas.
+    """
+    schema = {
+        "type": "object",
+        "patternProperties": {
+            "^x_": {"type": "integer", "minimum": 0},
+            "_count$": {"type": "integer", "maximum": 10},
+        },
+        "additionalProperties": False,
+    }
+    state_machine = ObjectSchemaStateMachine(schema, base_context)
+    steppers = state_machine.advance_all_basic(state_machine.get_steppers(), value)
+    assert any(stepper.has_reached_accept_state() for stepper in steppers) == should_accept
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ('{"x_count": 3}', True),
+        ('{"x_count": "three"}', False),
+        ('{"y_count": "three"}', True),
+        ('{"y_count": 3}', False),
+    ],
+)
+def test_pattern_key_is_not_an_additional_property(
+    base_context: dict[str, Any], value: str, should_accept: bool
+) -> None:
+    """
+    Test that a key matching a pattern can't fall back to the additionalProperties schema.
+    """
+    schema = {
+        "type": "object",
+        "patternProperties": {"^x_": {"type": "integer"}},
+        "additionalProperties": {"type": "string"},
+    }
+    state_machine = ObjectSchemaStateMachine(schema, base_context)
+    steppers = state_machine.advance_all_basic(state_machine.get_steppers(), value)
+    assert any(stepper.has_reached_accept_state() for stepper in steppers) == should_accept
+
+
+@pytest.mark.parametrize(
+    "property_schema, value",
+    [
+        ({"type": "string"}, "abc"),
+        ({"type": "string", "pattern": "^[a-z]+$"}, "abc"),
+        ({"type": "string", "format": "email"}, "a@b.co"),
+    ],
+)
+def test_quote_brace_token_closes_string_and_object(
+    base_context: dict[str, Any], property_schema: dict[str, Any], value: str
+) -> None:
+    """
+    Test that a single `"}` token closes the string value and then the object.
+    """
+    schema = {
+        "type": "object",
+        "properties": {"code": property_schema},
+        "required": ["code"],
+    }
+    state_machine = ObjectSchemaStateMachine(schema, base_context)
+    steppers = state_machine.get_steppers()
+    steppers = state_machine.advance_all_basic(steppers, f'{{"code": "{value}')
+    assert steppers
+
+    steppers = state_machine.advance_all_basic(steppers, '"}')
+    assert any(stepper.has_reached_accept_state() for stepper in steppers)
+    for stepper in steppers:
+        if stepper.has_reached_accept_state():
+            assert not stepper.remaining_input
+            assert stepper.get_current_value() == {"code": value}
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ('{"count": 2, "items": ["a", "b"]}', True),
+        ('{"count": 0, "items": []}', True),
+        ('{"count": 2, "items": ["a"]}', False),
+        ('{"count": 2, "items": ["a", "b", "c"]}', False),
+        ('{"count": 3, "items": ["a", "b", "c"]}', True),
+    ],
+)
+def test_array_length_from_earlier_property(
+    base_context: dict[str, Any], value: str, should_accept: bool
+) -> None:
+    """
+    Test that `count` drives the required length of a later array via `$data`.
+    """
+    schema = {
+        "type": "object",
+        "properties": {
+            "count": {"type": "integer"},
+            "items": {
+                "type": "array",
+                "items": {"type": "string"},
+                "minItems": {"$data": "1/count"},
+                "maxItems": {"$data": "1/count"},
+            },
+        },
+        "required": ["count", "items"],
+        "additionalProperties": False,
+    }
+    state_machine = ObjectSchemaStateMachine(schema, base_context)
+    steppers = state_machine.advance_all_basic(state_machine.get_steppers(), value)
+    assert any(stepper.has_reached_accept_state() for stepper in steppers) == should_accept
+
+
+def test_resolve_data_references() -> None:
+    """
+    Test that references resolve against parsed properties and unparsed ones are dropped.
+    """
+    schema = {
+        "type": "array",
+        "minItems": {"$data": "1/low"},
+        "maxItems": {"$data": "1/high"},
+    }
+    assert resolve_data_references(schema, {"low": 1}) == {
+        "type": "array",
+        "minItems": 1,
+    }
+
+    assert resolve_data_references(schema, {"low": "one", "high": -1}) == {
+        "type": "array"
+    }
+
+    with pytest.raises(ValueError):
+        resolve_data_references({"maxItems": {"$data": "/count"}}, {"count": 1})
+
+
+def test_data_references_checked_up_front(base_context: dict[str, Any]) -> None:
+    """
+    Test that a bad `$data` pointer fails at compile time, and resolved schemas are reused.
+    """
+    with pytest.raises(ValueError):
+        ObjectSchemaStateMachine(
+            {"properties": {"items": {"maxItems": {"$data": "/count"}}}},
+            base_context,
+        )
+
+    state_machine = ObjectSchemaStateMachine(
+        {
+            "properties": {
+                "count": {"type": "integer"},
+                "items": {"type": "array", "maxItems": {"$data": "1/count"}},
+            },
+        },
+        base_context,
+    )
+    first = state_machine.get_property_state_machines({"count": 2})
+    second = state_machine.get_property_state_machines({"count": 2})
+    assert first[0] is second[0]
+    assert first[0] is not state_machine.get_property_state_machines({"count": 3})[0]
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ('{"a":1,"b":2}', True),
+        ('{"b":2,"a":1}', False),
+        ('{"a":1,"c":3}', True),
+        ('{"b":2}', True),
+    ],
+)
+def test_sorted_keys(base_context: dict[str, Any], value: str, should_accept: bool) -> None:
+    """
+    Test that under `sortedKeys` each key must sort after the previous one.
+    """
+    schema = {
+        "type": "object",
+        "properties": {
+            "c": {"type": "integer"},
+            "b": {"type": "integer"},
+            "a": {"type": "integer"},
+        },
+        "sortedKeys": True,
+    }
+    state_machine = ObjectSchemaStateMachine(schema, base_context)
+    steppers = state_machine.advance_all_basic(state_machine.get_steppers(), value)
+    assert any(stepper.has_reached_accept_state() for stepper in steppers) == should_accept
+
+
+def test_sorted_keys_offers_only_later_properties(base_context: dict[str, Any]) -> None:
+    """
+    Test that only properties sorting after the last key are offered.
+    """
+    schema = {
+        "type": "object",
+        "properties": {"c": {}, "b": {}, "a": {}},
+        "orderedProperties": False,
+        "sortedKeys": True,
+    }
+    state_machine = ObjectSchemaStateMachine(schema, base_context)
+    assert state_machine.get_property_names({}) == ["a", "b", "c"]
+    assert state_machine.get_property_names({"b": 2}) == ["c"]
+
+
+def test_sorted_keys_offers_nothing_past_missing_required(
+    base_context: dict[str, Any],
+) -> None:
+    """
+    Test that a property sorting after a missing required key is not offered.
+    """
+    schema = {
+        "type": "object",
+        "properties": {"a": {}, "b": {}, "c": {}},
+        "required": ["b"],
+        "orderedProperties": False,
+        "sortedKeys": True,
+    }
+    state_machine = ObjectSchemaStateMachine(schema, base_context)
+    assert state_machine.get_property_names({}) == ["a", "b"]
+    assert state_machine.get_property_names({"a": 1}) == ["b"]
+    assert state_machine.get_property_names({"b": 2}) == ["c"]
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ('{"x": 1, "y": 2}', True),
+        ('{"y": 2, "x": 1}', False),
+    ],
+)
+def test_sorted_additional_keys(
+    base_context: dict[str, Any], value: str, should_accept: bool
+) -> None:
+    """
+    Test that additional property keys are held to the sorted order too.
+    """
+    schema = {
+        "type": "object",
+        "additionalProperties": {"type": "integer"},
+        "sortedKeys": True,
+    }
+    state_machine = ObjectSchemaStateMachine(schema, base_context)
+    steppers = state_machine.advance_all_basic(state_machine.get_steppers(), value)
+    assert any(stepper.has_reached_accept_state() for stepper in steppers) == should_accept
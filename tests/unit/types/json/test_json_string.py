@@ -303,6 +303,13 @@ def test_clean_value_quoted_string():
     # String with internal quotes
     assert stepper.clean_value('"hello"world"') == 'hello', '"hello"world" should be cleaned to hello'
 
+    # Closing quote followed by structural characters from the same token
+    assert stepper.clean_value('"hello"}') == "hello", '"hello"} should be cleaned to hello'
+
+    # Escaped quotes are part of the string; only the unescaped one closes it
+    assert stepper.clean_value('"say \\"hi\\""}') == 'say \\"hi\\"'
+    assert stepper.clean_value('"a\\\\"b') == 'a\\\\'
+
 
 def test_string_stepper_consume_with_remaining_input():
     """
@@ -387,3 +394,29 @@ def test_invalid_pattern():
         raise ValueError("Should have raised ValueError for invalid pattern")
     except ValueError as e:
         assert "Invalid pattern" in str(e), "Error message should mention invalid pattern"
+
+
+def test_equal_min_and_max_length_is_exact():
+    """Test that minLength == maxLength enforces exactly that many characters."""
+    state_machine = StringSchemaStateMachine({"minLength": 2, "maxLength": 2})
+    assert state_machine.exact_length == 2
+    for value, accepted in [('"US"', True), ('"U"', False), ('"USA"', False)]:
+        steppers = state_machine.advance_all_basic(state_machine.get_steppers(), value)
+        assert (
+            any(
+                stepper.has_reached_accept_state() and not stepper.remaining_input
+                for stepper in steppers
+            )
+            == accepted
+        )
+
+
+def test_max_length_counts_cjk_characters() -> None:
+    """Test that each multi-byte CJK character counts once towards maxLength."""
+    state_machine = StringSchemaStateMachine(schema={"maxLength": 5})
+
+    steppers = state_machine.advance_all_basic(state_machine.get_steppers(), '"中文字日本"')
+    assert any(stepper.has_reached_accept_state() for stepper in steppers)
+
+    steppers = state_machine.advance_all_basic(state_machine.get_steppers(), '"中文字日本')
+    assert not state_machine.advance_all_basic(steppers, "人")
@@ -5,12 +5,17 @@ import pytest
 
 from pse.types.base.chain import ChainStateMachine
 from pse.types.enum import EnumStateMachine
-from pse.types.json import _json_schema_to_state_machine
+from pse.types.json import (
+    _json_schema_to_state_machine,
+    json_schema_state_machine,
+    tool_call_state_machine,
+)
 from pse.types.json.any_json_schema import AnySchemaStateMachine
 from pse.types.json.json_array import ArraySchemaStateMachine
 from pse.types.json.json_number import NumberSchemaStateMachine
 from pse.types.json.json_object import ObjectSchemaStateMachine
 from pse.types.json.json_string import StringSchemaStateMachine
+from pse.util.errors import PseGrammarError
 
 
 @pytest.mark.parametrize(
@@ -85,3 +90,62 @@ def test_get_acceptor_with_ref_schema(context_with_definition: dict[str, Any]) -
     ), (
         "get_json_acceptor should return an ObjectSchemaAcceptor for $ref schemas referencing object definitions."
     )
+
+
+WEATHER_PARAMS = {
+    "type": "object",
+    "properties": {"city": {"type": "string"}, "days": {"type": "integer"}},
+    "required": ["city"],
+}
+
+
+def test_tool_call_state_machine() -> None:
+    """Test that a tool call fixes the name and constrains the arguments."""
+    schema, state_machine = tool_call_state_machine("get_weather", WEATHER_PARAMS)
+    assert schema["properties"]["arguments"] == WEATHER_PARAMS
+
+    call = '{"name": "get_weather", "arguments": {"city": "Paris", "days": 3}}'
+    steppers = state_machine.advance_all_basic(state_machine.get_steppers(), call)
+    assert any(stepper.has_reached_accept_state() for stepper in steppers)
+    for stepper in steppers:
+        if stepper.has_reached_accept_state():
+            assert stepper.get_current_value() == {
+                "name": "get_weather",
+                "arguments": {"city": "Paris", "days": 3},
+            }
+
+
+@pytest.mark.parametrize(
+    "call",
+    [
+        '{"name": "get_time"',
+        '{"name": "get_weather", "arguments": {"days": 3}}',
+        '{"name": "get_weather", "arguments": {"city": 3}}',
+    ],
+)
+def test_tool_call_state_machine_rejects_invalid_calls(call: str) -> None:
+    """Test that a tool call with a wrong name or invalid arguments is rejected."""
+    _, state_machine = tool_call_state_machine("get_weather", WEATHER_PARAMS)
+    steppers = state_machine.advance_all_basic(state_machine.get_steppers(), call)
+    assert not any(stepper.has_reached_accept_state() for stepper in steppers)
+
+
+def test_dangling_reference_raises_at_compile_time() -> None:
+    """Test that a reference to a missing definition is named before walking starts."""
+    schema = {
+        "type": "object",
+        "properties": {
+            "home": {"$ref": "#/$defs/Address"},
+            "work": {"$ref": "#/$defs/Office"},
+        },
+        "$defs": {
+            "Address": {"type": "object", "properties": {"city": {"type": "string"}}}
+        },
+    }
+    with pytest.raises(PseGrammarError, match="#/\\$defs/Office") as exc_info:
+        json_schema_state_machine(schema)
+    assert exc_info.value.reference == "#/$defs/Office"
+
+    del schema["properties"]["work"]
+    _, state_machine = json_schema_state_machine(schema)
+    assert isinstance(state_machine, ObjectSchemaStateMachine)
@@ -0,0 +1,93 @@
+import threading
+
+import pytest
+
+from pse.types.json.schema_cache import SchemaCache
+
+SCHEMA = {
+    "type": "object",
+    "properties": {"name": {"type": "string"}},
+    "required": ["name"],
+}
+
+
+def test_same_schema_hits_cache():
+    """Test that compiling an equivalent schema twice returns the same state machine."""
+    cache = SchemaCache()
+    _, first = cache.get_or_compile(SCHEMA)
+    _, second = cache.get_or_compile(dict(SCHEMA))
+    assert first is second
+    assert cache.hits == 1
+    assert cache.misses == 1
+
+
+def test_different_schema_misses_cache():
+    """Test that a different schema or different options compile separately."""
+    cache = SchemaCache()
+    _, first = cache.get_or_compile(SCHEMA)
+    _, other_schema = cache.get_or_compile({"type": "string"})
+    _, other_options = cache.get_or_compile(SCHEMA, max_whitespace=0)
+    assert first is not other_schema
+    assert first is not other_options
+    assert cache.misses == 3
+    assert len(cache) == 3
+
+
+def test_least_recently_used_schema_is_evicted():
+    """Test that the cache evicts the least recently used schema when full."""
+    cache = SchemaCache(max_size=1)
+    _, first = cache.get_or_compile(SCHEMA)
+    cache.get_or_compile({"type": "string"})
+    _, recompiled = cache.get_or_compile(SCHEMA)
+    assert len(cache) == 1
+    assert recompiled is not first
+
+
+def test_concurrent_compiles_share_state_machine():
+    """Test that concurrent compiles of the same schema return one shared state machine."""
+    cache = SchemaCache()
+    results = []
+
+    def compile_schema():
+        results.append(cache.get_or_compile(SCHEMA)[1])
+
+    threads = [threading.Thread(target=compile_schema) for _ in range(8)]
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join()
+
+    assert all(result is results[0] for result in results)
+    assert cache.misses == 1
+
+
+def test_slow_compile_does_not_block_other_schemas(monkeypatch: pytest.MonkeyPatch):
+    """Test that a schema still compiling leaves other schemas free to compile."""
+    from pse.types.json import schema_cache
+
+    compile_schema = schema_cache.json_schema_state_machine
+    started, release = threading.Event(), threading.Event()
+
+    def slow_compile(json_schema, **kwargs):
+        if json_schema == SCHEMA:
+            started.set()
+            release.wait(timeout=5)
+        return compile_schema(json_schema, **kwargs)
+
+    monkeypatch.setattr(schema_cache, "json_schema_state_machine", slow_compile)
+    cache = SchemaCache()
+    slow = threading.Thread(target=cache.get_or_compile, args=(SCHEMA,))
+    slow.start()
+    assert started.wait(timeout=5)
+
+    cache.get_or_compile({"type": "integer"})
+    assert len(cache) == 1
+    release.set()
+    slow.join()
+    assert len(cache) == 2
+
+
+def test_invalid_max_size():
+    """Test that a non-positive cache size is rejected."""
+    with pytest.raises(ValueError):
+        SchemaCache(max_size=0)
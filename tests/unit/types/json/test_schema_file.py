@@ -0,0 +1,66 @@
+import struct
+from pathlib import Path
+
+import pytest
+from helpers import accepts
+
+from pse.types.json.schema_cache import SchemaCache
+from pse.types.json.schema_file import (
+    SCHEMA_FILE_MAGIC,
+    SCHEMA_FILE_VERSION,
+    load_versioned,
+    save_versioned,
+)
+
+SCHEMA = {
+    "type": "object",
+    "properties": {"name": {"type": "string"}},
+    "required": ["name"],
+}
+
+
+def test_round_trip(tmp_path: Path) -> None:
+    path = tmp_path / "schema.pse"
+    save_versioned(path, SCHEMA, max_whitespace=0)
+    assert path.read_bytes().startswith(SCHEMA_FILE_MAGIC)
+
+    json_schema, state_machine = load_versioned(path)
+    assert json_schema == SCHEMA
+    assert accepts(state_machine, '{"name":"Ada"}')
+    assert not accepts(state_machine, '{"name": "Ada"}')
+
+
+def test_round_trip_through_cache(tmp_path: Path) -> None:
+    path = tmp_path / "schema.pse"
+    save_versioned(path, SCHEMA)
+    cache = SchemaCache()
+    _, first = load_versioned(path, schema_cache=cache)
+    _, second = load_versioned(path, schema_cache=cache)
+    assert first is second
+
+
+def test_bumped_version_is_refused(tmp_path: Path) -> None:
+    path = tmp_path / "schema.pse"
+    save_versioned(path, SCHEMA)
+    data = bytearray(path.read_bytes())
+    struct.pack_into(">I", data, len(SCHEMA_FILE_MAGIC), SCHEMA_FILE_VERSION + 1)
+    path.write_bytes(bytes(data))
+
+    with pytest.raises(ValueError, match="Unsupported schema file version"):
+        load_versioned(path)
+
+
+@pytest.mark.parametrize("content", [b"", b"JSON{}", SCHEMA_FILE_MAGIC + b"\x00\x00\x00\x01{"])
+def test_invalid_file_is_refused(tmp_path: Path, content: bytes) -> None:
+    path = tmp_path / "schema.pse"
+    path.write_bytes(content)
+    with pytest.raises(ValueError):
+        load_versioned(path)
+
+
+def test_non_json_options_are_refused(tmp_path: Path) -> None:
+    """Test that an option that cannot be restored on load is refused up front."""
+    path = tmp_path / "schema.pse"
+    with pytest.raises(ValueError):
+        save_versioned(path, SCHEMA, name_fn=lambda name: name)
+    assert not path.exists()
@@ -0,0 +1,43 @@
+import pytest
+from helpers import accepts
+
+from pse.types.base.phrase import PhraseStateMachine
+from pse.types.misc.dialogue import alternating
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ("a b", True),
+        ("a b a b", True),
+        ("a a", False),
+        ("b a", False),
+        ("a b a", False),
+        ("a b b", False),
+    ],
+)
+def test_alternating(value: str, should_accept: bool) -> None:
+    """Test that turns must alternate, starting with the first structure."""
+    sm = alternating(PhraseStateMachine("a"), PhraseStateMachine("b"))
+    assert accepts(sm, value) == should_accept
+
+
+def test_alternating_max_rounds() -> None:
+    sm = alternating(PhraseStateMachine("a"), PhraseStateMachine("b"), max_rounds=2)
+    assert accepts(sm, "a b a b")
+    assert not accepts(sm, "a b a b a b")
+
+
+def test_alternating_schemas() -> None:
+    """Test alternating between two JSON schemas."""
+    from pse.types.json import json_schema_state_machine
+
+    _, user = json_schema_state_machine(
+        {"type": "object", "properties": {"question": {"type": "string"}}, "required": ["question"]}
+    )
+    _, assistant = json_schema_state_machine(
+        {"type": "object", "properties": {"answer": {"type": "string"}}, "required": ["answer"]}
+    )
+    sm = alternating(user, assistant, separator=PhraseStateMachine("\n"))
+    assert accepts(sm, '{"question": "hi?"}\n{"answer": "hello"}\n{"question": "ok?"}\n{"answer": "yes"}')
+    assert not accepts(sm, '{"question": "hi?"}\n{"question": "hi?"}')
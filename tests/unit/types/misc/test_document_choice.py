@@ -0,0 +1,60 @@
+import pytest
+
+from pse.types.misc.document_choice import DocumentChoiceStateMachine
+
+FIRST = '{"name": "alpha", "value": 1}'
+SECOND = '{"name": "alpha", "value": 2}'
+
+
+def test_shared_prefix_is_factored():
+    """Test that documents sharing a prefix start with a single edge."""
+    sm = DocumentChoiceStateMachine([FIRST, SECOND])
+    assert len(sm.get_edges(0)) == 1
+
+
+@pytest.mark.parametrize("document", [FIRST, SECOND])
+def test_accepts_each_document(document):
+    """Test that each document is accepted in full."""
+    sm = DocumentChoiceStateMachine([FIRST, SECOND])
+    steppers = sm.advance_all_basic(sm.get_steppers(), document)
+
+    assert any(stepper.has_reached_accept_state() for stepper in steppers)
+    for stepper in steppers:
+        if stepper.has_reached_accept_state():
+            assert stepper.get_raw_value() == document
+
+
+def test_narrows_after_first_differing_token():
+    """Test that the choice narrows to one document once they differ."""
+    sm = DocumentChoiceStateMachine([FIRST, SECOND])
+    steppers = sm.advance_all_basic(sm.get_steppers(), '{"name": "alpha", "value": ')
+    assert steppers
+    assert not any(stepper.has_reached_accept_state() for stepper in steppers)
+
+    steppers = sm.advance_all_basic(steppers, "2")
+    assert steppers
+    assert all(
+        continuation == "}"
+        for stepper in steppers
+        for continuation in stepper.get_valid_continuations()
+    )
+    assert not sm.advance_all_basic(steppers, "1")
+
+
+def test_rejects_other_documents():
+    """Test that a document outside the choice is rejected."""
+    sm = DocumentChoiceStateMachine([FIRST, SECOND])
+    steppers = sm.advance_all_basic(sm.get_steppers(), '{"name": "beta"')
+    assert not any(stepper.has_reached_accept_state() for stepper in steppers)
+
+
+def test_non_string_documents_are_serialized():
+    """Test that non-string documents are serialized as JSON."""
+    sm = DocumentChoiceStateMachine([{"a": 1}, [1, 2]])
+    assert sm.documents == ['{"a": 1}', "[1, 2]"]
+
+
+def test_empty_documents():
+    """Test that an empty document list is rejected."""
+    with pytest.raises(ValueError):
+        DocumentChoiceStateMachine([])
@@ -1,4 +1,8 @@
-from pse.types.misc.freeform import FreeformStateMachine, FreeformStepper
+from pse.types.misc.freeform import (
+    FreeformStateMachine,
+    FreeformStepper,
+    followed_by_optional,
+)
 
 
 def test_freeform_basic():
@@ -121,3 +125,29 @@ def test_freeform_string_representation():
     """Test the string representation of FreeformStateMachine."""
     sm = FreeformStateMachine(end_delimiters=["END"])
     assert str(sm) == "FreeformText"
+
+
+def test_followed_by_optional_allows_trailing_text():
+    """Test that trailing text after a complete value is allowed but not required."""
+    from pse.types.json import json_schema_state_machine
+
+    _, object_sm = json_schema_state_machine(
+        {"type": "object", "properties": {"a": {"type": "integer"}}, "required": ["a"]}
+    )
+    sm = followed_by_optional(object_sm)
+
+    steppers = sm.advance_all_basic(sm.get_steppers(), '{"a": 1}')
+    assert any(stepper.has_reached_accept_state() for stepper in steppers)
+
+    steppers = sm.advance_all_basic(steppers, " because a must be one.")
+    assert any(stepper.has_reached_accept_state() for stepper in steppers)
+    assert all(not stepper.remaining_input for stepper in steppers)
+
+
+def test_followed_by_optional_still_requires_primary_value():
+    """Test that the free region cannot replace the primary value."""
+    from pse.types.base.phrase import PhraseStateMachine
+
+    sm = followed_by_optional(PhraseStateMachine("done"))
+    steppers = sm.advance_all_basic(sm.get_steppers(), "not done")
+    assert not any(stepper.has_reached_accept_state() for stepper in steppers)
@@ -0,0 +1,29 @@
+import pytest
+from helpers import accepts
+
+from pse.types.color import BASIC_COLOR_NAMES, ColorStateMachine
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ("#ff0000", True),
+        ("#F00", True),
+        ("rgb(255,0,0)", True),
+        ("rgb(12, 34, 56)", True),
+        ("rgb(300,0,0)", False),
+        ("rgb(255,0)", False),
+        ("#ff00", False),
+        ("#gg0000", False),
+        ("red", False),
+    ],
+)
+def test_color(value: str, should_accept: bool) -> None:
+    assert accepts(ColorStateMachine(), value) == should_accept
+
+
+def test_named_colors() -> None:
+    state_machine = ColorStateMachine(BASIC_COLOR_NAMES)
+    assert accepts(state_machine, "red")
+    assert accepts(state_machine, "#ff0000")
+    assert not accepts(state_machine, "crimson")
@@ -0,0 +1,25 @@
+import pytest
+from helpers import accepts
+
+from pse.types.cron import CronStateMachine
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ("*/5 0 * * 1-5", True),
+        ("* * * * *", True),
+        ("0,15,30,45 9-17 1 1,6 0", True),
+        ("5/10 */2 1-31/2 12 7", True),
+        ("60 0 * * *", False),
+        ("0 24 * * *", False),
+        ("0 0 0 * *", False),
+        ("0 0 * 13 *", False),
+        ("0 0 * * 8", False),
+        ("*/0 * * * *", False),
+        ("* * * *", False),
+        ("* * * * * *", False),
+    ],
+)
+def test_cron(value: str, should_accept: bool) -> None:
+    assert accepts(CronStateMachine(), value) == should_accept
@@ -0,0 +1,48 @@
+import pytest
+from helpers import accepts
+
+from pse.types.currency import CurrencyStateMachine
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ("$1,234.56", True),
+        ("$1234.56", True),
+        ("1,234,567.00", True),
+        ("EUR 12.00", True),
+        ("£0.99", True),
+        ("$1234.5", False),
+        ("$1234", False),
+        ("$1,234.567", False),
+        ("$12,34.56", False),
+        ("$1,2345.00", False),
+        ("CHF 1.00", False),
+        ("$", False),
+    ],
+)
+def test_currency(value: str, should_accept: bool) -> None:
+    assert accepts(CurrencyStateMachine(), value) == should_accept
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ("€1.234,56", True),
+        ("1234,56", True),
+        ("€1,234.56", False),
+    ],
+)
+def test_currency_for_locale(value: str, should_accept: bool) -> None:
+    assert accepts(CurrencyStateMachine.for_locale("de_DE"), value) == should_accept
+
+
+def test_whole_amounts() -> None:
+    state_machine = CurrencyStateMachine(currencies=["JPY"], decimal_places=0)
+    assert accepts(state_machine, "JPY 1,500")
+    assert not accepts(state_machine, "JPY 1,500.00")
+
+
+def test_unknown_locale() -> None:
+    with pytest.raises(ValueError):
+        CurrencyStateMachine.for_locale("xx_XX")
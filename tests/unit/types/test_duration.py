@@ -0,0 +1,54 @@
+import pytest
+from helpers import accepts
+
+from pse.types.duration import DurationStateMachine
+from pse.types.json import json_schema_state_machine
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ("P1Y2M10DT2H30M", True),
+        ("P3D", True),
+        ("PT45S", True),
+        ("PT1.5S", True),
+        ("P1M", True),
+        ("PT1M", True),
+        ("P", False),
+        ("PT", False),
+        ("P1YT", False),
+        ("P2M1Y", False),
+        ("PT30M2H", False),
+        ("P1.5D", False),
+        ("1Y", False),
+    ],
+)
+def test_duration(value: str, should_accept: bool) -> None:
+    assert accepts(DurationStateMachine(), value) == should_accept
+
+
+def test_duration_format_schema() -> None:
+    _, state_machine = json_schema_state_machine({"type": "string", "format": "duration"})
+    assert accepts(state_machine, '"P1Y2M10DT2H30M"')
+    assert not accepts(state_machine, '"P"')
+
+
+def test_duration_format_with_length_constraints() -> None:
+    """Test that length constraints are kept rather than replaced by the duration grammar."""
+    _, state_machine = json_schema_state_machine(
+        {"type": "string", "format": "duration", "maxLength": 4}
+    )
+    assert accepts(state_machine, '"P1Y"')
+    assert not accepts(state_machine, '"P1Y2M10D"')
+    # the length constraint narrows the duration grammar rather than replacing it
+    assert not accepts(state_machine, '"abc"')
+
+
+def test_duration_format_with_pattern() -> None:
+    """Test that a pattern and the duration grammar must both match."""
+    _, state_machine = json_schema_state_machine(
+        {"type": "string", "format": "duration", "pattern": "^PT"}
+    )
+    assert accepts(state_machine, '"PT45S"')
+    assert not accepts(state_machine, '"P3D"')
+    assert not accepts(state_machine, '"PTX"')
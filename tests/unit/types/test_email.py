@@ -0,0 +1,45 @@
+import pytest
+from helpers import accepts
+
+from pse.types.email import EmailStateMachine
+from pse.types.json import json_schema_state_machine
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ("a.b@example.com", True),
+        ("first+tag@mail.example.co.uk", True),
+        ("o'brien@my-host.org", True),
+        ("x@a.b", True),
+        ("a@@b", False),
+        ("example.com", False),
+        ("a@example", False),
+        (".a@example.com", False),
+        ("a.@example.com", False),
+        ("a..b@example.com", False),
+        ("a@-example.com", False),
+        ("a@example-.com", False),
+        ("a@example..com", False),
+        ("a b@example.com", False),
+    ],
+)
+def test_email(value: str, should_accept: bool) -> None:
+    assert accepts(EmailStateMachine(), value) == should_accept
+
+
+def test_email_format() -> None:
+    _, state_machine = json_schema_state_machine({"type": "string", "format": "email"})
+    assert accepts(state_machine, '"a.b@example.com"')
+    assert not accepts(state_machine, '"a@@b"')
+
+
+def test_email_format_with_length_constraints() -> None:
+    """Test that length constraints and the email grammar must both be met."""
+    _, state_machine = json_schema_state_machine(
+        {"type": "string", "format": "email", "minLength": 8, "maxLength": 12}
+    )
+    assert accepts(state_machine, '"ab@cd.com"')
+    assert not accepts(state_machine, '"a@b.co"')
+    assert not accepts(state_machine, '"abcdef@example.com"')
+    assert not accepts(state_machine, '"abcdefghij"')
@@ -118,3 +118,75 @@ def test_enum_requires_quotes_by_default():
     steppers = sm.advance_all_basic(steppers, "test")  # no quotes
 
     assert not any(stepper.has_reached_accept_state() for stepper in steppers)
+
+
+@pytest.mark.parametrize(
+    "value, expected",
+    [('"a"', "a"), ("1", 1), ("true", True), ("null", None)],
+)
+def test_enum_with_mixed_types(value, expected):
+    """Test that enums mixing strings, numbers, booleans and null accept each serialization."""
+    sm = EnumStateMachine(["a", 1, True, None])
+    steppers = sm.get_steppers()
+    steppers = sm.advance_all_basic(steppers, value)
+
+    accepted = [stepper for stepper in steppers if stepper.has_reached_accept_state()]
+    assert accepted
+    for stepper in accepted:
+        assert stepper.get_current_value() == expected
+        assert type(stepper.get_current_value()) is type(expected)
+
+
+def test_enum_with_mixed_types_prefix_narrowing():
+    """Test that a partial prefix narrows the candidates across types."""
+    sm = EnumStateMachine(["a", 1, True, None])
+    steppers = sm.advance_all_basic(sm.get_steppers(), "t")
+
+    assert steppers
+    assert not any(stepper.has_reached_accept_state() for stepper in steppers)
+    assert not sm.advance_all_basic(steppers, "ull")
+    steppers = sm.advance_all_basic(steppers, "rue")
+    assert any(stepper.has_reached_accept_state() for stepper in steppers)
+
+
+def test_enum_with_mixed_types_rejects_quoted_literals():
+    """Test that non-string enum values are not accepted in quoted form."""
+    sm = EnumStateMachine(["a", 1, True, None])
+    steppers = sm.advance_all_basic(sm.get_steppers(), '"true"')
+
+    assert not any(stepper.has_reached_accept_state() for stepper in steppers)
+
+
+@pytest.mark.parametrize(
+    "value, expected",
+    [('"1"', "1"), ("1", 1), ('"true"', "true"), ("true", True)],
+)
+def test_enum_keeps_strings_that_look_like_literals(value, expected):
+    """Test that "1" and 1 (or "true" and True) are distinct enum members."""
+    sm = EnumStateMachine(["1", 1, "true", True])
+    steppers = sm.advance_all_basic(sm.get_steppers(), value)
+
+    accepted = [stepper for stepper in steppers if stepper.has_reached_accept_state()]
+    assert accepted
+    for stepper in accepted:
+        assert stepper.get_current_value() == expected
+        assert type(stepper.get_current_value()) is type(expected)
+
+
+def test_case_insensitive_enum_returns_canonical_value():
+    """Test that case-insensitive enums match any casing but return the schema value."""
+    sm = EnumStateMachine(["red"], case_insensitive=True)
+    steppers = sm.advance_all_basic(sm.get_steppers(), '"RED"')
+
+    assert any(stepper.has_reached_accept_state() for stepper in steppers)
+    for stepper in steppers:
+        if stepper.has_reached_accept_state():
+            assert stepper.get_current_value() == "red"
+
+
+def test_enum_is_case_sensitive_by_default():
+    """Test that enums only match the exact casing by default."""
+    sm = EnumStateMachine(["red"])
+    steppers = sm.advance_all_basic(sm.get_steppers(), '"RED"')
+
+    assert not any(stepper.has_reached_accept_state() for stepper in steppers)
@@ -0,0 +1,42 @@
+import pytest
+from helpers import accepts
+
+from pse.types.file_path import FilePathStateMachine, PathStyle
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ("/usr/bin/x", True),
+        ("/", True),
+        ("docs/readme.md", True),
+        ("build/", True),
+        ("/usr/bin:x", False),
+        ("/usr//bin", False),
+        ("C:\\Users", False),
+    ],
+)
+def test_unix_path(value: str, should_accept: bool) -> None:
+    assert accepts(FilePathStateMachine(PathStyle.UNIX), value) == should_accept
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ("C:\\Users\\me\\file.txt", True),
+        ("c:/temp", True),
+        ("docs\\readme.md", True),
+        ("C:\\a|b", False),
+        ("C:", False),
+        ("C:\\\\x", False),
+    ],
+)
+def test_windows_path(value: str, should_accept: bool) -> None:
+    assert accepts(FilePathStateMachine(PathStyle.WINDOWS), value) == should_accept
+
+
+def test_absolute_requirement() -> None:
+    assert accepts(FilePathStateMachine(absolute=True), "/etc/hosts")
+    assert not accepts(FilePathStateMachine(absolute=True), "etc/hosts")
+    assert accepts(FilePathStateMachine(absolute=False), "etc/hosts")
+    assert not accepts(FilePathStateMachine(absolute=False), "/etc/hosts")
@@ -0,0 +1,52 @@
+import pytest
+from helpers import accepts
+
+from pse.types.identifier import IdentifierRules, IdentifierStateMachine
+
+
+@pytest.mark.parametrize("language", ["python", "rust"])
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ("my_var", True),
+        ("_private", True),
+        ("x1", True),
+        ("classic", True),
+        ("1var", False),
+        ("my-var", False),
+        ("", False),
+    ],
+)
+def test_identifier_rules(language: str, value: str, should_accept: bool) -> None:
+    assert accepts(IdentifierStateMachine(language), value) == should_accept
+
+
+@pytest.mark.parametrize(
+    "language, reserved",
+    [
+        ("python", "class"),
+        ("python", "lambda"),
+        ("rust", "fn"),
+        ("rust", "impl"),
+    ],
+)
+def test_identifier_rejects_reserved_keywords(language: str, reserved: str) -> None:
+    assert not accepts(IdentifierStateMachine(language), reserved)
+
+
+def test_keywords_are_per_language() -> None:
+    assert accepts(IdentifierStateMachine("rust"), "class")
+    assert accepts(IdentifierStateMachine("python"), "fn")
+
+
+def test_custom_rules() -> None:
+    rules = IdentifierRules("$abc", "$abc123", frozenset(["abc"]))
+    state_machine = IdentifierStateMachine(rules=rules)
+    assert accepts(state_machine, "$a1")
+    assert not accepts(state_machine, "abc")
+    assert not accepts(state_machine, "1a")
+
+
+def test_unknown_language() -> None:
+    with pytest.raises(ValueError):
+        IdentifierStateMachine("cobol")
@@ -0,0 +1,52 @@
+import pytest
+from helpers import accepts
+
+from pse.types.json import json_schema_state_machine
+from pse.types.json_pointer import JsonPointerStateMachine
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ("/a/b/0", True),
+        ("/a~1b", True),
+        ("/m~0n", True),
+        ("/", True),
+        ("/a//b", True),
+        ("/a~b", False),
+        ("/a~", False),
+        ("a/b", False),
+        ('/a"b', False),
+    ],
+)
+def test_json_pointer(value: str, should_accept: bool) -> None:
+    assert accepts(JsonPointerStateMachine(), value) == should_accept
+
+
+def test_json_pointer_format_schema() -> None:
+    _, state_machine = json_schema_state_machine({"type": "string", "format": "json-pointer"})
+    assert accepts(state_machine, '"/a~1b/0"')
+    assert accepts(state_machine, '""')
+    assert not accepts(state_machine, '"/a~2"')
+
+
+def test_json_pointer_format_with_pattern() -> None:
+    """Test that a pattern and the pointer grammar must both match."""
+    _, state_machine = json_schema_state_machine(
+        {"type": "string", "format": "json-pointer", "pattern": "^/items/"}
+    )
+    assert accepts(state_machine, '"/items/0"')
+    assert not accepts(state_machine, '"/other/0"')
+    assert not accepts(state_machine, '""')
+    assert not accepts(state_machine, '"items/0"')
+
+
+def test_json_pointer_format_with_length_constraints() -> None:
+    """Test that length constraints and the pointer grammar must both be met."""
+    _, state_machine = json_schema_state_machine(
+        {"type": "string", "format": "json-pointer", "minLength": 2, "maxLength": 6}
+    )
+    assert accepts(state_machine, '"/a/b"')
+    assert not accepts(state_machine, '""')
+    assert not accepts(state_machine, '"/a/b/c/d"')
+    assert not accepts(state_machine, '"ab"')
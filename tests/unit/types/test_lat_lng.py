@@ -0,0 +1,43 @@
+import pytest
+from helpers import accepts
+
+from pse.types.lat_lng import LatLngStateMachine
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ("37.77,-122.41", True),
+        ("37.77, -122.41", True),
+        ("-90,180", True),
+        ("90.0,-180.0", True),
+        ("0,0", True),
+        ("95.0,10", False),
+        ("-90.5,0", False),
+        ("10,180.01", False),
+        ("10,-200", False),
+        ("37.77", False),
+        ("37.77,", False),
+        ("37.77;-122.41", False),
+    ],
+)
+def test_lat_lng(value: str, should_accept: bool) -> None:
+    assert accepts(LatLngStateMachine(), value) == should_accept
+
+
+@pytest.mark.parametrize(
+    "prefix, is_live",
+    [
+        ("9", True),
+        ("90", True),
+        ("95", False),
+        ("-90.5", False),
+        ("10,18", True),
+        ("10,181", False),
+        ("10,-200", False),
+    ],
+)
+def test_lat_lng_rejects_out_of_range_prefix(prefix: str, is_live: bool) -> None:
+    state_machine = LatLngStateMachine()
+    steppers = state_machine.advance_all_basic(state_machine.get_steppers(), prefix)
+    assert bool(steppers) is is_live
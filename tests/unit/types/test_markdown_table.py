@@ -0,0 +1,56 @@
+import pytest
+from helpers import accepts
+
+from pse.types.json.json_number import NumberSchemaStateMachine
+from pse.types.markdown_table import (
+    MarkdownTableRowStateMachine,
+    MarkdownTableStateMachine,
+)
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ("| a | b | c |", True),
+        ("|a|b|c|", True),
+        ("| 1 | two words | 3.0 |", True),
+        ("| a | b |", False),
+        ("| a | b | c | d |", False),
+        ("| a | | c |", False),
+        ("|a|  |c|", False),
+        ("a | b | c", False),
+    ],
+)
+def test_markdown_table_row(value: str, should_accept: bool) -> None:
+    assert accepts(MarkdownTableRowStateMachine.free_form(3), value) == should_accept
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ("| name | age |\n| --- | ---: |\n| Ada | 36 |", True),
+        ("| name | age |\n|:---|:---:|\n| Ada | 36 |\n| Alan | 41 |", True),
+        ("| name | age |\n| --- | --- |", False),
+        ("| name | age |\n| -- | --- |\n| Ada | 36 |", False),
+        ("| name | age |\n| --- | --- |\n| Ada | old |", False),
+        ("| name | years |\n| --- | --- |\n| Ada | 36 |", False),
+        ("| name | age |\n| --- | --- |\n| Ada |", False),
+    ],
+)
+def test_markdown_table(value: str, should_accept: bool) -> None:
+    state_machine = MarkdownTableStateMachine(
+        ["name", "age"],
+        cell_state_machines={"age": NumberSchemaStateMachine({"type": "integer"})},
+    )
+    assert accepts(state_machine, value) == should_accept
+
+
+def test_markdown_table_without_rows() -> None:
+    state_machine = MarkdownTableStateMachine(["name"], min_rows=0)
+    assert accepts(state_machine, "| name |\n| --- |")
+    assert accepts(state_machine, "| name |\n| --- |\n| Ada |")
+
+
+def test_unknown_column_cells() -> None:
+    with pytest.raises(ValueError):
+        MarkdownTableStateMachine(["name"], cell_state_machines={"age": NumberSchemaStateMachine({})})
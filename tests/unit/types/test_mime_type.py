@@ -0,0 +1,24 @@
+import pytest
+from helpers import accepts
+
+from pse.types.mime_type import MimeTypeStateMachine
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ("application/json", True),
+        ("application/json; charset=utf-8", True),
+        ("text/plain;charset=us-ascii;format=flowed", True),
+        ('multipart/form-data; boundary="a b"', True),
+        ("application/vnd.api+json", True),
+        ("application//json", False),
+        ("application/", False),
+        ("/json", False),
+        ("application/json;", False),
+        ("application/json; charset", False),
+        ("text/pl ain", False),
+    ],
+)
+def test_mime_type(value: str, should_accept: bool) -> None:
+    assert accepts(MimeTypeStateMachine(), value) == should_accept
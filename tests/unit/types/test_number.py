@@ -1,9 +1,15 @@
+from decimal import Decimal
+
 import pytest
 from pse_core.state_machine import StateMachine
 from pse_core.stepper import Stepper
 
 from pse.types.base.phrase import PhraseStateMachine
-from pse.types.number import NumberStateMachine
+from pse.types.number import (
+    CanonicalForm,
+    NumberStateMachine,
+    QuantizedNumberStateMachine,
+)
 
 
 @pytest.fixture
@@ -521,3 +527,135 @@ def test_number_acceptor(
                 stepper.get_current_value()
                 == f"Value: {str(value) + str(followup_value or '')}!"
             )
+
+
+@pytest.mark.parametrize(
+    "input_string, expected_value",
+    [
+        ("1e3", 1000.0),
+        ("1.5E-2", 0.015),
+        ("2E+2", 200.0),
+        ("-1e308", -1e308),
+    ],
+)
+def test_exponent_value_keeps_raw_value(input_string: str, expected_value: float) -> None:
+    """Test that exponent forms parse to floats while the raw value is preserved."""
+    sm = NumberStateMachine()
+    steppers = sm.advance_all_basic(sm.get_steppers(), input_string)
+    assert any(stepper.has_reached_accept_state() for stepper in steppers)
+    for stepper in steppers:
+        if stepper.has_reached_accept_state():
+            value = stepper.get_current_value()
+            assert isinstance(value, float)
+            assert value == pytest.approx(expected_value)
+            assert stepper.get_raw_value() == input_string
+
+
+def test_exact_decimal_mode() -> None:
+    """Test that exact-decimal mode keeps every digit of a high-precision number."""
+    input_string = "12345678901234567890.123456789"
+    sm = NumberStateMachine(exact_decimal=True)
+    steppers = sm.advance_all_basic(sm.get_steppers(), input_string)
+    assert any(stepper.has_reached_accept_state() for stepper in steppers)
+    for stepper in steppers:
+        if stepper.has_reached_accept_state():
+            assert stepper.get_current_value() == Decimal(input_string)
+
+
+@pytest.mark.parametrize(
+    "canonical_form, input_string, should_accept",
+    [
+        (CanonicalForm.ANY, "1", True),
+        (CanonicalForm.ANY, "1.0", True),
+        (CanonicalForm.ANY, "1e0", True),
+        (CanonicalForm.INTEGER_IF_WHOLE, "1", True),
+        (CanonicalForm.INTEGER_IF_WHOLE, "1.0", False),
+        (CanonicalForm.INTEGER_IF_WHOLE, "1e0", False),
+        (CanonicalForm.INTEGER_IF_WHOLE, "1.5", True),
+        (CanonicalForm.INTEGER_IF_WHOLE, "-2.50", True),
+        (CanonicalForm.ALWAYS_DECIMAL, "1", False),
+        (CanonicalForm.ALWAYS_DECIMAL, "1.0", True),
+        (CanonicalForm.ALWAYS_DECIMAL, "1e0", False),
+    ],
+)
+def test_canonical_form(
+    canonical_form: CanonicalForm, input_string: str, should_accept: bool
+) -> None:
+    """Test that the canonical form restricts which spelling of a number is accepted."""
+    sm = NumberStateMachine(canonical_form=canonical_form)
+    steppers = sm.advance_all_basic(sm.get_steppers(), input_string)
+    assert any(stepper.has_reached_accept_state() for stepper in steppers) == should_accept
+
+
+
+@pytest.mark.parametrize(
+    "input_string, should_accept",
+    [
+        ("0.00123", True),
+        ("0.001234", False),
+        ("123", True),
+        ("1234", False),
+        ("-1.23e10", True),
+        ("1.234e1", False),
+    ],
+)
+def test_max_significant_figures(input_string: str, should_accept: bool) -> None:
+    """Test that leading zeros and the decimal point do not count as significant figures."""
+    sm = NumberStateMachine(max_significant_figures=3)
+    steppers = sm.advance_all_basic(sm.get_steppers(), input_string)
+    accepted = any(
+        stepper.has_reached_accept_state() and not stepper.remaining_input
+        for stepper in steppers
+    )
+    assert accepted == should_accept
+
+
+def test_max_significant_figures_token_spanning_exponent() -> None:
+    """Test that exponent digits in a token that also ends the mantissa are not counted."""
+    sm = NumberStateMachine(max_significant_figures=2)
+    steppers = sm.advance_all_basic(sm.get_steppers(), "1")
+    steppers = sm.advance_all_basic(steppers, "2e345")
+    assert any(
+        stepper.has_reached_accept_state() and not stepper.remaining_input
+        for stepper in steppers
+    )
+    for stepper in steppers:
+        if stepper.has_reached_accept_state():
+            assert stepper.get_raw_value() == "12e345"
+
+    steppers = sm.advance_all_basic(sm.get_steppers(), "1")
+    assert not sm.advance_all_basic(steppers, "23e4")
+
+
+@pytest.mark.parametrize(
+    "input_string, expected_value",
+    [("0", 0), ("0.5", 0.5), ("1", 1), ("1.5", 1.5), ("2", 2)],
+)
+def test_quantized_number_accepts_grid_values(
+    input_string: str, expected_value: float
+) -> None:
+    """Test that every value on the grid is accepted."""
+    sm = QuantizedNumberStateMachine(0, 2, 0.5)
+    steppers = sm.advance_all_basic(sm.get_steppers(), input_string)
+    accepted = [s for s in steppers if s.has_reached_accept_state() and not s.remaining_input]
+    assert accepted
+    assert all(stepper.get_current_value() == expected_value for stepper in accepted)
+
+
+@pytest.mark.parametrize("input_string", ["0.25", "2.5", "1.0", "-0.5", "3"])
+def test_quantized_number_rejects_off_grid_values(input_string: str) -> None:
+    """Test that values off the grid, or not in shortest form, are rejected."""
+    sm = QuantizedNumberStateMachine(0, 2, 0.5)
+    steppers = sm.advance_all_basic(sm.get_steppers(), input_string)
+    assert not any(
+        stepper.has_reached_accept_state() and not stepper.remaining_input
+        for stepper in steppers
+    )
+
+
+def test_quantized_number_invalid_grid() -> None:
+    """Test that invalid grids are rejected."""
+    with pytest.raises(ValueError):
+        QuantizedNumberStateMachine(0, 1, 0)
+    with pytest.raises(ValueError):
+        QuantizedNumberStateMachine(1, 0, 0.5)
@@ -0,0 +1,62 @@
+import pytest
+from helpers import accepts
+
+from pse.types.percentage import PercentageStateMachine
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ("50%", True),
+        ("99.9%", True),
+        ("0%", True),
+        ("100%", True),
+        ("100.0%", True),
+        ("150%", False),
+        ("100.5%", False),
+        ("-5%", False),
+        ("50", False),
+        ("50.%", False),
+        ("%", False),
+    ],
+)
+def test_bounded_percentage(value: str, should_accept: bool) -> None:
+    assert accepts(PercentageStateMachine(), value) == should_accept
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ("150%", True),
+        ("-5.25%", True),
+        ("1e3%", False),
+    ],
+)
+def test_unbounded_percentage(value: str, should_accept: bool) -> None:
+    assert accepts(PercentageStateMachine(bounded=False), value) == should_accept
+
+
+@pytest.mark.parametrize(
+    "decimal_places, value, should_accept",
+    [
+        (0, "50%", True),
+        (0, "50.5%", False),
+        (2, "12.34%", True),
+        (2, "12.345%", False),
+    ],
+)
+def test_decimal_places(decimal_places: int, value: str, should_accept: bool) -> None:
+    state_machine = PercentageStateMachine(decimal_places=decimal_places)
+    assert accepts(state_machine, value) == should_accept
+
+
+def test_second_decimal_point_in_token() -> None:
+    """Test that a token repeating the decimal point is rejected rather than raising."""
+    state_machine = PercentageStateMachine()
+    steppers = state_machine.advance_all_basic(state_machine.get_steppers(), "5.5")
+    assert all(stepper.is_within_bounds(".5") for stepper in steppers)
+    steppers = state_machine.advance_all_basic(steppers, ".5%")
+    assert not any(
+        stepper.has_reached_accept_state() and not stepper.remaining_input
+        for stepper in steppers
+    )
@@ -0,0 +1,91 @@
+import pytest
+from helpers import accepts
+
+from pse.types.regex import RegexSetStateMachine, RegexStateMachine
+
+
+@pytest.mark.parametrize(
+    "pattern, value, should_accept",
+    [
+        (r"\d{4}-\d{2}-\d{2}", "2024-01-15", True),
+        (r"\d{4}-\d{2}-\d{2}", "2024-01-1", False),
+        (r"\d{4}-\d{2}-\d{2}", "2024-1a-15", False),
+        (r"\w+", "snake_case1", True),
+        (r"\w+", "kebab-case", False),
+        (r"[A-F0-9]{2,4}", "0A", True),
+        (r"[A-F0-9]{2,4}", "0AFFE", False),
+        (r"(ab)*c?", "ababc", True),
+        (r"(ab)*c?", "aba", False),
+    ],
+)
+def test_regex_patterns(pattern: str, value: str, should_accept: bool) -> None:
+    assert accepts(RegexStateMachine(pattern), value) == should_accept
+
+
+def test_regex_consumes_valid_prefix() -> None:
+    """A token is consumed up to where the pattern can no longer match."""
+    state_machine = RegexStateMachine(r"\d{3}")
+    steppers = state_machine.advance_all_basic(state_machine.get_steppers(), "123abc")
+    assert steppers
+    assert all(stepper.get_raw_value() == "123" for stepper in steppers)
+    assert all(stepper.remaining_input == "abc" for stepper in steppers)
+
+
+def test_regex_is_not_free_text() -> None:
+    """A regex without a charset still constrains its tokens."""
+    state_machine = RegexStateMachine(r"\d+")
+    assert not any(stepper.accepts_any_token() for stepper in state_machine.get_steppers())
+
+
+def test_regex_continuations_follow_pattern() -> None:
+    """The continuations are the characters that keep a full match possible."""
+    state_machine = RegexStateMachine(r"\d{2}-[a-c]")
+    stepper = state_machine.get_new_stepper()
+    assert sorted(stepper.get_valid_continuations()) == list("0123456789")
+
+    steppers = state_machine.advance_all_basic([stepper], "12")
+    assert [stepper.get_valid_continuations() for stepper in steppers] == [["-"]]
+    steppers = state_machine.advance_all_basic(steppers, "-")
+    assert [sorted(stepper.get_valid_continuations()) for stepper in steppers] == [
+        ["a", "b", "c"]
+    ]
+
+
+def test_regex_matching_empty_string() -> None:
+    """A pattern matching the empty string may be skipped within a chain."""
+    from pse.types.base.chain import ChainStateMachine
+    from pse.types.base.phrase import PhraseStateMachine
+
+    state_machine = ChainStateMachine(
+        [PhraseStateMachine("a"), RegexStateMachine(r"\d*"), PhraseStateMachine("b")]
+    )
+    assert accepts(state_machine, "ab")
+    assert accepts(state_machine, "a12b")
+    assert not accepts(state_machine, "axb")
+
+
+def test_regex_invalid_pattern() -> None:
+    with pytest.raises(ValueError):
+        RegexStateMachine("[a-")
+
+
+def test_regex_set_reports_matched_pattern() -> None:
+    state_machine = RegexSetStateMachine([r"\d{3}", r"[a-z]+"])
+    for value, index in [("123", 0), ("abc", 1)]:
+        steppers = state_machine.advance_all_basic(state_machine.get_steppers(), value)
+        accepted = [stepper for stepper in steppers if stepper.has_reached_accept_state()]
+        assert accepted
+        assert accepted[0].get_matched_pattern_index() == index
+    assert not accepts(state_machine, "12a")
+
+    with pytest.raises(ValueError):
+        RegexSetStateMachine([])
+
+
+def test_regex_set_keeps_backreferences() -> None:
+    """A backreference refers to a group of its own pattern, not an earlier one."""
+    state_machine = RegexSetStateMachine([r"(a)x", r"(b)\1"])
+    assert accepts(state_machine, "ax")
+    assert accepts(state_machine, "bb")
+    assert not accepts(state_machine, "ba")
+    assert not any(stepper.accepts_any_token() for stepper in state_machine.get_steppers())
@@ -0,0 +1,31 @@
+import pytest
+from helpers import accepts
+
+from pse.types.sql import SqlSelectStateMachine
+
+
+@pytest.mark.parametrize(
+    "value, should_accept",
+    [
+        ("SELECT a FROM t WHERE a > 1", True),
+        ("SELECT a FROM t", True),
+        ("select * from t where a<=1.5", True),
+        ("SELECT a, b FROM t WHERE a = 'x' AND b != a", True),
+        ("SELECT a FROM t WHERE a > 1 OR b < -2", True),
+        ("SELECT c FROM t", False),
+        ("SELECT a FROM u", False),
+        ("SELECT a FROM t WHERE c > 1", False),
+        ("SELECT a FROM t WHERE a >", False),
+        ("SELECT a FROM t WHERE a > 1 AND", False),
+        ("SELECT FROM t", False),
+        ("SELECT a FROM t; DROP TABLE t", False),
+    ],
+)
+def test_sql_select(value: str, should_accept: bool) -> None:
+    state_machine = SqlSelectStateMachine(tables=["t"], columns=["a", "b"])
+    assert accepts(state_machine, value) == should_accept
+
+
+def test_names_required() -> None:
+    with pytest.raises(ValueError):
+        SqlSelectStateMachine(tables=[], columns=["a"])
@@ -1,6 +1,7 @@
 import pytest
 
-from pse.types.string import StringStateMachine
+from pse.types.base.character import CharacterStateMachine
+from pse.types.string import StringStateMachine, slug_string
 
 
 def test_basic() -> None:
@@ -451,3 +452,82 @@ def test_edge_case():
     assert len(steppers) == 1
     assert steppers[0].has_reached_accept_state()
     assert steppers[0].get_current_value() == "Hello There!"
+
+
+def test_slug_string():
+    slug_acceptor = slug_string()
+    steppers = slug_acceptor.advance_all_basic(slug_acceptor.get_steppers(), '"my-slug_1"')
+    assert any(stepper.has_reached_accept_state() for stepper in steppers)
+
+    steppers = slug_acceptor.advance_all_basic(slug_acceptor.get_steppers(), '"My Slug"')
+    assert not any(stepper.has_reached_accept_state() for stepper in steppers)
+
+
+def test_slug_string_length_bounds():
+    slug_acceptor = slug_string(min_length=2, max_length=4)
+    for value, accepted in [('"a"', False), ('"ab"', True), ('"abcd"', True), ('"abcde"', False)]:
+        steppers = slug_acceptor.advance_all_basic(slug_acceptor.get_steppers(), value)
+        assert any(stepper.has_reached_accept_state() for stepper in steppers) == accepted
+
+
+def test_slug_string_rejects_invalid_charset():
+    with pytest.raises(ValueError):
+        slug_string('abc"')
+
+
+@pytest.mark.parametrize(
+    "value, accepted",
+    [
+        ('"US"', True),
+        ('"U"', False),
+        ('"USA"', False),
+        ('""', False),
+        # an escape sequence decodes to a single character
+        ('"\\nA"', True),
+        ('"\\u00e9A"', True),
+    ],
+)
+def test_string_exact_length(value: str, accepted: bool) -> None:
+    sm = StringStateMachine(exact_length=2)
+    steppers = sm.advance_all_basic(sm.get_steppers(), value)
+    assert (
+        any(
+            stepper.has_reached_accept_state() and not stepper.remaining_input
+            for stepper in steppers
+        )
+        == accepted
+    )
+
+
+def test_string_exact_length_blocks_early_close() -> None:
+    """The closing quote is not valid before the string is long enough."""
+    sm = StringStateMachine(exact_length=2)
+    steppers = sm.advance_all_basic(sm.get_steppers(), '"U')
+    assert steppers
+    assert not sm.advance_all_basic(steppers, '"')
+
+
+def test_character_exact_length() -> None:
+    sm = CharacterStateMachine("ABCDEFGHIJKLMNOPQRSTUVWXYZ", exact_length=2)
+    for value, accepted in [("U", False), ("US", True), ("USA", False)]:
+        steppers = sm.advance_all_basic(sm.get_steppers(), value)
+        assert (
+            any(
+                stepper.has_reached_accept_state() and not stepper.remaining_input
+                for stepper in steppers
+            )
+            == accepted
+        )
+
+
+def test_many_repeated_characters() -> None:
+    """Test that a long run of one character, fed one at a time, is accepted intact."""
+    state_machine = StringStateMachine()
+    steppers = state_machine.advance_all_basic(state_machine.get_steppers(), '"')
+    for _ in range(1000):
+        steppers = state_machine.advance_all_basic(steppers, "a")
+        assert steppers
+    steppers = state_machine.advance_all_basic(steppers, '"')
+    accepted = [stepper for stepper in steppers if stepper.has_reached_accept_state()]
+    assert accepted
+    assert all(stepper.get_current_value() == "a" * 1000 for stepper in accepted)
@@ -46,6 +46,16 @@ def test_whitespace_acceptor_custom_max_whitespace():
     assert any(stepper.has_reached_accept_state() for stepper in advanced_steppers)
 
 
+def test_whitespace_acceptor_zero_max_whitespace():
+    """Test that a max_whitespace of 0 disallows whitespace entirely."""
+    state_machine = WhitespaceStateMachine(max_whitespace=0)
+    steppers = list(state_machine.get_steppers())
+    assert all(not stepper.get_valid_continuations() for stepper in steppers)
+
+    advanced_steppers = state_machine.advance_all_basic(steppers, " ")
+    assert not advanced_steppers
+
+
 @pytest.mark.parametrize(
     "token, expected_value",
     [
@@ -0,0 +1,53 @@
+from __future__ import annotations
+
+import pytest
+
+from pse.types.number import NumberStateMachine
+from pse.types.xml.xml_element import XMLElementStateMachine
+
+
+def accepted(state_machine: XMLElementStateMachine, text: str) -> list:
+    steppers = state_machine.advance_all_basic(state_machine.get_steppers(), text)
+    return [
+        stepper
+        for stepper in steppers
+        if stepper.has_reached_accept_state() and not stepper.remaining_input
+    ]
+
+
+def test_element_with_numeric_content() -> None:
+    """Test that a numeric element is accepted and its value is the number."""
+    sm = XMLElementStateMachine("answer", NumberStateMachine())
+    steppers = accepted(sm, "<answer>42</answer>")
+    assert steppers
+    assert all(stepper.get_current_value() == 42 for stepper in steppers)
+
+
+@pytest.mark.parametrize(
+    "text",
+    [
+        "<answer>forty-two</answer>",
+        "<answer>42</result>",
+        "<answer>42",
+        "text before <answer>42</answer>",
+        '<answer id="1">42</answer>',
+    ],
+)
+def test_element_rejects_invalid_input(text: str) -> None:
+    """Test that wrong content, mismatched tags, prefixes and undeclared attributes are rejected."""
+    sm = XMLElementStateMachine("answer", NumberStateMachine())
+    assert not accepted(sm, text)
+
+
+@pytest.mark.parametrize(
+    "text",
+    [
+        "<answer>42</answer>",
+        '<answer id="1">42</answer>',
+        '<answer id="1" unit="m">42</answer>',
+    ],
+)
+def test_element_with_attributes(text: str) -> None:
+    """Test that attributes are accepted when allowed."""
+    sm = XMLElementStateMachine("answer", NumberStateMachine(), allow_attributes=True)
+    assert accepted(sm, text)
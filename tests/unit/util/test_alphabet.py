@@ -0,0 +1,30 @@
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+from pse.types.json import json_schema_state_machine
+from pse.util.alphabet import UNBOUNDED_CHARSET, alphabet
+
+
+def test_object_schema_alphabet() -> None:
+    """Test that a small object schema's alphabet covers its structural characters."""
+    _, state_machine = json_schema_state_machine(
+        {
+            "type": "object",
+            "properties": {"count": {"type": "integer"}},
+            "required": ["count"],
+        }
+    )
+    chars = alphabet(state_machine)
+    assert {"{", "}", ":", '"', ","} <= chars
+    assert set("0123456789") <= chars
+    assert set("count") <= chars
+    assert "x" not in chars
+
+
+def test_phrase_alphabet_case_insensitive() -> None:
+    assert alphabet(PhraseStateMachine("ok")) == {"o", "k"}
+    assert alphabet(PhraseStateMachine("ok", is_case_sensitive=False)) == set("okOK")
+
+
+def test_unbounded_character_class_is_capped() -> None:
+    chars = alphabet(CharacterStateMachine(blacklist_charset="ab"))
+    assert chars == set(UNBOUNDED_CHARSET) - {"a", "b"}
@@ -0,0 +1,38 @@
+import pytest
+
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+from pse.util.costs import get_cost, path_cost, set_cost
+
+
+def test_set_cost() -> None:
+    state_machine = ChainStateMachine([PhraseStateMachine("a"), PhraseStateMachine("b")])
+    assert get_cost(state_machine, 0, 0) == 0
+    assert get_cost(set_cost(state_machine, 0, 0, 2.5), 0, 0) == 2.5
+    assert get_cost(state_machine, 1, 0) == 0
+    with pytest.raises(ValueError):
+        set_cost(state_machine, 0, 0, -1)
+    with pytest.raises(ValueError):
+        set_cost(state_machine, 0, 1, 1)
+
+
+def test_cost_is_per_edge_not_per_state_machine() -> None:
+    """Test that a state machine shared by two edges is only charged on one of them."""
+    shared = PhraseStateMachine("a")
+    state_machine = set_cost(ChainStateMachine([shared, shared]), 1, 0, 1.5)
+
+    steppers = state_machine.advance_all_basic(state_machine.get_steppers(), "a")
+    assert min(path_cost(stepper) for stepper in steppers) == 0
+    steppers = state_machine.advance_all_basic(steppers, "a")
+    assert [path_cost(stepper) for stepper in steppers] == [1.5]
+
+
+def test_path_cost_memo_keeps_sub_steppers() -> None:
+    """Test that memoized sub-steppers are held, so their ids cannot be reused."""
+    state_machine = set_cost(
+        ChainStateMachine([PhraseStateMachine("a"), PhraseStateMachine("b")]), 0, 0, 1.5
+    )
+    steppers = state_machine.advance_all_basic(state_machine.get_steppers(), "ab")
+    memo: dict = {}
+    assert path_cost(steppers[0], memo) == 1.5
+    assert all(id(stepper) == key for key, (stepper, _) in memo.items())
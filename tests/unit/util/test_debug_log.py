@@ -0,0 +1,45 @@
+import pytest
+
+from pse.util.debug_log import (
+    DEFAULT_MAX_LOGGED_STEPPERS,
+    MAX_LOGGED_STEPPERS_ENV,
+    get_max_logged_steppers,
+    summarize_steppers,
+)
+
+
+class FakeStepper:
+    def __init__(self, index: int) -> None:
+        self.index = index
+
+    def __repr__(self) -> str:
+        return f"Stepper({self.index})"
+
+
+def test_summary_is_bounded_for_100_steppers() -> None:
+    steppers = [FakeStepper(index) for index in range(100)]
+    summary = summarize_steppers(steppers, limit=5)
+    lines = summary.splitlines()
+    assert len(lines) == 7
+    assert lines[0] == "100 steppers (100 distinct)"
+    assert lines[1] == "  Stepper(0)"
+    assert lines[-1] == "  ... and 95 more distinct"
+
+
+def test_identical_steppers_are_counted_once() -> None:
+    steppers = [FakeStepper(index % 2) for index in range(100)]
+    summary = summarize_steppers(steppers, limit=5)
+    assert summary.splitlines() == [
+        "100 steppers (2 distinct)",
+        "  Stepper(0) (x50)",
+        "  Stepper(1) (x50)",
+    ]
+
+
+def test_limit_from_environment(monkeypatch: pytest.MonkeyPatch) -> None:
+    steppers = [FakeStepper(index) for index in range(100)]
+    monkeypatch.setenv(MAX_LOGGED_STEPPERS_ENV, "3")
+    assert len(summarize_steppers(steppers).splitlines()) == 5
+
+    monkeypatch.setenv(MAX_LOGGED_STEPPERS_ENV, "many")
+    assert get_max_logged_steppers() == DEFAULT_MAX_LOGGED_STEPPERS
@@ -0,0 +1,31 @@
+import pytest
+from helpers import accepts
+from pse_core.state_machine import StateMachine
+
+from pse.types.base.phrase import PhraseStateMachine
+from pse.util.fork import fork_to
+
+
+def test_forks_advance_independently() -> None:
+    state_machine = StateMachine(
+        {
+            0: [(PhraseStateMachine("a"), 1), (PhraseStateMachine("b"), 2)],
+            1: [(PhraseStateMachine("x"), "$")],
+            2: [(PhraseStateMachine("y"), "$")],
+        }
+    )
+    stepper = state_machine.get_new_stepper(0)
+    to_a, to_b = fork_to(stepper, 1), fork_to(stepper, 2)
+
+    assert accepts(state_machine, "ax", [to_a])
+    assert not accepts(state_machine, "by", [to_a])
+    assert accepts(state_machine, "by", [to_b])
+    assert not accepts(state_machine, "ax", [to_b])
+    # the original is still free to take either edge
+    assert stepper.sub_stepper is None
+
+
+def test_fork_to_unreachable_state() -> None:
+    state_machine = StateMachine({0: [(PhraseStateMachine("a"), "$")]})
+    with pytest.raises(ValueError):
+        fork_to(state_machine.get_new_stepper(0), 5)
@@ -0,0 +1,37 @@
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+from pse.util.grammar_stats import compile_stats, grammar_stats
+
+
+def test_nested_schema_stats() -> None:
+    """Test that nesting an object adds to the counts and the depth of the inner one."""
+    inner = {
+        "type": "object",
+        "properties": {"flag": {"type": "boolean"}},
+        "required": ["flag"],
+    }
+    flat = compile_stats(inner)
+    nested = compile_stats(
+        {"type": "object", "properties": {"inner": inner}, "required": ["inner"]}
+    )
+    assert nested.state_count > flat.state_count
+    assert nested.edge_count > flat.edge_count
+    assert (flat.max_depth, nested.max_depth) == (1, 2)
+    assert not flat.has_unbounded_text
+    assert not nested.has_unbounded_text
+
+
+def test_shared_state_machines_counted_once() -> None:
+    """Test that a state machine on several edges adds its states once."""
+    phrase = PhraseStateMachine("a")
+    stats = grammar_stats(ChainStateMachine([phrase, phrase]))
+    # the chain's three states and two edges, plus the phrase's start and end
+    assert stats.state_count == 5
+    assert stats.edge_count == 2
+    assert stats.max_depth == 0
+
+
+def test_unbounded_text() -> None:
+    assert compile_stats({"type": "string"}).has_unbounded_text
+    assert not compile_stats({"type": "string", "maxLength": 8}).has_unbounded_text
+    assert compile_stats({"type": "object"}).has_unbounded_text
@@ -0,0 +1,52 @@
+from pse.util.json_events import JsonEvent, JsonEventParser, JsonEventType
+
+
+def test_nested_object_event_sequence() -> None:
+    """Test that events for a small nested object arrive in order across chunk boundaries."""
+    events: list[JsonEvent] = []
+    parser = JsonEventParser(events.append)
+    text = '{"a": 1, "b": {"c": [true, "x\\"y"]}}'
+    for i in range(0, len(text), 3):
+        parser.feed(text[i : i + 3])
+    parser.finish()
+
+    assert events == [
+        JsonEvent(JsonEventType.START_OBJECT),
+        JsonEvent(JsonEventType.KEY, "a"),
+        JsonEvent(JsonEventType.VALUE, 1),
+        JsonEvent(JsonEventType.KEY, "b"),
+        JsonEvent(JsonEventType.START_OBJECT),
+        JsonEvent(JsonEventType.KEY, "c"),
+        JsonEvent(JsonEventType.START_ARRAY),
+        JsonEvent(JsonEventType.VALUE, True),
+        JsonEvent(JsonEventType.VALUE, 'x"y'),
+        JsonEvent(JsonEventType.END_ARRAY),
+        JsonEvent(JsonEventType.END_OBJECT),
+        JsonEvent(JsonEventType.END_OBJECT),
+    ]
+
+
+def test_trailing_scalar_needs_finish() -> None:
+    events: list[JsonEvent] = []
+    parser = JsonEventParser(events.append)
+    parser.feed("12")
+    assert events == []
+    parser.finish()
+    assert events == [JsonEvent(JsonEventType.VALUE, 12)]
+
+
+def test_unmatched_close_brackets_are_ignored() -> None:
+    """Test that close brackets in free text around the JSON neither raise nor emit."""
+    events: list[JsonEvent] = []
+    parser = JsonEventParser(events.append)
+    parser.feed('see ] and } here: {"a": [1]}')
+    parser.finish()
+
+    assert events == [
+        JsonEvent(JsonEventType.START_OBJECT),
+        JsonEvent(JsonEventType.KEY, "a"),
+        JsonEvent(JsonEventType.START_ARRAY),
+        JsonEvent(JsonEventType.VALUE, 1),
+        JsonEvent(JsonEventType.END_ARRAY),
+        JsonEvent(JsonEventType.END_OBJECT),
+    ]
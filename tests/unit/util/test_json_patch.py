@@ -0,0 +1,27 @@
+from pse.util.json_patch import JsonPatchOp, diff_json
+
+
+def test_diff_json_objects():
+    ops = diff_json({"a": 1, "b": 2}, {"a": 1, "b": 3, "c/d": 4})
+    assert ops == [
+        JsonPatchOp("replace", "/b", 3),
+        JsonPatchOp("add", "/c~1d", 4),
+    ]
+
+
+def test_diff_json_arrays():
+    assert diff_json([1, 2], [1, 2, 3]) == [JsonPatchOp("add", "/2", 3)]
+    assert diff_json([1, 2, 3], [1]) == [
+        JsonPatchOp("remove", "/2"),
+        JsonPatchOp("remove", "/1"),
+    ]
+
+
+def test_diff_json_scalars():
+    assert diff_json(None, {"a": 1}) == [JsonPatchOp("replace", "", {"a": 1})]
+    assert diff_json(1, 1) == []
+    assert diff_json(1, True) == [JsonPatchOp("replace", "", True)]
+
+
+def test_remove_op_has_no_value():
+    assert JsonPatchOp("remove", "/a").to_dict() == {"op": "remove", "path": "/a"}
@@ -0,0 +1,32 @@
+import threading
+
+from pse.util.metrics import EngineMetrics
+
+
+def test_record_step_is_thread_safe():
+    metrics = EngineMetrics()
+
+    def worker():
+        for _ in range(1000):
+            metrics.record_step(1, 2)
+
+    threads = [threading.Thread(target=worker) for _ in range(8)]
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join()
+
+    snapshot = metrics.snapshot()
+    assert snapshot["pse_tokens_processed_total"] == 8000
+    assert snapshot["pse_steppers_per_step_avg"] == 2
+
+
+def test_record_generation():
+    metrics = EngineMetrics()
+    metrics.record_generation(completed=True)
+    metrics.record_generation(completed=False)
+    metrics.record_generation(completed=False)
+    snapshot = metrics.snapshot()
+    assert snapshot["pse_generations_completed_total"] == 1
+    assert snapshot["pse_generations_stuck_total"] == 2
+    assert snapshot["pse_tokens_healed_total"] == 0
@@ -0,0 +1,15 @@
+from pse.types.base.phrase import PhraseStateMachine
+from pse.types.json import json_schema_state_machine
+from pse.util.names import describe, get_name, set_name
+
+
+def test_set_name_is_shown_with_type() -> None:
+    state_machine = set_name(PhraseStateMachine("hi"), "greeting")
+    assert get_name(state_machine) == "greeting"
+    assert describe(state_machine) == f"{state_machine}[greeting]"
+    assert describe(PhraseStateMachine("hi")) == str(PhraseStateMachine("hi"))
+
+
+def test_schema_title_names_state_machine() -> None:
+    _, state_machine = json_schema_state_machine({"type": "integer", "title": "Age"})
+    assert get_name(state_machine) == "Age"
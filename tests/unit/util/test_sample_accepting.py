@@ -0,0 +1,29 @@
+from helpers import accepts
+
+from pse.types.enum import EnumStateMachine
+from pse.types.json import json_schema_state_machine
+from pse.util.sample_accepting import sample_accepting
+
+
+def test_breadth_first_enumerates_shortest_first() -> None:
+    state_machine = EnumStateMachine(["bb", "a", "ccc"], require_quotes=False)
+    assert sample_accepting(state_machine, max_len=10, limit=10) == ["a", "bb", "ccc"]
+    assert sample_accepting(state_machine, max_len=2, limit=10) == ["a", "bb"]
+
+
+def test_same_seed_yields_same_samples() -> None:
+    """Test that seeded random walks are reproducible and accepted."""
+    _, state_machine = json_schema_state_machine(
+        {
+            "type": "object",
+            "properties": {"a": {"type": "integer"}, "b": {"type": "boolean"}},
+            "required": ["a"],
+        }
+    )
+    samples = sample_accepting(state_machine, max_len=40, limit=5, seed=7)
+    assert samples
+    assert samples == sample_accepting(state_machine, max_len=40, limit=5, seed=7)
+    assert len(set(samples)) == len(samples)
+    for sample in samples:
+        assert len(sample) <= 40
+        assert accepts(state_machine, sample)
@@ -0,0 +1,82 @@
+import re
+
+import pytest
+from pse_core.state_machine import StateMachine
+
+from pse.types.base.any import AnyStateMachine
+from pse.types.base.chain import ChainStateMachine
+from pse.types.base.character import CharacterStateMachine
+from pse.types.base.loop import LoopStateMachine
+from pse.types.base.phrase import PhraseStateMachine
+from pse.types.json.json_number import NumberSchemaStateMachine
+from pse.util.to_regex import to_regex
+
+
+def test_choice_of_literals() -> None:
+    state_machine = AnyStateMachine(
+        [PhraseStateMachine("cat"), PhraseStateMachine("dog"), PhraseStateMachine("a.b")]
+    )
+    pattern = to_regex(state_machine)
+    assert pattern is not None
+    for value in ["cat", "dog", "a.b"]:
+        assert re.fullmatch(pattern, value)
+    for value in ["", "ca", "cats", "catdog", "axb"]:
+        assert not re.fullmatch(pattern, value)
+
+
+@pytest.mark.parametrize(
+    "value, should_match",
+    [
+        ("a01", True),
+        ("a110.", True),
+        ("a0", False),
+        ("a0101", False),
+        ("b01", False),
+    ],
+)
+def test_chain_with_optional_and_character_set(value: str, should_match: bool) -> None:
+    state_machine = ChainStateMachine(
+        [
+            PhraseStateMachine("a"),
+            CharacterStateMachine("01", char_min=2, char_limit=3),
+            PhraseStateMachine(".", is_optional=True),
+        ]
+    )
+    pattern = to_regex(state_machine)
+    assert pattern is not None
+    assert bool(re.fullmatch(pattern, value)) == should_match
+
+
+def test_state_graph_with_cycle() -> None:
+    state_machine = StateMachine(
+        {
+            0: [(PhraseStateMachine("a"), 1)],
+            1: [(PhraseStateMachine("b"), 0), (PhraseStateMachine("c"), "$")],
+        }
+    )
+    pattern = to_regex(state_machine)
+    assert pattern is not None
+    assert re.fullmatch(pattern, "ac")
+    assert re.fullmatch(pattern, "ababac")
+    assert not re.fullmatch(pattern, "abc")
+
+
+def test_separated_loop() -> None:
+    state_machine = LoopStateMachine(
+        PhraseStateMachine("ab"),
+        min_loop_count=2,
+        max_loop_count=3,
+        separator_state_machine=PhraseStateMachine(","),
+    )
+    pattern = to_regex(state_machine)
+    assert pattern is not None
+    assert re.fullmatch(pattern, "ab,ab")
+    assert re.fullmatch(pattern, "ab,ab,ab")
+    assert not re.fullmatch(pattern, "ab")
+    assert not re.fullmatch(pattern, "ab,ab,ab,ab")
+
+
+def test_semantic_validators_cannot_be_expressed() -> None:
+    number = NumberSchemaStateMachine({"type": "integer", "maximum": 10})
+    assert to_regex(number) is None
+    assert to_regex(ChainStateMachine([PhraseStateMachine("x"), number])) is None
@@ -0,0 +1,26 @@
+from pse.util.vocabulary import build_vocabulary, get_decode
+
+
+class ForwardOnlyTokenizer:
+    def __init__(self, vocab: dict[str, int]) -> None:
+        self.vocab = vocab
+
+    def get_vocab(self) -> dict[str, int]:
+        return self.vocab
+
+
+def test_build_vocabulary_from_forward_map() -> None:
+    vocab = {"{": 0, '"': 1, "key": 2, "}": 3}
+    assert build_vocabulary(vocab) == {0: "{", 1: '"', 2: "key", 3: "}"}
+
+
+def test_build_vocabulary_with_decode() -> None:
+    vocab = {"▁a": 0, "b": 1}
+    vocabulary = build_vocabulary(vocab, lambda ids: "".join("ab"[i] for i in ids))
+    assert vocabulary == {0: "a", 1: "b"}
+
+
+def test_get_decode_without_tokenizer_decode() -> None:
+    decode = get_decode(ForwardOnlyTokenizer({"{": 0, "key": 1, "}": 2}))
+    assert decode([0, 1, 2]) == "{key}"
+    assert decode([7]) == ""